@@ -0,0 +1,401 @@
+use std::{collections::HashSet, fmt, str::FromStr};
+
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, SubscribeUpdate, TokenBalance,
+};
+
+use crate::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use crate::geyser::{detect_pool_inits, owner_token_balance, recorder::RecordedUpdate};
+
+/// Тип события пула, восстановленный по изменению резервов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolEventKind {
+    Init,
+    Swap,
+    AddLiquidity,
+    RemoveLiquidity,
+}
+
+/// Резервы пула после события
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolEvent {
+    pub slot: u64,
+    pub kind: PoolEventKind,
+    pub reserve_sol: u64,
+    pub reserve_token: u64,
+}
+
+/// История резервов одного пула WSOL/токен
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolHistory {
+    pub pool: Pubkey,
+    pub target_mint: Pubkey,
+    pub init_slot: u64,
+    pub events: Vec<PoolEvent>,
+}
+
+impl PoolHistory {
+    /// Резервы на момент слота `slot` (после последнего события не позже него)
+    fn reserves_at(&self, slot: u64) -> Option<(u64, u64)> {
+        self.events
+            .iter()
+            .take_while(|e| e.slot <= slot)
+            .last()
+            .map(|e| (e.reserve_sol, e.reserve_token))
+    }
+}
+
+/// Количество на выходе constant-product свапа с комиссией `fee_bps`
+pub fn constant_product_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u64,
+) -> u64 {
+    if reserve_in == 0 || reserve_out == 0 {
+        return 0;
+    }
+    let in_after_fee = amount_in as u128 * (10_000 - fee_bps.min(10_000)) as u128 / 10_000;
+    let out = reserve_out as u128 * in_after_fee / (reserve_in as u128 + in_after_fee);
+    out as u64
+}
+
+/// Отслеживаемый пул: по владельцу его хранилищ находим резервы в token balances
+struct TrackedPool {
+    authority: String,
+    target_mint: String,
+    history: PoolHistory,
+}
+
+/// Восстанавливает историю резервов пулов WSOL из записи потока Geyser
+///
+/// Пул обнаруживается по init-инструкции, дальше каждая транзакция, меняющая балансы
+/// токен-аккаунтов его authority, даёт новую точку истории.
+pub fn extract_pool_histories<I>(recorded: I) -> Result<Vec<PoolHistory>>
+where
+    I: IntoIterator<Item = Result<RecordedUpdate>>,
+{
+    let meteora_program = Pubkey::from_str(METEORA_PROGRAM_ID)?;
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let wsol = WSOL_MINT.to_string();
+
+    let mut pools: Vec<TrackedPool> = vec![];
+    // DAMM v2 и DBC держат хранилища всех пулов под одним authority, поэтому пулы различаются по адресу
+    let mut known_pools: HashSet<Pubkey> = HashSet::new();
+
+    for rec in recorded {
+        let update = rec?.update;
+
+        for init in detect_pool_inits(&update, &meteora_program) {
+            let target_mint = if init.token_a == wsol_mint {
+                init.token_b
+            } else if init.token_b == wsol_mint {
+                init.token_a
+            } else {
+                continue;
+            };
+
+            if !known_pools.insert(init.accounts.pool) {
+                continue;
+            }
+            let authority = init.accounts.pool_authority.to_string();
+            pools.push(TrackedPool {
+                authority,
+                target_mint: target_mint.to_string(),
+                history: PoolHistory {
+                    pool: init.accounts.pool,
+                    target_mint,
+                    init_slot: init.slot,
+                    events: vec![],
+                },
+            });
+        }
+
+        let Some((slot, post_balances)) = post_token_balances(&update) else {
+            continue;
+        };
+
+        for tracked in pools.iter_mut() {
+            let reserve = |mint: &str| owner_token_balance(post_balances, &tracked.authority, mint);
+
+            let (Some(reserve_sol), Some(reserve_token)) =
+                (reserve(&wsol), reserve(&tracked.target_mint))
+            else {
+                continue;
+            };
+
+            let kind = match tracked.history.events.last() {
+                None => PoolEventKind::Init,
+                Some(prev) => classify(prev, reserve_sol, reserve_token),
+            };
+            tracked.history.events.push(PoolEvent {
+                slot,
+                kind,
+                reserve_sol,
+                reserve_token,
+            });
+        }
+    }
+
+    Ok(pools.into_iter().map(|p| p.history).collect())
+}
+
+fn post_token_balances(update: &SubscribeUpdate) -> Option<(u64, &[TokenBalance])> {
+    let Some(UpdateOneof::Transaction(tx)) = &update.update_oneof else {
+        return None;
+    };
+    let meta = tx.transaction.as_ref()?.meta.as_ref()?;
+    if meta.err.is_some() {
+        return None;
+    }
+    Some((tx.slot, &meta.post_token_balances))
+}
+
+fn classify(prev: &PoolEvent, reserve_sol: u64, reserve_token: u64) -> PoolEventKind {
+    let sol_up = reserve_sol > prev.reserve_sol;
+    let token_up = reserve_token > prev.reserve_token;
+    let sol_down = reserve_sol < prev.reserve_sol;
+    let token_down = reserve_token < prev.reserve_token;
+
+    if sol_up && token_up {
+        PoolEventKind::AddLiquidity
+    } else if sol_down && token_down {
+        PoolEventKind::RemoveLiquidity
+    } else {
+        PoolEventKind::Swap
+    }
+}
+
+/// Параметры стратегии для бэктеста
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyParams {
+    /// Размер покупки в лампортах
+    pub buy_lamports: u64,
+    /// Комиссия пула в базисных пунктах
+    pub fee_bps: u64,
+    /// Через сколько слотов после решения транзакция попадает в блок
+    pub landing_delay_slots: u64,
+    /// Выход при доходности не ниже этой доли (0.5 = +50%)
+    pub take_profit: f64,
+    /// Выход при убытке не меньше этой доли (0.3 = −30%)
+    pub stop_loss: f64,
+    /// Максимальное время удержания позиции в слотах
+    pub max_hold_slots: u64,
+    /// Стоимость одной транзакции (комиссия сети и чаевые) в лампортах
+    pub tx_cost_lamports: u64,
+}
+
+/// Причина выхода из позиции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    MaxHold,
+    EndOfData,
+}
+
+/// Результат одной сделки
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeReport {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub entry_slot: u64,
+    pub exit_slot: u64,
+    pub sol_in: u64,
+    pub tokens: u64,
+    pub sol_out: u64,
+    pub pnl_lamports: i64,
+    pub exit_reason: ExitReason,
+}
+
+impl TradeReport {
+    /// Доходность сделки относительно вложенного SOL
+    pub fn return_pct(&self) -> f64 {
+        if self.sol_in == 0 {
+            return 0.0;
+        }
+        self.pnl_lamports as f64 / self.sol_in as f64 * 100.0
+    }
+}
+
+/// Итоговый отчёт бэктеста
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BacktestReport {
+    pub trades: Vec<TradeReport>,
+    /// Пулы, в которые не удалось войти (нет резервов на момент входа)
+    pub skipped: usize,
+    pub total_pnl_lamports: i64,
+    pub wins: usize,
+    pub losses: usize,
+    /// Максимальная просадка кумулятивного PnL в лампортах
+    pub max_drawdown_lamports: i64,
+}
+
+impl BacktestReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.trades.is_empty() {
+            return 0.0;
+        }
+        self.wins as f64 / self.trades.len() as f64 * 100.0
+    }
+}
+
+impl fmt::Display for BacktestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for t in &self.trades {
+            writeln!(
+                f,
+                "{} slots {}→{} in={} out={} pnl={} ({:+.2}%) {:?}",
+                t.pool,
+                t.entry_slot,
+                t.exit_slot,
+                t.sol_in,
+                t.sol_out,
+                t.pnl_lamports,
+                t.return_pct(),
+                t.exit_reason
+            )?;
+        }
+        write!(
+            f,
+            "Сделок: {}, пропущено: {}, прибыльных: {} ({:.1}%), PnL: {} лампортов, макс. просадка: {}",
+            self.trades.len(),
+            self.skipped,
+            self.wins,
+            self.win_rate(),
+            self.total_pnl_lamports,
+            self.max_drawdown_lamports
+        )
+    }
+}
+
+/// Симулирует одну сделку в пуле
+///
+/// Вход — через `landing_delay_slots` после инициализации по резервам на тот момент.
+/// Наша покупка сдвигает резервы, и этот сдвиг учитывается во всех последующих точках.
+/// Выход срабатывает по take-profit/stop-loss/max-hold и тоже исполняется с задержкой.
+pub fn simulate_trade(history: &PoolHistory, params: &StrategyParams) -> Option<TradeReport> {
+    let entry_slot = history.init_slot + params.landing_delay_slots;
+    let (entry_sol, entry_token) = history.reserves_at(entry_slot)?;
+
+    let tokens = constant_product_out(params.buy_lamports, entry_sol, entry_token, params.fee_bps);
+    if tokens == 0 {
+        return None;
+    }
+
+    // Резервы рынка с учётом нашей сделки. Наш вклад сдвигает резервы на
+    // (+buy_lamports, −tokens); при добавлении/изъятии ликвидности он масштабируется
+    // вместе с пулом, как доля в резервах
+    let mut offset_sol = params.buy_lamports as f64;
+    let mut offset_token = -(tokens as f64);
+    let mut prev_sol = entry_sol;
+    let mut effective = vec![];
+    for event in history.events.iter().filter(|e| e.slot > entry_slot) {
+        if matches!(
+            event.kind,
+            PoolEventKind::AddLiquidity | PoolEventKind::RemoveLiquidity
+        ) && prev_sol > 0
+        {
+            let ratio = event.reserve_sol as f64 / prev_sol as f64;
+            offset_sol *= ratio;
+            offset_token *= ratio;
+        }
+        prev_sol = event.reserve_sol;
+
+        let sol = (event.reserve_sol as f64 + offset_sol).max(1.0) as u64;
+        let token = (event.reserve_token as f64 + offset_token).max(1.0) as u64;
+        effective.push((event.slot, sol, token));
+    }
+
+    let sell_value =
+        |sol: u64, token: u64| constant_product_out(tokens, token, sol, params.fee_bps);
+    let value_at = |slot: u64| {
+        effective
+            .iter()
+            .take_while(|(s, _, _)| *s <= slot)
+            .last()
+            .map(|&(_, sol, token)| sell_value(sol, token))
+            .unwrap_or_else(|| {
+                sell_value(
+                    entry_sol.saturating_add(params.buy_lamports),
+                    entry_token.saturating_sub(tokens),
+                )
+            })
+    };
+
+    let take_profit = params.buy_lamports as f64 * (1.0 + params.take_profit);
+    let stop_loss = params.buy_lamports as f64 * (1.0 - params.stop_loss);
+
+    let mut trigger = None;
+    for &(slot, sol, token) in &effective {
+        let value = sell_value(sol, token) as f64;
+        let reason = if value >= take_profit {
+            Some(ExitReason::TakeProfit)
+        } else if value <= stop_loss {
+            Some(ExitReason::StopLoss)
+        } else if slot >= entry_slot + params.max_hold_slots {
+            Some(ExitReason::MaxHold)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            trigger = Some((slot, reason));
+            break;
+        }
+    }
+
+    let (exit_slot, exit_reason) = match trigger {
+        Some((slot, reason)) => (slot + params.landing_delay_slots, reason),
+        None => (
+            effective.last().map_or(entry_slot, |&(slot, _, _)| slot),
+            ExitReason::EndOfData,
+        ),
+    };
+
+    let sol_out = value_at(exit_slot);
+    let pnl_lamports =
+        sol_out as i64 - params.buy_lamports as i64 - 2 * params.tx_cost_lamports as i64;
+
+    Some(TradeReport {
+        pool: history.pool,
+        mint: history.target_mint,
+        entry_slot,
+        exit_slot,
+        sol_in: params.buy_lamports,
+        tokens,
+        sol_out,
+        pnl_lamports,
+        exit_reason,
+    })
+}
+
+/// Прогоняет стратегию по всем пулам и собирает отчёт
+pub fn run_backtest(histories: &[PoolHistory], params: &StrategyParams) -> BacktestReport {
+    let mut report = BacktestReport::default();
+    let mut cumulative = 0i64;
+    let mut peak = 0i64;
+
+    for history in histories {
+        let Some(trade) = simulate_trade(history, params) else {
+            report.skipped += 1;
+            continue;
+        };
+
+        if trade.pnl_lamports > 0 {
+            report.wins += 1;
+        } else {
+            report.losses += 1;
+        }
+
+        cumulative += trade.pnl_lamports;
+        peak = peak.max(cumulative);
+        report.max_drawdown_lamports = report.max_drawdown_lamports.max(peak - cumulative);
+        report.trades.push(trade);
+    }
+
+    report.total_pnl_lamports = cumulative;
+    report
+}
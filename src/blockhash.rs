@@ -0,0 +1,187 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::{clock::MAX_PROCESSING_AGE, commitment_config::CommitmentConfig, hash::Hash};
+use tokio::task::JoinHandle;
+use yellowstone_grpc_proto::prelude::{subscribe_update::UpdateOneof, SubscribeUpdate};
+
+use crate::rpc::RpcPool;
+
+/// Откуда получен blockhash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockhashSource {
+    Geyser,
+    Rpc,
+}
+
+/// Последний известный blockhash вместе с границей его действия
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+    pub source: BlockhashSource,
+    pub updated_at: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    latest: Option<CachedBlockhash>,
+    /// Наибольшая увиденная высота блока — по ней определяется истечение blockhash
+    block_height: u64,
+}
+
+/// Кэш blockhash, который обновляется в фоне и читается синхронно при сборке транзакции.
+///
+/// Основной источник — block-meta из подписки Geyser ([`BlockhashCache::observe`]),
+/// запасной — периодический опрос RPC ([`spawn_rpc_refresher`]). Более старый blockhash
+/// (с меньшей `last_valid_block_height`) никогда не заменяет более новый.
+pub struct BlockhashCache {
+    state: RwLock<State>,
+    max_age: Duration,
+}
+
+impl BlockhashCache {
+    /// Создаёт пустой кэш
+    ///
+    /// # Аргументы
+    /// * `max_age` — сколько blockhash считается свежим без обновлений
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            state: RwLock::new(State::default()),
+            max_age,
+        }
+    }
+
+    /// Записывает blockhash, если он не старее уже известного
+    ///
+    /// # Возвращает
+    /// `true`, если значение в кэше обновилось
+    pub fn update(
+        &self,
+        blockhash: Hash,
+        last_valid_block_height: u64,
+        source: BlockhashSource,
+    ) -> bool {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(current) = &state.latest {
+            if last_valid_block_height < current.last_valid_block_height {
+                return false;
+            }
+        }
+
+        state.latest = Some(CachedBlockhash {
+            blockhash,
+            last_valid_block_height,
+            source,
+            updated_at: Instant::now(),
+        });
+        true
+    }
+
+    /// Запоминает текущую высоту блока
+    pub fn observe_block_height(&self, block_height: u64) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.block_height = state.block_height.max(block_height);
+    }
+
+    /// Обновляет кэш из block-meta обновления Geyser; остальные обновления игнорируются
+    pub fn observe(&self, update: &SubscribeUpdate) -> bool {
+        let Some(UpdateOneof::BlockMeta(meta)) = &update.update_oneof else {
+            return false;
+        };
+        let (Ok(blockhash), Some(height)) = (Hash::from_str(&meta.blockhash), &meta.block_height)
+        else {
+            return false;
+        };
+
+        self.observe_block_height(height.block_height);
+        self.update(
+            blockhash,
+            height.block_height + MAX_PROCESSING_AGE as u64,
+            BlockhashSource::Geyser,
+        )
+    }
+
+    /// Последнее значение из кэша, даже устаревшее
+    pub fn latest(&self) -> Option<CachedBlockhash> {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).latest
+    }
+
+    /// Возвращает blockhash для новой транзакции
+    ///
+    /// # Возвращает
+    /// Ошибку, если кэш пуст, давно не обновлялся или сеть уже ушла дальше `last_valid_block_height`
+    pub fn get(&self) -> Result<Hash> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let cached = state
+            .latest
+            .ok_or_else(|| anyhow!("Blockhash ещё не получен"))?;
+
+        let age = cached.updated_at.elapsed();
+        if age > self.max_age {
+            return Err(anyhow!(
+                "Blockhash {} устарел: обновлялся {:?} назад",
+                cached.blockhash,
+                age
+            ));
+        }
+        if state.block_height > cached.last_valid_block_height {
+            return Err(anyhow!(
+                "Blockhash {} истёк: высота {} > {}",
+                cached.blockhash,
+                state.block_height,
+                cached.last_valid_block_height
+            ));
+        }
+        Ok(cached.blockhash)
+    }
+
+    /// Проверяет, можно ли сейчас взять blockhash из кэша
+    pub fn is_stale(&self) -> bool {
+        self.get().is_err()
+    }
+}
+
+/// Опрашивает RPC, если Geyser не обновлял кэш дольше `interval`
+///
+/// # Аргументы
+/// * `cache` — кэш, который нужно поддерживать свежим
+/// * `rpc` — пул RPC-узлов; опрашивается с переключением между узлами
+/// * `interval` — период опроса
+pub fn spawn_rpc_refresher(
+    cache: Arc<BlockhashCache>,
+    rpc: Arc<RpcPool>,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            let fresh = cache
+                .latest()
+                .is_some_and(|c| c.updated_at.elapsed() < interval);
+            if fresh {
+                continue;
+            }
+
+            let latest = rpc
+                .read(|client| async move {
+                    client
+                        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+                        .await
+                })
+                .await;
+            match latest {
+                Ok((blockhash, last_valid_block_height)) => {
+                    cache.update(blockhash, last_valid_block_height, BlockhashSource::Rpc);
+                }
+                Err(e) => eprintln!("Ошибка обновления blockhash через RPC: {:#}", e),
+            }
+        }
+    })
+}
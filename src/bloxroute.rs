@@ -1,44 +1,120 @@
-use anyhow::{anyhow, Result};
-use reqwest::Client;
-use serde_json::{json, Value};
+use futures_util::future::BoxFuture;
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    Client,
+};
+use serde_json::json;
 
-use crate::config::{BLOXROUTE_URL, BLOXROUTE_API_KEY};
+use crate::config::{BLOXROUTE_API_KEY, BLOXROUTE_URL};
+use crate::relay::{
+    parse_relay_response, shared_http_client, warm_up_connection, Relay, RelayAck, RelayError,
+};
+use crate::secrets::Secret;
 
-/// Отправляет base64-сериализованную транзакцию в Bloxroute для Solana
-///
-/// # Аргументы:
-/// - `tx_base64`: сериализованная транзакция в формате base64
-/// - `tip`: чаевые в микролампортах (u64), передающиеся в теле запроса и внутри транзакции
-///
-/// # Возвращает:
-/// - `Ok(())` при успешной отправке и отсутствии ошибок
-/// - `Err` при сетевых ошибках или если Bloxroute вернул `error` в ответе
-pub async fn send_to_bloxroute(tx_base64: &str, tip: u64) -> Result<()> {
-    let client = Client::new();
+const RELAY_NAME: &str = "Bloxroute";
+
+/// Клиент Bloxroute с настраиваемым адресом и ключом
+#[derive(Debug, Clone)]
+pub struct BloxrouteRelay {
+    url: String,
+    api_key: Secret,
+    client: Client,
+}
+
+impl BloxrouteRelay {
+    /// Ретранслятор на общем HTTP-клиенте процесса
+    pub fn new(url: impl Into<String>, api_key: impl Into<Secret>) -> Self {
+        Self::with_client(shared_http_client(), url, api_key)
+    }
+
+    /// Ретранслятор на переданном HTTP-клиенте (например, из [`crate::relay::RelayRegistry`])
+    pub fn with_client(
+        client: Client,
+        url: impl Into<String>,
+        api_key: impl Into<Secret>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            api_key: api_key.into(),
+            client,
+        }
+    }
+
+    /// Адрес и ключ из config.rs
+    pub fn from_config() -> anyhow::Result<Self> {
+        Self::from_config_with_client(shared_http_client())
+    }
 
-    let body = json!({
-        "transaction": tx_base64,
-        "channel": "solana-mainnet",
-        "mev": true,
-        "max_block_delay": 2,
-        "tip": tip.to_string()
-    });
+    /// Адрес и ключ из config.rs на переданном HTTP-клиенте
+    pub fn from_config_with_client(client: Client) -> anyhow::Result<Self> {
+        Ok(Self::with_client(client, BLOXROUTE_URL, BLOXROUTE_API_KEY.load()?))
+    }
 
-    let res = client
-        .post(BLOXROUTE_URL)
-        .header("Authorization", format!("Bearer {}", BLOXROUTE_API_KEY))
-        .json(&body)
-        .send()
-        .await?;
+    /// Отправляет base64-сериализованную транзакцию в Bloxroute для Solana
+    ///
+    /// # Аргументы:
+    /// - `tx_base64`: сериализованная транзакция в формате base64
+    /// - `tip`: чаевые в микролампортах (u64), передающиеся в теле запроса и внутри транзакции
+    ///
+    /// # Возвращает:
+    /// - `Ok(RelayAck)` с подписью транзакции, если Bloxroute её принял
+    /// - `Err(RelayError)` — отказ в доступе, лимит, отклонённая транзакция или сбой соединения
+    pub async fn send(&self, tx_base64: &str, tip: u64) -> Result<RelayAck, RelayError> {
+        let body = json!({
+            "transaction": tx_base64,
+            "channel": "solana-mainnet",
+            "mev": true,
+            "max_block_delay": 2,
+            "tip": tip.to_string()
+        });
 
-    let text = res.text().await?;
-    println!("Bloxroute response: {}", text);
+        let res = self
+            .client
+            .post(&self.url)
+            .header(AUTHORIZATION, self.authorization()?)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| RelayError::transport(RELAY_NAME, e))?;
 
-    let json: Value = serde_json::from_str(&text)?;
+        // Ретранслятор может повторить ключ в тексте ошибки
+        parse_relay_response(RELAY_NAME, res)
+            .await
+            .map_err(|e| e.scrub(&self.api_key))
+    }
 
-    if json.get("error").is_some() {
-        return Err(anyhow!("Bloxroute returned error: {}", json));
+    fn authorization(&self) -> Result<HeaderValue, RelayError> {
+        self.api_key
+            .bearer_header()
+            .map_err(|e| RelayError::Auth {
+                relay: RELAY_NAME.to_string(),
+                message: e.to_string(),
+            })
     }
+}
 
+impl Relay for BloxrouteRelay {
+    fn name(&self) -> &str {
+        RELAY_NAME
+    }
+
+    fn send<'a>(
+        &'a self,
+        tx_base64: &'a str,
+        tip: u64,
+    ) -> BoxFuture<'a, Result<RelayAck, RelayError>> {
+        Box::pin(BloxrouteRelay::send(self, tx_base64, tip))
+    }
+
+    fn warm_up(&self) -> BoxFuture<'_, anyhow::Result<()>> {
+        Box::pin(warm_up_connection(&self.client, &self.url))
+    }
+}
+
+/// Отправляет транзакцию в Bloxroute по адресу и ключу из config.rs
+///
+/// См. [`BloxrouteRelay::send`].
+pub async fn send_to_bloxroute(tx_base64: &str, tip: u64) -> anyhow::Result<()> {
+    BloxrouteRelay::from_config()?.send(tx_base64, tip).await?;
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,934 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::Engine as _;
+use clap::{Parser, Subcommand};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::{lamports_to_sol, sol_to_lamports},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionTokenBalance,
+};
+
+use crate::backtest::{extract_pool_histories, run_backtest, StrategyParams};
+use crate::blockhash::{spawn_rpc_refresher, BlockhashCache};
+use crate::config::{
+    BACKTEST_FEE_BPS, BACKTEST_LANDING_DELAY_SLOTS, BACKTEST_MAX_HOLD_SLOTS, BACKTEST_STOP_LOSS,
+    BACKTEST_TAKE_PROFIT, BACKTEST_TX_COST_LAMPORTS, BLOCKHASH_MAX_AGE_MS,
+    BLOCKHASH_POLL_INTERVAL_MS, BLOXROUTE_API_KEY, BLOXROUTE_URL, BUY_AMOUNT_LAMPORTS,
+    BUY_STRATEGY, DAMM_V2_PROGRAM_ID, DBC_CURVE_ENABLED, DBC_CURVE_STRATEGY, DBC_MIGRATION_ENABLED,
+    DBC_MIGRATION_STRATEGY, DBC_PROGRAM_ID, DLMM_PROGRAM_ID, FILTER_DIR, FILTER_RELOAD_INTERVAL_MS,
+    GEYSER_RECORD_DIR, GRPC_URL, METEORA_PROGRAM_ID, NEXTBLOCK_API_KEY, NEXTBLOCK_URL,
+    PRIVATE_KEY_BASE58, RELAYER_TIP_LAMPORTS, RELAY_ROUTING_ENABLED, RELAY_WARMUP_INTERVAL_MS,
+    REMOTE_SIGNER, REPUTATION_ENABLED, RPC_CONFIRM_TIMEOUT_MS, RPC_ENDPOINTS,
+    SEEN_POOLS_CACHE_SIZE, STORE_PATH, TPU_CONTACTS_REFRESH_INTERVAL_MS, TPU_ENABLED,
+    TPU_LEADER_LOOKAHEAD_SLOTS, TPU_LEADER_REFRESH_INTERVAL_MS, TPU_TIMEOUT_MS, WSOL_MINT,
+    WSOL_UNBUDGETED_LAMPORTS,
+};
+use crate::context::BotContext;
+use crate::filters::{spawn_filter_reloader, LaunchFilter};
+use crate::geyser::{self, detect_pool_inits_in_transaction, recorder::read_recording, Decision};
+use crate::keyloader;
+use crate::relay::{
+    guard::{GuardedRelay, RelayPolicy},
+    routing::RelayRouter,
+    Relay, RelayRegistry,
+};
+use crate::replay::{replay_recording, ReplaySpeed};
+use crate::reputation::{DeployerReputation, ReputationParams};
+use crate::risk::{RiskLimits, RiskManager};
+use crate::rpc::RpcPool;
+use crate::secrets::{redact_url, scrub_url_secrets};
+use crate::signer::{SignerEndpoint, TxSigner};
+use crate::sizing::{BuySizing, PhaseSizing};
+use crate::store::Store;
+use crate::swap::{build_transaction, fetch_fill_amount, submit_swap, swap_instructions};
+use crate::tpu::{
+    leaders::{spawn_leader_refresher, LeaderTracker},
+    TpuRelay,
+};
+use crate::wallets::{Wallet, WalletPolicy, WalletPool};
+use crate::wsol;
+
+/// Снайпер новых пулов Meteora
+#[derive(Debug, Parser)]
+#[command(name = "meteora_sniper_bot", version)]
+pub struct Cli {
+    /// Без подкоманды запускается бот (`run`)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+pub enum Command {
+    /// Запустить мониторинг новых пулов и покупку
+    Run,
+    /// Обернуть SOL в новый WSOL-аккаунт
+    Wrap {
+        /// Сумма в SOL, например 0.1
+        #[arg(value_parser = parse_sol)]
+        amount: u64,
+        /// Адрес кошелька из WALLETS (по умолчанию — первый)
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Закрыть все WSOL-аккаунты и вернуть SOL на кошелёк
+    Unwrap {
+        /// Адрес кошелька из WALLETS (по умолчанию — первый)
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Показать баланс SOL и WSOL кошельков
+    Balance {
+        /// Адрес кошелька из WALLETS (по умолчанию — все кошельки)
+        #[arg(long)]
+        wallet: Option<Pubkey>,
+    },
+    /// Показать позиции из хранилища
+    Positions,
+    /// Продать купленный токен в том же пуле
+    Sell {
+        mint: Pubkey,
+        /// Количество токенов (по умолчанию — вся позиция)
+        #[arg(long)]
+        amount: Option<u64>,
+        /// Минимум WSOL на выходе, в лампортах
+        #[arg(long, default_value_t = 1)]
+        min_out: u64,
+    },
+    /// Разобрать транзакцию: статус, изменения балансов, инициализации пулов
+    Inspect { signature: Signature },
+    /// Прогнать запись Geyser через конвейер, ничего не отправляя
+    Replay {
+        /// Файл записи или каталог с ротированными файлами
+        path: PathBuf,
+        /// Выдерживать исходные интервалы между обновлениями
+        #[arg(long)]
+        realtime: bool,
+    },
+    /// Посчитать PnL стратегии по записи Geyser
+    Backtest {
+        /// Файл записи или каталог с ротированными файлами
+        path: PathBuf,
+    },
+    /// Проверить настройки из config.rs
+    CheckConfig {
+        /// Дополнительно проверить доступность RPC и баланс кошелька
+        #[arg(long)]
+        online: bool,
+    },
+}
+
+/// Результат одной проверки `check-config`
+#[derive(Debug)]
+pub struct ConfigCheck {
+    pub name: &'static str,
+    pub result: Result<String>,
+}
+
+/// Выполняет подкоманду
+pub async fn run(cli: Cli) -> Result<()> {
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run_bot().await,
+        Command::Wrap { amount, wallet } => {
+            let wallet = load_wallet(wallet.as_ref())?;
+            let account =
+                wsol::create_wsol_account(&rpc_pool()?.client(), wallet.signer.as_ref(), amount)
+                    .await?;
+            println!("Обёрнуто {} SOL в {}", lamports_to_sol(amount), account);
+            Ok(())
+        }
+        Command::Unwrap { wallet } => {
+            let wallet = load_wallet(wallet.as_ref())?;
+            let unwrapped =
+                wsol::unwrap_wsol(&rpc_pool()?.client(), wallet.signer.as_ref()).await?;
+            println!("Развёрнуто {} SOL", lamports_to_sol(unwrapped));
+            Ok(())
+        }
+        Command::Balance { wallet } => show_balance(wallet.as_ref()).await,
+        Command::Positions => show_positions(),
+        Command::Sell {
+            mint,
+            amount,
+            min_out,
+        } => sell(&mint, amount, min_out).await,
+        Command::Inspect { signature } => inspect(&signature).await,
+        Command::Replay { path, realtime } => {
+            let speed = if realtime {
+                ReplaySpeed::Original
+            } else {
+                ReplaySpeed::AsFastAsPossible
+            };
+            show_replay(&path, speed).await
+        }
+        Command::Backtest { path } => show_backtest(&path),
+        Command::CheckConfig { online } => {
+            let mut checks = check_config();
+            if online {
+                checks.extend(check_connectivity().await);
+            }
+
+            let mut failed = 0;
+            for check in &checks {
+                match &check.result {
+                    Ok(details) => println!("OK    {}: {}", check.name, details),
+                    Err(e) => {
+                        failed += 1;
+                        println!("FAIL  {}: {:#}", check.name, e);
+                    }
+                }
+            }
+            if failed > 0 {
+                bail!("Проверок не пройдено: {}", failed);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn rpc_pool() -> Result<RpcPool> {
+    RpcPool::from_config().context("Ошибка настройки RPC_ENDPOINTS")
+}
+
+/// Подписант подкоманд, которым кошелёк не важен, — первый из торговых кошельков
+fn load_payer() -> Result<Arc<dyn TxSigner>> {
+    Ok(WalletPool::from_config()?.primary().signer.clone())
+}
+
+/// Торговый кошелёк с адресом `address`; без адреса — первый
+fn load_wallet(address: Option<&Pubkey>) -> Result<Arc<Wallet>> {
+    let wallets = WalletPool::from_config()?;
+    let Some(address) = address else {
+        return Ok(wallets.primary().clone());
+    };
+    wallets
+        .wallets()
+        .iter()
+        .find(|wallet| wallet.pubkey() == *address)
+        .cloned()
+        .ok_or_else(|| anyhow!("Кошелёк {} не задан в WALLETS", address))
+}
+
+fn open_store() -> Result<Store> {
+    Store::open(STORE_PATH, SEEN_POOLS_CACHE_SIZE).context("Ошибка открытия хранилища")
+}
+
+fn parse_sol(s: &str) -> std::result::Result<u64, String> {
+    let sol: f64 = s
+        .parse()
+        .map_err(|e| format!("Невалидная сумма {}: {}", s, e))?;
+    if !sol.is_finite() || sol <= 0.0 {
+        return Err(format!("Сумма должна быть положительной: {}", s));
+    }
+    Ok(sol_to_lamports(sol))
+}
+
+/// Расписание лидеров с фоновым обновлением расписания и адресов валидаторов
+fn leader_tracker(rpc: &Arc<RpcPool>) -> Arc<LeaderTracker> {
+    let leaders = Arc::new(LeaderTracker::new());
+    spawn_leader_refresher(
+        leaders.clone(),
+        rpc.clone(),
+        TPU_LEADER_LOOKAHEAD_SLOTS,
+        Duration::from_millis(TPU_LEADER_REFRESH_INTERVAL_MS),
+        Duration::from_millis(TPU_CONTACTS_REFRESH_INTERVAL_MS),
+    );
+    leaders
+}
+
+/// Ретранслятор TPU со своим таймаутом и автоматом защиты
+fn tpu_relay(leaders: Arc<LeaderTracker>, payer: &Keypair) -> Result<Arc<dyn Relay>> {
+    let tpu = TpuRelay::from_config(leaders, payer).context("Ошибка создания QUIC-клиента TPU")?;
+    let policy = RelayPolicy::from_config(Duration::from_millis(TPU_TIMEOUT_MS));
+    Ok(Arc::new(GuardedRelay::new(Arc::new(tpu), policy)))
+}
+
+/// Основной режим: восстановление состояния, создание WSOL и мониторинг Geyser
+async fn run_bot() -> Result<()> {
+    // Пул RPC-узлов с уровнем подтверждения "confirmed"
+    let rpc = Arc::new(rpc_pool()?);
+
+    // Загрузка торговых кошельков
+    let wallets = WalletPool::from_config()?;
+
+    // Открытие хранилища и восстановление состояния после перезапуска
+    let store = Arc::new(open_store()?);
+    let state = store.recover().context("Ошибка восстановления состояния")?;
+    println!(
+        "Восстановлено пулов: {}, неподтверждённых транзакций: {}",
+        state.seen_pools,
+        state.pending.len()
+    );
+
+    // Покупки, отправленные до перезапуска, сверяются с сетью: иначе лимиты риска
+    // и бюджеты кошельков не учтут уже купленные токены
+    let reconciled =
+        geyser::reconcile_pending(&rpc, &store, &state.pending, &wallets.primary().pubkey())
+            .await
+            .context("Ошибка сверки неподтверждённых транзакций")?;
+    if !state.pending.is_empty() {
+        println!(
+            "Сверено: исполнено {}, отклонено {}, без статуса {}",
+            reconciled.filled, reconciled.failed, reconciled.unresolved
+        );
+    }
+
+    // У каждого кошелька свой WSOL-аккаунт: прежний переиспользуется и доливается до бюджета
+    let mut wsol_accounts = Vec::with_capacity(wallets.wallets().len());
+    for wallet in wallets.wallets() {
+        let lamports = match wallet.budget_lamports {
+            u64::MAX => WSOL_UNBUDGETED_LAMPORTS,
+            budget => budget,
+        };
+        let account = wsol::ensure_wsol_account(&rpc.client(), wallet.signer.as_ref(), lamports)
+            .await
+            .with_context(|| format!("Ошибка подготовки WSOL аккаунта {}", wallet.pubkey()))?;
+        wsol_accounts.push(account);
+    }
+    let mut wsol_accounts = wsol_accounts.into_iter();
+    let wallets = Arc::new(wallets.map_wallets(|wallet| match wsol_accounts.next() {
+        Some(account) => wallet.with_wsol_account(account),
+        None => wallet,
+    })?);
+    println!(
+        "Кошельков: {}, выбор: {}",
+        wallets.wallets().len(),
+        wallets.policy()
+    );
+
+    // Фоновое обновление blockhash: из Geyser, а при его молчании — через RPC
+    let blockhash = Arc::new(BlockhashCache::new(Duration::from_millis(
+        BLOCKHASH_MAX_AGE_MS,
+    )));
+    spawn_rpc_refresher(
+        blockhash.clone(),
+        rpc.clone(),
+        Duration::from_millis(BLOCKHASH_POLL_INTERVAL_MS),
+    );
+
+    // TPU и выбор ретрансляторов по лидерам используют одно расписание
+    let mut relays = RelayRegistry::from_config();
+    if TPU_ENABLED || RELAY_ROUTING_ENABLED {
+        let leaders = leader_tracker(&rpc);
+        if TPU_ENABLED {
+            // С сервисом подписи ключа в процессе нет: сертификат QUIC подписывается временным
+            let identity = wallets
+                .primary()
+                .signer
+                .keypair()
+                .map_or_else(Keypair::new, Keypair::insecure_clone);
+            relays = relays.with(tpu_relay(leaders.clone(), &identity)?);
+        }
+        if RELAY_ROUTING_ENABLED {
+            relays = relays.with_router(RelayRouter::from_config(leaders)?);
+        }
+    }
+
+    // Соединения с ретрансляторами открываются заранее и поддерживаются тёплыми
+    let relays = Arc::new(relays);
+    relays
+        .clone()
+        .spawn_warmer(Duration::from_millis(RELAY_WARMUP_INTERVAL_MS));
+
+    // Белые и чёрные списки перечитываются при изменении файлов
+    let filter = Arc::new(LaunchFilter::from_config()?);
+    if FILTER_DIR.is_some() {
+        spawn_filter_reloader(
+            filter.clone(),
+            Duration::from_millis(FILTER_RELOAD_INTERVAL_MS),
+        );
+    }
+
+    let reputation = REPUTATION_ENABLED.then(|| {
+        Arc::new(DeployerReputation::new(
+            rpc.clone(),
+            store.clone(),
+            ReputationParams::from_config(),
+        ))
+    });
+
+    println!("Запуск отслеживания ликвидности через Meteora...");
+
+    // Запуск мониторинга транзакций через Geyser
+    let ctx = BotContext {
+        rpc,
+        relays,
+        wallets,
+        store,
+        blockhash,
+        risk: Arc::new(RiskManager::new(RiskLimits::from_config())),
+        filter,
+        reputation,
+    };
+    geyser::monitor_liquidity_additions(GRPC_URL, ctx)
+        .await
+        .context("Ошибка мониторинга")
+}
+
+async fn show_balance(wallet: Option<&Pubkey>) -> Result<()> {
+    let rpc = rpc_pool()?;
+    let owners: Vec<Pubkey> = match wallet {
+        Some(_) => vec![load_wallet(wallet)?.pubkey()],
+        None => WalletPool::from_config()?
+            .wallets()
+            .iter()
+            .map(|wallet| wallet.pubkey())
+            .collect(),
+    };
+
+    for owner in owners {
+        let lamports = rpc
+            .read(|client| async move { client.get_balance(&owner).await })
+            .await?;
+        println!("Кошелёк: {}", owner);
+        println!("SOL:     {}", lamports_to_sol(lamports));
+
+        let accounts = rpc
+            .read(|client| async move { wsol::wsol_accounts(&client, &owner).await })
+            .await?;
+        for (account, amount) in &accounts {
+            println!("WSOL:    {} ({})", lamports_to_sol(*amount), account);
+        }
+        if accounts.is_empty() {
+            println!("WSOL:    аккаунтов нет");
+        }
+    }
+    Ok(())
+}
+
+fn show_positions() -> Result<()> {
+    let store = open_store()?;
+    let positions = store.positions()?;
+    if positions.is_empty() {
+        println!("Позиций нет");
+        return Ok(());
+    }
+
+    for p in &positions {
+        // `-` — покупка записана без кошелька
+        let wallet = store
+            .pool_buy_wallet(&p.pool)?
+            .map_or_else(|| "-".to_string(), |wallet| wallet.to_string());
+        println!(
+            "{} pool={} wallet={} held={} bought={} sold={} spent={} SOL received={} SOL",
+            p.mint,
+            p.pool,
+            wallet,
+            p.tokens_held(),
+            p.tokens_bought,
+            p.tokens_sold,
+            lamports_to_sol(p.sol_spent),
+            lamports_to_sol(p.sol_received),
+        );
+    }
+    Ok(())
+}
+
+/// Продаёт токен обратным свапом в пуле, где он был куплен, с кошелька покупки
+async fn sell(mint: &Pubkey, amount: Option<u64>, min_out: u64) -> Result<()> {
+    let store = open_store()?;
+    let accounts = store
+        .pool_accounts_for_mint(mint)?
+        .ok_or_else(|| anyhow!("Токен {} не покупался ботом", mint))?;
+
+    let held = store
+        .positions()?
+        .into_iter()
+        .find(|p| p.mint == *mint)
+        .map_or(0, |p| p.tokens_held());
+    let amount = amount.unwrap_or(held);
+    if amount == 0 {
+        bail!("Нечего продавать: позиция по {} пуста", mint);
+    }
+
+    // Аккаунты пула записаны под кошелёк покупки, поэтому и подписывает он;
+    // покупки, записанные без кошелька, сделаны первым кошельком
+    let wallet = load_wallet(store.pool_buy_wallet(&accounts.pool)?.as_ref())?;
+    let payer = wallet.signer.clone();
+
+    let rpc = rpc_pool()?;
+    let blockhash = rpc
+        .read(|client| async move { client.get_latest_blockhash().await })
+        .await?;
+    let instructions =
+        swap_instructions(&rpc, &payer.pubkey(), &accounts.reversed(), amount, min_out).await?;
+    let tx = build_transaction(
+        payer.as_ref(),
+        instructions,
+        RELAYER_TIP_LAMPORTS,
+        blockhash,
+    )
+    .await?;
+    let signature = tx.signatures[0];
+
+    if let Err(e) = submit_swap(
+        &rpc,
+        &RelayRegistry::from_config(),
+        &tx,
+        RELAYER_TIP_LAMPORTS,
+    )
+    .await
+    {
+        eprintln!("Продажа отправлена с ошибками: {:?}", e);
+    }
+
+    let timeout = Duration::from_millis(RPC_CONFIRM_TIMEOUT_MS);
+    match rpc.wait_for_confirmation(&signature, timeout).await? {
+        Some(Ok(())) => {
+            let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+            let owner = payer.pubkey();
+            let received = rpc
+                .read(|client| async move {
+                    fetch_fill_amount(&client, &signature, &owner, &wsol_mint).await
+                })
+                .await?;
+            store.record_sell(&signature, &accounts.pool, mint, amount, received)?;
+            println!(
+                "Продано {} токенов за {} SOL: {}",
+                amount,
+                lamports_to_sol(received),
+                signature
+            );
+            Ok(())
+        }
+        Some(Err(e)) => bail!("Продажа {} отклонена: {}", signature, e),
+        None => {
+            println!("Транзакция {} ещё не подтверждена", signature);
+            Ok(())
+        }
+    }
+}
+
+/// Печатает разбор подтверждённой транзакции
+async fn inspect(signature: &Signature) -> Result<()> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(CommitmentConfig::confirmed()),
+        max_supported_transaction_version: Some(0),
+    };
+    let tx = rpc_pool()?
+        .read(|client| async move { client.get_transaction_with_config(signature, config).await })
+        .await?;
+
+    println!("Подпись: {}", signature);
+    println!("Слот:    {}", tx.slot);
+    if let Some(block_time) = tx.block_time {
+        println!("Время:   {}", block_time);
+    }
+
+    let versioned = tx
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| anyhow!("Не удалось декодировать транзакцию {}", signature))?;
+    let Some(meta) = tx.transaction.meta else {
+        println!("Meta отсутствует");
+        return Ok(());
+    };
+
+    match &meta.err {
+        None => println!("Статус:  успешно"),
+        Some(e) => println!("Статус:  ошибка {}", e),
+    }
+    println!("Комиссия: {} SOL", lamports_to_sol(meta.fee));
+
+    for change in token_balance_changes(
+        Option::from(meta.pre_token_balances).unwrap_or_default(),
+        Option::from(meta.post_token_balances).unwrap_or_default(),
+    ) {
+        println!(
+            "Токены: owner={} mint={} {:+}",
+            change.0, change.1, change.2
+        );
+    }
+
+    let loaded: Vec<Pubkey> = match meta.loaded_addresses {
+        OptionSerializer::Some(loaded) => loaded
+            .writable
+            .iter()
+            .chain(&loaded.readonly)
+            .map(|s| Pubkey::from_str(s))
+            .collect::<std::result::Result<_, _>>()?,
+        _ => vec![],
+    };
+    let meteora_program = Pubkey::from_str(METEORA_PROGRAM_ID)?;
+    for init in detect_pool_inits_in_transaction(tx.slot, &versioned, &loaded, &meteora_program) {
+        println!(
+            "Инициализация пула {}: pool={} token_a={} token_b={} creator={}",
+            init.accounts.program, init.accounts.pool, init.token_a, init.token_b, init.creator
+        );
+    }
+
+    Ok(())
+}
+
+/// Изменения токен-балансов по парам (владелец, mint), без нулевых
+fn token_balance_changes(
+    pre: Vec<UiTransactionTokenBalance>,
+    post: Vec<UiTransactionTokenBalance>,
+) -> Vec<(String, String, i128)> {
+    let mut changes: Vec<(String, String, i128)> = vec![];
+    for (balances, sign) in [(pre, -1i128), (post, 1i128)] {
+        for b in balances {
+            let owner = Option::<String>::from(b.owner).unwrap_or_default();
+            let amount = b.ui_token_amount.amount.parse::<i128>().unwrap_or_default() * sign;
+            match changes
+                .iter_mut()
+                .find(|(o, m, _)| *o == owner && *m == b.mint)
+            {
+                Some(entry) => entry.2 += amount,
+                None => changes.push((owner, b.mint, amount)),
+            }
+        }
+    }
+    changes.retain(|c| c.2 != 0);
+    changes
+}
+
+/// Прогоняет запись Geyser через конвейер обнаружения и печатает решения
+async fn show_replay(path: &Path, speed: ReplaySpeed) -> Result<()> {
+    // Ключ нужен только для подписи печатаемых транзакций
+    let payer = load_payer().unwrap_or_else(|_| Arc::new(Keypair::new()));
+
+    let decisions = replay_recording(path, speed, payer.as_ref())
+        .await
+        .context("Ошибка воспроизведения")?;
+
+    for d in &decisions {
+        match &d.decision {
+            Decision::Buy(launch) => {
+                println!(
+                    "BUY  slot={} pool={} mint={} init_tx={}",
+                    launch.slot, launch.accounts.pool, launch.target_mint, launch.signature
+                );
+                if let Some(tx) = &d.transaction {
+                    match bincode::serialize(tx) {
+                        Ok(bytes) => println!(
+                            "     tx={}",
+                            base64::engine::general_purpose::STANDARD.encode(bytes)
+                        ),
+                        Err(e) => eprintln!("     ошибка сериализации: {:?}", e),
+                    }
+                }
+            }
+            Decision::Skip { pool, reason } => println!("SKIP pool={} ({})", pool, reason),
+        }
+    }
+    println!("Всего решений: {}", decisions.len());
+    Ok(())
+}
+
+/// Восстанавливает историю пулов из записи и считает PnL стратегии из config.rs
+fn show_backtest(path: &Path) -> Result<()> {
+    let histories = read_recording(path)
+        .and_then(extract_pool_histories)
+        .context("Ошибка чтения записи")?;
+
+    let params = StrategyParams {
+        buy_lamports: BUY_AMOUNT_LAMPORTS,
+        fee_bps: BACKTEST_FEE_BPS,
+        landing_delay_slots: BACKTEST_LANDING_DELAY_SLOTS,
+        take_profit: BACKTEST_TAKE_PROFIT,
+        stop_loss: BACKTEST_STOP_LOSS,
+        max_hold_slots: BACKTEST_MAX_HOLD_SLOTS,
+        tx_cost_lamports: BACKTEST_TX_COST_LAMPORTS,
+    };
+
+    println!("{}", run_backtest(&histories, &params));
+    Ok(())
+}
+
+/// Проверяет config.rs без обращения к сети
+pub fn check_config() -> Vec<ConfigCheck> {
+    let mut checks = vec![ConfigCheck {
+        name: "PRIVATE_KEY_BASE58",
+        result: keyloader::load_keypair(&PRIVATE_KEY_BASE58).map(|k| k.pubkey().to_string()),
+    }];
+
+    checks.push(ConfigCheck {
+        name: "WALLETS",
+        result: WalletPool::from_config().and_then(|pool| {
+            // Каждой включённой фазе запуска нужен хотя бы один кошелёк её стратегии
+            let strategies = [
+                (true, BUY_STRATEGY),
+                (DBC_CURVE_ENABLED, DBC_CURVE_STRATEGY),
+                (DBC_MIGRATION_ENABLED, DBC_MIGRATION_STRATEGY),
+            ];
+            let missing = strategies.iter().find(|(enabled, strategy)| {
+                *enabled
+                    && !pool
+                        .wallets()
+                        .iter()
+                        .any(|w| w.strategy.as_deref().is_none_or(|s| s == *strategy))
+            });
+            if let (WalletPolicy::PerStrategy, Some((_, strategy))) = (pool.policy(), missing) {
+                return Err(anyhow!("нет кошельков для стратегии {}", strategy));
+            }
+            Ok(format!(
+                "кошельков: {}, выбор: {}",
+                pool.wallets().len(),
+                pool.policy()
+            ))
+        }),
+    });
+
+    checks.push(ConfigCheck {
+        name: "REMOTE_SIGNER",
+        result: match REMOTE_SIGNER {
+            Some(endpoint) => endpoint
+                .parse::<SignerEndpoint>()
+                .map(|endpoint| endpoint.to_string()),
+            None => Ok("ключи в процессе бота".to_string()),
+        },
+    });
+
+    for (name, value) in [
+        ("METEORA_PROGRAM_ID", METEORA_PROGRAM_ID),
+        ("DLMM_PROGRAM_ID", DLMM_PROGRAM_ID),
+        ("DAMM_V2_PROGRAM_ID", DAMM_V2_PROGRAM_ID),
+        ("DBC_PROGRAM_ID", DBC_PROGRAM_ID),
+        ("WSOL_MINT", WSOL_MINT),
+    ] {
+        checks.push(ConfigCheck {
+            name,
+            result: Pubkey::from_str(value)
+                .map(|_| value.to_string())
+                .map_err(|e| anyhow!("невалидный pubkey: {}", e)),
+        });
+    }
+
+    checks.push(ConfigCheck {
+        name: "RPC_ENDPOINTS",
+        result: RpcPool::from_config().map(|_| format!("узлов: {}", RPC_ENDPOINTS.len())),
+    });
+    for (url, role) in RPC_ENDPOINTS {
+        checks.push(ConfigCheck {
+            name: "RPC_ENDPOINTS",
+            result: check_url(url).map(|url| format!("{} ({})", url, role)),
+        });
+    }
+
+    for (name, value) in [
+        ("GRPC_URL", GRPC_URL),
+        ("BLOXROUTE_URL", BLOXROUTE_URL),
+        ("NEXTBLOCK_URL", NEXTBLOCK_URL),
+    ] {
+        checks.push(ConfigCheck {
+            name,
+            result: check_url(value),
+        });
+    }
+
+    // Значение ключа не выводится — только источник
+    for (name, source) in [
+        ("BLOXROUTE_API_KEY", BLOXROUTE_API_KEY),
+        ("NEXTBLOCK_API_KEY", NEXTBLOCK_API_KEY),
+    ] {
+        checks.push(ConfigCheck {
+            name,
+            result: source.load().map(|_| format!("задан ({})", source)),
+        });
+    }
+
+    checks.push(ConfigCheck {
+        name: "STORE_PATH",
+        result: check_store_path(Path::new(STORE_PATH)),
+    });
+
+    checks.push(ConfigCheck {
+        name: "GEYSER_RECORD_DIR",
+        result: match GEYSER_RECORD_DIR {
+            Some(dir) => check_record_dir(Path::new(dir)),
+            None => Ok("запись выключена".to_string()),
+        },
+    });
+
+    checks.push(ConfigCheck {
+        name: "FILTER_DIR",
+        result: match FILTER_DIR {
+            Some(_) => LaunchFilter::from_config().map(|filter| filter.lists().to_string()),
+            None => Ok("фильтры выключены".to_string()),
+        },
+    });
+
+    let limits = RiskLimits::from_config();
+    checks.push(ConfigCheck {
+        name: "BUY_AMOUNT_LAMPORTS",
+        result: if BUY_AMOUNT_LAMPORTS == 0 {
+            Err(anyhow!("сумма покупки должна быть больше нуля"))
+        } else if BUY_AMOUNT_LAMPORTS > limits.max_trade_lamports {
+            Err(anyhow!(
+                "{} SOL больше лимита на сделку RISK_MAX_TRADE_LAMPORTS ({} SOL)",
+                lamports_to_sol(BUY_AMOUNT_LAMPORTS),
+                lamports_to_sol(limits.max_trade_lamports)
+            ))
+        } else {
+            Ok(format!("{} SOL", lamports_to_sol(BUY_AMOUNT_LAMPORTS)))
+        },
+    });
+
+    let sizing = BuySizing::from_config();
+    checks.push(ConfigCheck {
+        name: "MIN/MAX_POOL_LIQUIDITY_LAMPORTS",
+        result: if sizing.min_liquidity > sizing.max_liquidity {
+            Err(anyhow!("минимум ликвидности больше максимума"))
+        } else if sizing
+            .liquidity_bps
+            .is_some_and(|bps| bps == 0 || bps > 10_000)
+        {
+            Err(anyhow!(
+                "BUY_LIQUIDITY_BPS должен быть в диапазоне 1..=10000"
+            ))
+        } else if !sizing.is_bounded() {
+            Ok("без ограничений".to_string())
+        } else {
+            Ok(format!(
+                "{}..={} SOL",
+                lamports_to_sol(sizing.min_liquidity),
+                lamports_to_sol(sizing.max_liquidity)
+            ))
+        },
+    });
+
+    checks.push(ConfigCheck {
+        name: "DBC_CURVE/DBC_MIGRATION",
+        result: PhaseSizing::from_config().and_then(|phases| {
+            if phases
+                .migration
+                .is_some_and(|m| m.min_liquidity > m.max_liquidity)
+            {
+                return Err(anyhow!(
+                    "минимум ликвидности после миграции больше максимума"
+                ));
+            }
+            let curve = match phases.curve {
+                Some(curve) if phases.curve_configs.is_empty() => {
+                    format!("{} SOL на любой кривой", lamports_to_sol(curve.max_amount))
+                }
+                Some(curve) => format!(
+                    "{} SOL на кривых {} конфигов",
+                    lamports_to_sol(curve.max_amount),
+                    phases.curve_configs.len()
+                ),
+                None => "выкл.".to_string(),
+            };
+            let migration = match phases.migration {
+                Some(migration) => format!("{} SOL", lamports_to_sol(migration.max_amount)),
+                None => "выкл.".to_string(),
+            };
+            Ok(format!("кривая: {}, после миграции: {}", curve, migration))
+        }),
+    });
+
+    checks
+}
+
+/// Проверяет базу, ничего не создавая: существующая открывается только на чтение,
+/// а для новой нужен каталог, доступный на запись
+pub fn check_store_path(path: &Path) -> Result<String> {
+    if path.exists() {
+        if fs::metadata(path)?.permissions().readonly() {
+            bail!("{} доступна только на чтение", path.display());
+        }
+        let pools = Store::inspect(path)?;
+        return Ok(format!("{} (увиденных пулов: {})", path.display(), pools));
+    }
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    if !dir.is_dir() {
+        bail!("каталог {} не существует", dir.display());
+    }
+    check_writable_dir(dir)?;
+    Ok(format!("{} (будет создана)", path.display()))
+}
+
+/// Проверяет каталог записи, ничего не создавая: он или ближайший существующий
+/// родитель должен быть доступен на запись
+pub fn check_record_dir(dir: &Path) -> Result<String> {
+    if dir.exists() {
+        if !dir.is_dir() {
+            bail!("{} — не каталог", dir.display());
+        }
+        check_writable_dir(dir)?;
+        return Ok(dir.display().to_string());
+    }
+
+    let existing = dir
+        .ancestors()
+        .skip(1)
+        .find(|parent| parent.is_dir())
+        .unwrap_or(Path::new("."));
+    check_writable_dir(existing)?;
+    Ok(format!("{} (будет создан)", dir.display()))
+}
+
+fn check_writable_dir(dir: &Path) -> Result<()> {
+    if fs::metadata(dir)?.permissions().readonly() {
+        bail!("каталог {} недоступен на запись", dir.display());
+    }
+    Ok(())
+}
+
+fn check_url(value: &str) -> Result<String> {
+    let url = reqwest::Url::parse(value)?;
+    match url.scheme() {
+        "http" | "https" => Ok(redact_url(value)),
+        scheme => Err(anyhow!("неподдерживаемая схема {}", scheme)),
+    }
+}
+
+/// Проверяет доступность каждого RPC-узла и баланс кошелька
+async fn check_connectivity() -> Vec<ConfigCheck> {
+    let mut checks = vec![];
+    for (url, _) in RPC_ENDPOINTS {
+        let rpc = RpcClient::new(url.to_string());
+        let shown = redact_url(url);
+        checks.push(ConfigCheck {
+            name: "RPC",
+            result: rpc
+                .get_version()
+                .await
+                .map(|v| format!("{}: solana-core {}", shown, v.solana_core))
+                .map_err(|e| anyhow!("{}: {}", shown, scrub_url_secrets(url, &e.to_string()))),
+        });
+    }
+
+    let Ok(rpc) = RpcPool::from_config() else {
+        return checks;
+    };
+    if let Ok(payer) = load_payer() {
+        let owner = payer.pubkey();
+        let balance = rpc
+            .read(|client| async move { client.get_balance(&owner).await })
+            .await;
+        let result = match balance {
+            Ok(lamports) if lamports >= BUY_AMOUNT_LAMPORTS => {
+                Ok(format!("{} SOL", lamports_to_sol(lamports)))
+            }
+            Ok(lamports) => Err(anyhow!(
+                "{} SOL меньше суммы покупки",
+                lamports_to_sol(lamports)
+            )),
+            Err(e) => Err(e),
+        };
+        checks.push(ConfigCheck {
+            name: "Баланс кошелька",
+            result,
+        });
+    }
+
+    checks
+}
@@ -1,14 +1,175 @@
+use crate::rpc::RpcRole;
+use crate::secrets::SecretSource;
+use crate::wallets::WalletPolicy;
+
 pub const RPC_URL: &str = "https://api.mainnet-beta.solana.com";
-pub const PRIVATE_KEY_BASE58: &str = "ВАШ_BASE58_ПРИВАТНЫЙ_КЛЮЧ";
+/// RPC-узлы бота: чтения переключаются между узлами с ролью Read, транзакции рассылаются во все узлы с ролью Send
+pub const RPC_ENDPOINTS: &[(&str, RpcRole)] = &[(RPC_URL, RpcRole::ReadSend)];
+/// Приватный ключ в base58; секреты читаются из окружения или файла, а не хранятся в коде
+pub const PRIVATE_KEY_BASE58: SecretSource = SecretSource::Env("SNIPER_PRIVATE_KEY");
+/// Торговые кошельки: (base58-ключ, бюджет в лампортах, стратегия); пусто — только PRIVATE_KEY_BASE58 без бюджета
+pub const WALLETS: &[(SecretSource, u64, Option<&str>)] = &[];
+pub const WALLET_POLICY: WalletPolicy = WalletPolicy::RoundRobin;
+/// Сколько держать в WSOL у кошелька без бюджета, лампорты; кошелёк с бюджетом доливается до бюджета
+pub const WSOL_UNBUDGETED_LAMPORTS: u64 = 100_000_000;
+/// SOL, который долив WSOL оставляет на кошельке на комиссии, лампорты
+pub const WSOL_FEE_RESERVE_LAMPORTS: u64 = 10_000_000;
+/// Стратегия live-покупок для WalletPolicy::PerStrategy
+pub const BUY_STRATEGY: &str = "snipe";
+/// Сервис подписи: `http://…` или `unix:/путь` (`None` — ключи в процессе бота).
+/// С ним в WALLETS указываются адреса кошельков (SecretSource::Inline), а не приватные ключи
+pub const REMOTE_SIGNER: Option<&str> = None;
+pub const REMOTE_SIGNER_TIMEOUT_MS: u64 = 500;
 
 pub const BLOXROUTE_URL: &str = "https://api.blxrbdn.com/solana/submit";
-pub const BLOXROUTE_API_KEY: &str = "your_bloxroute_api_key_here";
+pub const BLOXROUTE_API_KEY: SecretSource = SecretSource::Env("BLOXROUTE_API_KEY");
 
 pub const NEXTBLOCK_URL: &str = "https://api.nextblock.io/solana/tx";
-pub const NEXTBLOCK_API_KEY: &str = "entry1742492086-2HBDaHE5gh%2BhMT3DybovkplWHaBdLB9ds78I7x%2FghZc%3D";
+pub const NEXTBLOCK_API_KEY: SecretSource = SecretSource::Env("NEXTBLOCK_API_KEY");
 
 pub const METEORA_PROGRAM_ID: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
 pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const TOKEN_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+/// Программа Meteora DLMM (liquidity book)
+pub const DLMM_PROGRAM_ID: &str = "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9t6wHdmhB";
+/// Сколько bin array в сторону свапа передаётся DLMM, включая массив с активным бином
+pub const DLMM_SWAP_BIN_ARRAYS: usize = 3;
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+/// Программа Meteora DAMM v2 (cp-amm)
+pub const DAMM_V2_PROGRAM_ID: &str = "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG";
+/// Допустимое отклонение выхода свапа DAMM v2 от котировки, базисные пункты;
+/// `None` — котировка только проверяет пул, а минимум выхода задаёт вызывающий
+pub const DAMM_V2_SLIPPAGE_BPS: Option<u64> = None;
+/// Программа Meteora Dynamic Bonding Curve (стартовые кривые лаунчпадов)
+pub const DBC_PROGRAM_ID: &str = "dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN";
+/// Покупка на кривой сразу после её создания
+pub const DBC_CURVE_ENABLED: bool = false;
+pub const DBC_CURVE_BUY_AMOUNT_LAMPORTS: u64 = 500_000;
+/// Конфиги кривых (лаунчпады), на которых покупать; пусто — любые
+pub const DBC_CURVE_CONFIGS: &[&str] = &[];
+/// Стратегия кошельков для покупок на кривой (WalletPolicy::PerStrategy)
+pub const DBC_CURVE_STRATEGY: &str = "curve";
+/// Покупка в пуле DAMM v2, в который мигрировала завершённая кривая
+pub const DBC_MIGRATION_ENABLED: bool = true;
+pub const DBC_MIGRATION_BUY_AMOUNT_LAMPORTS: u64 = 1_000_000;
+/// Допустимая WSOL-ликвидность пула после миграции, лампорты
+pub const DBC_MIGRATION_MIN_LIQUIDITY_LAMPORTS: u64 = 0;
+pub const DBC_MIGRATION_MAX_LIQUIDITY_LAMPORTS: u64 = u64::MAX;
+/// Стратегия кошельков для покупок после миграции (WalletPolicy::PerStrategy)
+pub const DBC_MIGRATION_STRATEGY: &str = "migration";
 pub const GRPC_URL: &str = "https://grpc.ny.shyft.to";
 
 pub const RELAYER_TIP_LAMPORTS: u64 = 10_000;
+
+pub const BUY_AMOUNT_LAMPORTS: u64 = 1_000_000;
+pub const BUY_MIN_OUT: u64 = 1;
+/// Допустимая начальная WSOL-ликвидность пула, лампорты
+pub const MIN_POOL_LIQUIDITY_LAMPORTS: u64 = 0;
+pub const MAX_POOL_LIQUIDITY_LAMPORTS: u64 = u64::MAX;
+/// Покупка как доля начальной WSOL-ликвидности, б.п., но не больше BUY_AMOUNT_LAMPORTS (`None` — всегда BUY_AMOUNT_LAMPORTS)
+pub const BUY_LIQUIDITY_BPS: Option<u64> = None;
+
+pub const STORE_PATH: &str = "sniper_state.db";
+pub const SEEN_POOLS_CACHE_SIZE: usize = 10_000;
+/// Через сколько секунд ненайденная в истории покупка считается не попавшей в блок
+pub const PENDING_EXPIRY_SECS: i64 = 120;
+
+/// Каталог для записи потока Geyser (`None` — запись выключена)
+pub const GEYSER_RECORD_DIR: Option<&str> = None;
+pub const GEYSER_RECORD_MAX_FILE_BYTES: u64 = 256 * 1024 * 1024;
+/// Как часто буфер записи сбрасывается на диск, мс: при падении теряется не больше
+pub const GEYSER_RECORD_FLUSH_INTERVAL_MS: u64 = 1_000;
+/// Очередь обновлений к рекордеру; при переполнении обновление не записывается, а детектор не ждёт диск
+pub const GEYSER_RECORD_QUEUE_CAPACITY: usize = 4_096;
+
+pub const BACKTEST_FEE_BPS: u64 = 25;
+pub const BACKTEST_LANDING_DELAY_SLOTS: u64 = 1;
+pub const BACKTEST_TAKE_PROFIT: f64 = 0.5;
+pub const BACKTEST_STOP_LOSS: f64 = 0.3;
+pub const BACKTEST_MAX_HOLD_SLOTS: u64 = 1_500;
+pub const BACKTEST_TX_COST_LAMPORTS: u64 = 15_000;
+
+/// Сколько покупок выполняется одновременно
+pub const EXECUTOR_WORKERS: usize = 8;
+/// Ёмкость очереди запусков между детектором и исполнителями
+pub const EXECUTOR_QUEUE_CAPACITY: usize = 64;
+
+/// Сколько blockhash из кэша считается свежим без обновлений, мс
+pub const BLOCKHASH_MAX_AGE_MS: u64 = 20_000;
+/// Период опроса RPC, когда Geyser не присылает block-meta, мс
+pub const BLOCKHASH_POLL_INTERVAL_MS: u64 = 2_000;
+
+/// Период прогрева соединений с ретрансляторами, мс
+pub const RELAY_WARMUP_INTERVAL_MS: u64 = 30_000;
+
+/// Таймаут одной попытки отправки в ретранслятор, мс
+pub const BLOXROUTE_TIMEOUT_MS: u64 = 1_500;
+pub const NEXTBLOCK_TIMEOUT_MS: u64 = 1_500;
+/// Повторы отправки при сетевых ошибках, таймаутах и 429
+pub const RELAY_MAX_RETRIES: u32 = 2;
+pub const RELAY_RETRY_BACKOFF_MS: u64 = 100;
+/// После стольких сбоев подряд ретранслятор отключается на RELAY_BREAKER_COOLDOWN_MS
+pub const RELAY_BREAKER_THRESHOLD: u32 = 5;
+pub const RELAY_BREAKER_COOLDOWN_MS: u64 = 30_000;
+
+/// После стольких ошибок подряд RPC-узел опрашивается последним в течение RPC_ENDPOINT_COOLDOWN_MS
+pub const RPC_ENDPOINT_FAILURE_THRESHOLD: u32 = 3;
+pub const RPC_ENDPOINT_COOLDOWN_MS: u64 = 10_000;
+/// Сколько ждать подтверждения отправленной транзакции, мс
+pub const RPC_CONFIRM_TIMEOUT_MS: u64 = 30_000;
+pub const RPC_CONFIRM_POLL_MS: u64 = 400;
+
+/// Прямая отправка транзакций в TPU ближайших лидеров по QUIC
+pub const TPU_ENABLED: bool = false;
+/// Скольким ближайшим лидерам отправляется транзакция
+pub const TPU_LEADER_FANOUT: usize = 3;
+/// На сколько слотов вперёд запрашивается расписание лидеров
+pub const TPU_LEADER_LOOKAHEAD_SLOTS: u64 = 64;
+pub const TPU_LEADER_REFRESH_INTERVAL_MS: u64 = 2_000;
+/// Период обновления адресов TPU из getClusterNodes, мс
+pub const TPU_CONTACTS_REFRESH_INTERVAL_MS: u64 = 60_000;
+pub const TPU_TIMEOUT_MS: u64 = 1_000;
+
+/// Выбирать ретрансляторы и чаевые по ближайшим лидерам
+pub const RELAY_ROUTING_ENABLED: bool = false;
+/// Сколько ближайших лидеров учитывается при выборе ретрансляторов
+pub const RELAY_ROUTING_LEADERS: usize = 2;
+/// Ретрансляторы и чаевые для лидеров без собственного маршрута
+pub const RELAY_DEFAULT_ROUTE: &[(&str, u64)] = &[
+    ("Bloxroute", RELAYER_TIP_LAMPORTS),
+    ("NextBlock", RELAYER_TIP_LAMPORTS),
+    ("TPU", 0),
+];
+/// Валидатор → ретрансляторы и чаевые, которые до него доходят лучше всего
+pub const RELAY_LEADER_ROUTES: &[(&str, &[(&str, u64)])] = &[];
+
+/// Лимиты риска: сумма одной покупки, все вложенные средства и число открытых позиций
+pub const RISK_MAX_TRADE_LAMPORTS: u64 = 10_000_000;
+pub const RISK_MAX_EXPOSURE_LAMPORTS: u64 = 100_000_000;
+pub const RISK_MAX_OPEN_POSITIONS: usize = 10;
+/// Не больше RISK_MAX_BUYS_PER_WINDOW покупок за RISK_BUY_WINDOW_MS
+pub const RISK_MAX_BUYS_PER_WINDOW: usize = 5;
+pub const RISK_BUY_WINDOW_MS: u64 = 60_000;
+/// Реализованный убыток за UTC-сутки, после которого покупки останавливаются
+pub const RISK_MAX_DAILY_LOSS_LAMPORTS: u64 = 50_000_000;
+
+/// Каталог белых и чёрных списков (`mint_allow.txt`, `creator_deny.txt` и т.д.; `None` — без фильтров)
+pub const FILTER_DIR: Option<&str> = None;
+/// Период проверки изменений файлов списков, мс
+pub const FILTER_RELOAD_INTERVAL_MS: u64 = 2_000;
+
+/// Оценка создателя пула по истории его кошелька перед покупкой
+pub const REPUTATION_ENABLED: bool = false;
+/// Покупать, только если оценка создателя (0..=100) не ниже
+pub const REPUTATION_MIN_SCORE: u8 = 50;
+/// Сколько последних транзакций создателя просматривается
+pub const REPUTATION_HISTORY_LIMIT: usize = 200;
+/// Сколько история создателя в хранилище считается свежей, секунды
+pub const REPUTATION_CACHE_TTL_SECS: i64 = 3_600;
+/// Изъятие ликвидности быстрее этого после запуска считается rug-ом, секунды
+pub const REPUTATION_RUG_WINDOW_SECS: i64 = 3_600;
+/// Штрафы к оценке: за прежний запуск, за rug и за источник средств, пополнявший создателей с rug-ами
+pub const REPUTATION_LAUNCH_PENALTY: u8 = 10;
+pub const REPUTATION_RUG_PENALTY: u8 = 40;
+pub const REPUTATION_FUNDER_PENALTY: u8 = 30;
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use crate::blockhash::BlockhashCache;
+use crate::filters::LaunchFilter;
+use crate::relay::RelayRegistry;
+use crate::reputation::DeployerReputation;
+use crate::risk::RiskManager;
+use crate::rpc::RpcPool;
+use crate::store::Store;
+use crate::wallets::WalletPool;
+
+/// Общие зависимости live-режима: пул RPC-узлов, ретрансляторы, кошельки, хранилище,
+/// кэш blockhash, риск-менеджер, фильтры запусков и оценка создателей
+#[derive(Clone)]
+pub struct BotContext {
+    pub rpc: Arc<RpcPool>,
+    pub relays: Arc<RelayRegistry>,
+    pub wallets: Arc<WalletPool>,
+    pub store: Arc<Store>,
+    pub blockhash: Arc<BlockhashCache>,
+    pub risk: Arc<RiskManager>,
+    pub filter: Arc<LaunchFilter>,
+    /// `None` — создатели не оцениваются
+    pub reputation: Option<Arc<DeployerReputation>>,
+}
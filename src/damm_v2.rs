@@ -0,0 +1,673 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context, Result};
+use num_bigint::BigUint;
+use solana_sdk::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::config::{DAMM_V2_PROGRAM_ID, DAMM_V2_SLIPPAGE_BPS, TOKEN_2022_PROGRAM_ID};
+use crate::rpc::RpcPool;
+use crate::swap::{resolve_user_token_accounts, PoolProgram, SwapAccounts};
+
+/// Дискриминаторы Anchor, как в [`crate::dlmm`]
+pub const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+pub const INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_DISCRIMINATOR: [u8; 8] =
+    [149, 82, 72, 197, 253, 252, 68, 15];
+pub const INITIALIZE_CUSTOMIZABLE_POOL_DISCRIMINATOR: [u8; 8] =
+    [20, 161, 241, 24, 189, 221, 180, 2];
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+/// Знаменатель числителей комиссии
+pub const FEE_DENOMINATOR: u64 = 1_000_000_000;
+/// Потолок суммарной комиссии (50%)
+pub const MAX_FEE_NUMERATOR: u64 = 500_000_000;
+/// Знаменатель `reduction_factor` экспоненциального расписания
+const BASIS_POINT_MAX: u64 = 10_000;
+/// Масштаб динамической комиссии: `(v * bin_step)² * variable_fee_control / 1e11`
+const DYNAMIC_FEE_SCALE: u128 = 100_000_000_000;
+
+/// Смещения полей в аккаунте `Pool`: сначала комиссии (базовая и динамическая),
+/// затем mint'ы и хранилища, ликвидность, цены и параметры активации
+const CLIFF_FEE_NUMERATOR_OFFSET: usize = 8;
+const FEE_SCHEDULER_MODE_OFFSET: usize = 16;
+const NUMBER_OF_PERIOD_OFFSET: usize = 22;
+const PERIOD_FREQUENCY_OFFSET: usize = 24;
+const REDUCTION_FACTOR_OFFSET: usize = 32;
+const DYNAMIC_FEE_INITIALIZED_OFFSET: usize = 56;
+const MAX_VOLATILITY_ACCUMULATOR_OFFSET: usize = 64;
+const VARIABLE_FEE_CONTROL_OFFSET: usize = 68;
+const BIN_STEP_OFFSET: usize = 72;
+const VOLATILITY_ACCUMULATOR_OFFSET: usize = 120;
+const TOKEN_A_MINT_OFFSET: usize = 168;
+const TOKEN_B_MINT_OFFSET: usize = 200;
+const TOKEN_A_VAULT_OFFSET: usize = 232;
+const TOKEN_B_VAULT_OFFSET: usize = 264;
+const LIQUIDITY_OFFSET: usize = 360;
+const SQRT_MIN_PRICE_OFFSET: usize = 424;
+const SQRT_MAX_PRICE_OFFSET: usize = 440;
+const SQRT_PRICE_OFFSET: usize = 456;
+const ACTIVATION_POINT_OFFSET: usize = 472;
+const ACTIVATION_TYPE_OFFSET: usize = 480;
+const POOL_STATUS_OFFSET: usize = 481;
+const TOKEN_A_FLAG_OFFSET: usize = 482;
+const TOKEN_B_FLAG_OFFSET: usize = 483;
+const COLLECT_FEE_MODE_OFFSET: usize = 484;
+const CREATOR_OFFSET: usize = 648;
+/// Размер аккаунта `Pool`
+pub const POOL_LEN: usize = 1112;
+
+/// Адрес программы DAMM v2 из config.rs
+pub fn program_id() -> Result<Pubkey> {
+    Ok(Pubkey::from_str(DAMM_V2_PROGRAM_ID)?)
+}
+
+/// Единый для всех пулов владелец хранилищ
+pub fn pool_authority_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_authority"], program).0
+}
+
+pub fn event_authority_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program).0
+}
+
+/// Токен-программа по флагу токена в пуле: 0 — SPL Token, 1 — Token-2022
+pub fn token_program_for_flag(flag: u8) -> Result<Pubkey> {
+    match flag {
+        0 => Ok(spl_token::id()),
+        1 => Ok(Pubkey::from_str(TOKEN_2022_PROGRAM_ID)?),
+        other => bail!("Неизвестный флаг токена DAMM v2 {}", other),
+    }
+}
+
+/// Инструкция создания пула DAMM v2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitializeKind {
+    /// `initialize_pool` по статическому конфигу
+    Config,
+    /// `initialize_customizable_pool` без конфига
+    Customizable,
+    /// `initialize_pool_with_dynamic_config` с подписью владельца конфига
+    DynamicConfig,
+}
+
+impl InitializeKind {
+    pub fn from_data(data: &[u8]) -> Option<Self> {
+        match data.get(..8)? {
+            d if d == INITIALIZE_POOL_DISCRIMINATOR => Some(InitializeKind::Config),
+            d if d == INITIALIZE_CUSTOMIZABLE_POOL_DISCRIMINATOR => {
+                Some(InitializeKind::Customizable)
+            }
+            d if d == INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_DISCRIMINATOR => {
+                Some(InitializeKind::DynamicConfig)
+            }
+            _ => None,
+        }
+    }
+
+    /// Индекс `pool_authority`; остальные аккаунты идут за ним одинаково во всех
+    /// вариантах: pool, position, mint A, mint B, хранилища A и B, токен-аккаунты
+    /// плательщика, токен-программы A и B
+    fn pool_authority_index(self) -> usize {
+        match self {
+            InitializeKind::Config => 5,
+            InitializeKind::Customizable => 4,
+            InitializeKind::DynamicConfig => 6,
+        }
+    }
+
+    /// Аккаунтов инструкции, нужных для покупки: до токен-программы B включительно
+    pub fn accounts_len(self) -> usize {
+        self.pool_authority_index() + 11
+    }
+}
+
+/// Создание пула DAMM v2, найденное в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolInitDammV2 {
+    pub kind: InitializeKind,
+    pub pool: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_program: Pubkey,
+    pub token_b_program: Pubkey,
+    pub pool_authority: Pubkey,
+    /// Подписант, вносящий ликвидность
+    pub payer: Pubkey,
+}
+
+impl PoolInitDammV2 {
+    /// Разбирает любую из трёх инструкций создания пула
+    ///
+    /// # Аргументы
+    /// * `accounts` — аккаунты инструкции, не меньше [`InitializeKind::accounts_len`]
+    /// * `data` — данные инструкции вместе с дискриминатором
+    pub fn parse(accounts: &[Pubkey], data: &[u8]) -> Option<Self> {
+        let kind = InitializeKind::from_data(data)?;
+        if accounts.len() < kind.accounts_len() {
+            return None;
+        }
+        let k = &accounts[kind.pool_authority_index()..];
+        Some(Self {
+            kind,
+            pool: k[1],
+            token_a_mint: k[3],
+            token_b_mint: k[4],
+            token_a_vault: k[5],
+            token_b_vault: k[6],
+            token_a_program: k[9],
+            token_b_program: k[10],
+            pool_authority: k[0],
+            payer: accounts[3],
+        })
+    }
+
+    /// Аккаунты свапа A → B; пользовательские аккаунты — ATA подписанта
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            program: PoolProgram::DammV2,
+            pool: self.pool,
+            user_source: Pubkey::default(),
+            user_destination: Pubkey::default(),
+            pool_source: self.token_a_vault,
+            pool_destination: self.token_b_vault,
+            pool_authority: self.pool_authority,
+            token_program: self.token_a_program,
+        }
+    }
+}
+
+/// Как базовая комиссия снижается после активации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeSchedulerMode {
+    /// Минус `reduction_factor` за период
+    Linear,
+    /// Умножение на `1 - reduction_factor / 10000` за период
+    Exponential,
+}
+
+/// Базовая комиссия с расписанием снижения
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseFee {
+    pub cliff_fee_numerator: u64,
+    pub fee_scheduler_mode: FeeSchedulerMode,
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+}
+
+impl BaseFee {
+    /// Числитель базовой комиссии в точке `current_point`
+    ///
+    /// До активации расписание считается пройденным полностью, как в программе.
+    pub fn fee_numerator(&self, current_point: u64, activation_point: u64) -> u64 {
+        if self.period_frequency == 0 {
+            return self.cliff_fee_numerator;
+        }
+        let max_period = self.number_of_period as u64;
+        let period = match current_point.checked_sub(activation_point) {
+            Some(elapsed) => (elapsed / self.period_frequency).min(max_period),
+            None => max_period,
+        };
+
+        match self.fee_scheduler_mode {
+            FeeSchedulerMode::Linear => self
+                .cliff_fee_numerator
+                .saturating_sub(period.saturating_mul(self.reduction_factor)),
+            FeeSchedulerMode::Exponential => {
+                let keep = BASIS_POINT_MAX.saturating_sub(self.reduction_factor) as u128;
+                (0..period).fold(self.cliff_fee_numerator as u128, |fee, _| {
+                    fee * keep / BASIS_POINT_MAX as u128
+                }) as u64
+            }
+        }
+    }
+}
+
+/// Динамическая комиссия по волатильности
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicFee {
+    pub initialized: bool,
+    pub max_volatility_accumulator: u32,
+    pub variable_fee_control: u32,
+    pub bin_step: u16,
+    pub volatility_accumulator: u128,
+}
+
+impl DynamicFee {
+    /// Числитель динамической комиссии по сохранённому аккумулятору волатильности
+    pub fn fee_numerator(&self) -> u64 {
+        if !self.initialized {
+            return 0;
+        }
+        let volatility = BigUint::from(self.volatility_accumulator) * self.bin_step;
+        let fee = (&volatility * &volatility * self.variable_fee_control + DYNAMIC_FEE_SCALE - 1u8)
+            / DYNAMIC_FEE_SCALE;
+        u64::try_from(fee).unwrap_or(u64::MAX)
+    }
+}
+
+/// В чём измеряется точка активации
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationType {
+    Slot,
+    Timestamp,
+}
+
+/// В каком токене удерживается комиссия
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectFeeMode {
+    /// В выходном токене
+    BothToken,
+    /// Только в токене B: при свапе B → A — со входа
+    OnlyB,
+}
+
+/// Поля аккаунта `Pool`, нужные для котировки и свапа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pool {
+    pub base_fee: BaseFee,
+    pub dynamic_fee: DynamicFee,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub liquidity: u128,
+    /// Корни цен в формате Q64.64
+    pub sqrt_min_price: u128,
+    pub sqrt_max_price: u128,
+    pub sqrt_price: u128,
+    pub activation_point: u64,
+    pub activation_type: ActivationType,
+    /// 0 — торговля разрешена
+    pub pool_status: u8,
+    pub token_a_flag: u8,
+    pub token_b_flag: u8,
+    pub collect_fee_mode: CollectFeeMode,
+    pub creator: Pubkey,
+}
+
+/// Результат котировки свапа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub amount_out: u64,
+    /// Удержанная комиссия во входном или выходном токене, см. [`CollectFeeMode`]
+    pub fee: u64,
+    pub fee_numerator: u64,
+}
+
+impl Pool {
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        if data.len() < POOL_LEN || !data.starts_with(&POOL_DISCRIMINATOR) {
+            bail!("Аккаунт не является пулом DAMM v2");
+        }
+        let bytes = |offset: usize, len: usize| &data[offset..offset + len];
+        let u16_at = |offset| u16::from_le_bytes(bytes(offset, 2).try_into().unwrap());
+        let u32_at = |offset| u32::from_le_bytes(bytes(offset, 4).try_into().unwrap());
+        let u64_at = |offset| u64::from_le_bytes(bytes(offset, 8).try_into().unwrap());
+        let u128_at = |offset| u128::from_le_bytes(bytes(offset, 16).try_into().unwrap());
+        let pubkey = |offset| Pubkey::try_from(bytes(offset, 32)).unwrap();
+
+        let fee_scheduler_mode = match data[FEE_SCHEDULER_MODE_OFFSET] {
+            0 => FeeSchedulerMode::Linear,
+            1 => FeeSchedulerMode::Exponential,
+            other => bail!("Неизвестный режим расписания комиссии DAMM v2 {}", other),
+        };
+        let activation_type = match data[ACTIVATION_TYPE_OFFSET] {
+            0 => ActivationType::Slot,
+            1 => ActivationType::Timestamp,
+            other => bail!("Неизвестный тип активации DAMM v2 {}", other),
+        };
+        let collect_fee_mode = match data[COLLECT_FEE_MODE_OFFSET] {
+            0 => CollectFeeMode::BothToken,
+            1 => CollectFeeMode::OnlyB,
+            other => bail!("Неизвестный режим комиссии DAMM v2 {}", other),
+        };
+
+        Ok(Self {
+            base_fee: BaseFee {
+                cliff_fee_numerator: u64_at(CLIFF_FEE_NUMERATOR_OFFSET),
+                fee_scheduler_mode,
+                number_of_period: u16_at(NUMBER_OF_PERIOD_OFFSET),
+                period_frequency: u64_at(PERIOD_FREQUENCY_OFFSET),
+                reduction_factor: u64_at(REDUCTION_FACTOR_OFFSET),
+            },
+            dynamic_fee: DynamicFee {
+                initialized: data[DYNAMIC_FEE_INITIALIZED_OFFSET] != 0,
+                max_volatility_accumulator: u32_at(MAX_VOLATILITY_ACCUMULATOR_OFFSET),
+                variable_fee_control: u32_at(VARIABLE_FEE_CONTROL_OFFSET),
+                bin_step: u16_at(BIN_STEP_OFFSET),
+                volatility_accumulator: u128_at(VOLATILITY_ACCUMULATOR_OFFSET),
+            },
+            token_a_mint: pubkey(TOKEN_A_MINT_OFFSET),
+            token_b_mint: pubkey(TOKEN_B_MINT_OFFSET),
+            token_a_vault: pubkey(TOKEN_A_VAULT_OFFSET),
+            token_b_vault: pubkey(TOKEN_B_VAULT_OFFSET),
+            liquidity: u128_at(LIQUIDITY_OFFSET),
+            sqrt_min_price: u128_at(SQRT_MIN_PRICE_OFFSET),
+            sqrt_max_price: u128_at(SQRT_MAX_PRICE_OFFSET),
+            sqrt_price: u128_at(SQRT_PRICE_OFFSET),
+            activation_point: u64_at(ACTIVATION_POINT_OFFSET),
+            activation_type,
+            pool_status: data[POOL_STATUS_OFFSET],
+            token_a_flag: data[TOKEN_A_FLAG_OFFSET],
+            token_b_flag: data[TOKEN_B_FLAG_OFFSET],
+            collect_fee_mode,
+            creator: pubkey(CREATOR_OFFSET),
+        })
+    }
+
+    /// Сериализует поля обратно в аккаунт `Pool`; остальные байты нулевые
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; POOL_LEN];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(0, &POOL_DISCRIMINATOR);
+        put(
+            CLIFF_FEE_NUMERATOR_OFFSET,
+            &self.base_fee.cliff_fee_numerator.to_le_bytes(),
+        );
+        put(
+            FEE_SCHEDULER_MODE_OFFSET,
+            &[self.base_fee.fee_scheduler_mode as u8],
+        );
+        put(
+            NUMBER_OF_PERIOD_OFFSET,
+            &self.base_fee.number_of_period.to_le_bytes(),
+        );
+        put(
+            PERIOD_FREQUENCY_OFFSET,
+            &self.base_fee.period_frequency.to_le_bytes(),
+        );
+        put(
+            REDUCTION_FACTOR_OFFSET,
+            &self.base_fee.reduction_factor.to_le_bytes(),
+        );
+        put(
+            DYNAMIC_FEE_INITIALIZED_OFFSET,
+            &[self.dynamic_fee.initialized as u8],
+        );
+        put(
+            MAX_VOLATILITY_ACCUMULATOR_OFFSET,
+            &self.dynamic_fee.max_volatility_accumulator.to_le_bytes(),
+        );
+        put(
+            VARIABLE_FEE_CONTROL_OFFSET,
+            &self.dynamic_fee.variable_fee_control.to_le_bytes(),
+        );
+        put(BIN_STEP_OFFSET, &self.dynamic_fee.bin_step.to_le_bytes());
+        put(
+            VOLATILITY_ACCUMULATOR_OFFSET,
+            &self.dynamic_fee.volatility_accumulator.to_le_bytes(),
+        );
+        put(TOKEN_A_MINT_OFFSET, self.token_a_mint.as_ref());
+        put(TOKEN_B_MINT_OFFSET, self.token_b_mint.as_ref());
+        put(TOKEN_A_VAULT_OFFSET, self.token_a_vault.as_ref());
+        put(TOKEN_B_VAULT_OFFSET, self.token_b_vault.as_ref());
+        put(LIQUIDITY_OFFSET, &self.liquidity.to_le_bytes());
+        put(SQRT_MIN_PRICE_OFFSET, &self.sqrt_min_price.to_le_bytes());
+        put(SQRT_MAX_PRICE_OFFSET, &self.sqrt_max_price.to_le_bytes());
+        put(SQRT_PRICE_OFFSET, &self.sqrt_price.to_le_bytes());
+        put(
+            ACTIVATION_POINT_OFFSET,
+            &self.activation_point.to_le_bytes(),
+        );
+        put(ACTIVATION_TYPE_OFFSET, &[self.activation_type as u8]);
+        put(POOL_STATUS_OFFSET, &[self.pool_status]);
+        put(TOKEN_A_FLAG_OFFSET, &[self.token_a_flag]);
+        put(TOKEN_B_FLAG_OFFSET, &[self.token_b_flag]);
+        put(COLLECT_FEE_MODE_OFFSET, &[self.collect_fee_mode as u8]);
+        put(CREATOR_OFFSET, self.creator.as_ref());
+        data
+    }
+
+    /// Числитель суммарной комиссии (базовая + динамическая, не выше [`MAX_FEE_NUMERATOR`])
+    pub fn fee_numerator(&self, current_point: u64) -> u64 {
+        self.base_fee
+            .fee_numerator(current_point, self.activation_point)
+            .saturating_add(self.dynamic_fee.fee_numerator())
+            .min(MAX_FEE_NUMERATOR)
+    }
+
+    /// Котировка свапа с учётом комиссии
+    ///
+    /// # Аргументы
+    /// * `amount_in` — количество входных токенов
+    /// * `a_to_b` — вход — токен A
+    /// * `current_point` — текущий слот или время в единицах `activation_type`
+    ///
+    /// # Возвращает
+    /// Ошибку, если торговля отключена, пул ещё не активирован или не хватает ликвидности
+    pub fn quote(&self, amount_in: u64, a_to_b: bool, current_point: u64) -> Result<Quote> {
+        if self.pool_status != 0 {
+            bail!("Торговля в пуле DAMM v2 отключена");
+        }
+        if current_point < self.activation_point {
+            bail!(
+                "Пул DAMM v2 активируется в {} ({:?}), сейчас {}",
+                self.activation_point,
+                self.activation_type,
+                current_point
+            );
+        }
+
+        let fee_numerator = self.fee_numerator(current_point);
+        let fee_of = |amount: u64| {
+            let fee = (amount as u128 * fee_numerator as u128).div_ceil(FEE_DENOMINATOR as u128);
+            fee as u64
+        };
+
+        let fees_on_input = self.collect_fee_mode == CollectFeeMode::OnlyB && !a_to_b;
+        let (amount_out, fee) = if fees_on_input {
+            let fee = fee_of(amount_in);
+            (self.swap_output(amount_in - fee, a_to_b)?, fee)
+        } else {
+            let gross = self.swap_output(amount_in, a_to_b)?;
+            let fee = fee_of(gross);
+            (gross - fee, fee)
+        };
+
+        Ok(Quote {
+            amount_out,
+            fee,
+            fee_numerator,
+        })
+    }
+
+    /// Выход свапа без комиссии на кривой постоянного произведения
+    /// с концентрированной ликвидностью в диапазоне `[sqrt_min_price, sqrt_max_price]`
+    fn swap_output(&self, amount_in: u64, a_to_b: bool) -> Result<u64> {
+        if self.liquidity == 0 || self.sqrt_price == 0 {
+            bail!("В пуле DAMM v2 нет ликвидности");
+        }
+        let liquidity = BigUint::from(self.liquidity);
+        let sqrt_price = BigUint::from(self.sqrt_price);
+        let amount = BigUint::from(amount_in);
+
+        let out = if a_to_b {
+            // Цена падает: √P' = L·√P / (L + Δa·√P), с округлением вверх
+            let product = &liquidity * &sqrt_price;
+            let denominator = &liquidity + &amount * &sqrt_price;
+            let next = (product + &denominator - 1u8) / &denominator;
+            if next < BigUint::from(self.sqrt_min_price) {
+                bail!("Недостаточно ликвидности в пуле DAMM v2");
+            }
+            // Δb = L·(√P - √P') в Q128
+            (&liquidity * (&sqrt_price - &next)) >> 128u32
+        } else {
+            // Цена растёт: √P' = √P + Δb / L
+            let next = &sqrt_price + (&amount << 128u32) / &liquidity;
+            if next > BigUint::from(self.sqrt_max_price) {
+                bail!("Недостаточно ликвидности в пуле DAMM v2");
+            }
+            // Δa = L·(√P' - √P) / (√P·√P')
+            (&liquidity * (&next - &sqrt_price)) / (&sqrt_price * &next)
+        };
+        u64::try_from(out).map_err(|_| anyhow!("Выход свапа DAMM v2 не помещается в u64"))
+    }
+}
+
+/// Минимальный выход с допуском `slippage_bps` от котировки
+pub fn min_out_with_slippage(amount_out: u64, slippage_bps: u64) -> u64 {
+    let keep = BASIS_POINT_MAX.saturating_sub(slippage_bps) as u128;
+    (amount_out as u128 * keep / BASIS_POINT_MAX as u128) as u64
+}
+
+/// Состояние пула и текущая точка активации для свапа в одну сторону
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DammV2Swap {
+    pub program: Pubkey,
+    pub pool: Pubkey,
+    pub state: Pool,
+    /// Вход — токен A
+    pub a_to_b: bool,
+    /// Слот или время из `Clock` — в единицах `state.activation_type`
+    pub current_point: u64,
+}
+
+impl DammV2Swap {
+    /// Загружает пул из `accounts.pool` и `Clock` одним запросом; направление —
+    /// по хранилищу `accounts.pool_source`
+    ///
+    /// # Возвращает
+    /// Ошибку, если пул не DAMM v2 или хранилища не совпадают с пулом
+    pub async fn fetch(rpc: &RpcPool, accounts: &SwapAccounts) -> Result<Self> {
+        let program = program_id()?;
+        let pool = accounts.pool;
+        let keys = &[pool, sysvar::clock::id()];
+        let fetched = rpc
+            .read(|client| async move { client.get_multiple_accounts(keys).await })
+            .await
+            .with_context(|| format!("Ошибка загрузки пула DAMM v2 {}", pool))?;
+
+        let account = |i: usize| fetched.get(i).and_then(Option::as_ref);
+        let pool_account = account(0).with_context(|| format!("Пул {} не найден", pool))?;
+        if pool_account.owner != program {
+            bail!("Пул {} не принадлежит программе DAMM v2", pool);
+        }
+        let state = Pool::unpack(&pool_account.data)?;
+        let clock: Clock = bincode::deserialize(&account(1).context("Нет sysvar Clock")?.data)?;
+        let current_point = match state.activation_type {
+            ActivationType::Slot => clock.slot,
+            ActivationType::Timestamp => clock.unix_timestamp.max(0) as u64,
+        };
+
+        let a_to_b = if accounts.pool_source == state.token_a_vault {
+            true
+        } else if accounts.pool_source == state.token_b_vault {
+            false
+        } else {
+            bail!(
+                "Аккаунт {} не является хранилищем пула DAMM v2 {}",
+                accounts.pool_source,
+                pool
+            );
+        };
+
+        Ok(Self {
+            program,
+            pool,
+            state,
+            a_to_b,
+            current_point,
+        })
+    }
+
+    pub fn quote(&self, amount_in: u64) -> Result<Quote> {
+        self.state.quote(amount_in, self.a_to_b, self.current_point)
+    }
+
+    /// Mint и токен-программа входного и выходного токена
+    fn sides(&self) -> Result<((Pubkey, Pubkey), (Pubkey, Pubkey))> {
+        let a = (
+            self.state.token_a_mint,
+            token_program_for_flag(self.state.token_a_flag)?,
+        );
+        let b = (
+            self.state.token_b_mint,
+            token_program_for_flag(self.state.token_b_flag)?,
+        );
+        Ok(if self.a_to_b { (a, b) } else { (b, a) })
+    }
+
+    /// Инструкции свапа: создание выходного ATA, если он не задан, и `swap`
+    ///
+    /// Свап предварительно котируется: неактивный пул или нехватка ликвидности —
+    /// ошибка, а при заданном `DAMM_V2_SLIPPAGE_BPS` минимум выхода поднимается до котировки
+    /// с допуском.
+    ///
+    /// # Аргументы
+    /// * `payer` — владелец пользовательских аккаунтов и подписант
+    /// * `accounts` — нулевой `user_source`/`user_destination` заменяется ATA `payer`
+    /// * `amount_in` — количество входных токенов
+    /// * `min_out` — минимальное количество выходных токенов
+    pub fn instructions(
+        &self,
+        payer: &Pubkey,
+        accounts: &SwapAccounts,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<Vec<Instruction>> {
+        let quote = self.quote(amount_in)?;
+        let min_out = match DAMM_V2_SLIPPAGE_BPS {
+            Some(bps) => min_out.max(min_out_with_slippage(quote.amount_out, bps)),
+            None => min_out,
+        };
+
+        let (input, output) = self.sides()?;
+        let (user_token_in, user_token_out, mut ixs) =
+            resolve_user_token_accounts(payer, accounts, input, output)?;
+        ixs.push(self.swap_instruction(
+            payer,
+            &user_token_in,
+            &user_token_out,
+            amount_in,
+            min_out,
+        )?);
+        Ok(ixs)
+    }
+
+    /// Инструкция `swap(amount_in: u64, minimum_amount_out: u64)` с полным списком аккаунтов
+    pub fn swap_instruction(
+        &self,
+        user: &Pubkey,
+        user_token_in: &Pubkey,
+        user_token_out: &Pubkey,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<Instruction> {
+        let accounts = vec![
+            AccountMeta::new_readonly(pool_authority_address(&self.program), false),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(*user_token_in, false),
+            AccountMeta::new(*user_token_out, false),
+            AccountMeta::new(self.state.token_a_vault, false),
+            AccountMeta::new(self.state.token_b_vault, false),
+            AccountMeta::new_readonly(self.state.token_a_mint, false),
+            AccountMeta::new_readonly(self.state.token_b_mint, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new_readonly(token_program_for_flag(self.state.token_a_flag)?, false),
+            AccountMeta::new_readonly(token_program_for_flag(self.state.token_b_flag)?, false),
+            // Реферального аккаунта нет — Anchor принимает адрес программы
+            AccountMeta::new_readonly(self.program, false),
+            AccountMeta::new_readonly(event_authority_address(&self.program), false),
+            AccountMeta::new_readonly(self.program, false),
+        ];
+
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+
+        Ok(Instruction {
+            program_id: self.program,
+            accounts,
+            data,
+        })
+    }
+}
@@ -0,0 +1,440 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::config::DBC_PROGRAM_ID;
+use crate::rpc::RpcPool;
+use crate::swap::{resolve_user_token_accounts, PoolProgram, SwapAccounts};
+
+/// Дискриминаторы Anchor, как в [`crate::dlmm`]
+pub const CREATE_CONFIG_DISCRIMINATOR: [u8; 8] = [201, 207, 243, 114, 75, 111, 47, 189];
+pub const INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR: [u8; 8] =
+    [140, 85, 215, 176, 102, 54, 104, 79];
+pub const INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR: [u8; 8] =
+    [169, 118, 51, 78, 145, 110, 220, 155];
+pub const MIGRATION_DAMM_V2_DISCRIMINATOR: [u8; 8] = [156, 169, 230, 103, 53, 228, 80, 64];
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const VIRTUAL_POOL_DISCRIMINATOR: [u8; 8] = [213, 224, 5, 209, 98, 69, 119, 92];
+
+/// Аккаунтов `create_config`, нужных для разбора: config, fee_claimer, leftover_receiver,
+/// quote mint, payer
+pub const CREATE_CONFIG_ACCOUNTS: usize = 5;
+/// Аккаунтов `migration_damm_v2` до токен-программы quote включительно
+pub const MIGRATION_DAMM_V2_ACCOUNTS: usize = 22;
+
+/// Смещения полей в аккаунте `VirtualPool` (после дискриминатора идут
+/// 64 байта трекера волатильности)
+const CONFIG_OFFSET: usize = 72;
+const CREATOR_OFFSET: usize = 104;
+const BASE_MINT_OFFSET: usize = 136;
+const BASE_VAULT_OFFSET: usize = 168;
+const QUOTE_VAULT_OFFSET: usize = 200;
+const BASE_RESERVE_OFFSET: usize = 232;
+const QUOTE_RESERVE_OFFSET: usize = 240;
+const SQRT_PRICE_OFFSET: usize = 280;
+const ACTIVATION_POINT_OFFSET: usize = 296;
+const POOL_TYPE_OFFSET: usize = 304;
+const IS_MIGRATED_OFFSET: usize = 305;
+/// Размер аккаунта `VirtualPool`
+pub const VIRTUAL_POOL_LEN: usize = 424;
+
+/// Адрес программы Dynamic Bonding Curve из config.rs
+pub fn program_id() -> Result<Pubkey> {
+    Ok(Pubkey::from_str(DBC_PROGRAM_ID)?)
+}
+
+/// Единый для всех кривых владелец хранилищ
+pub fn pool_authority_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_authority"], program).0
+}
+
+pub fn event_authority_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program).0
+}
+
+/// Создание конфига кривых (параметры лаунчпада), найденное в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveConfigInit {
+    pub config: Pubkey,
+    pub fee_claimer: Pubkey,
+    pub quote_mint: Pubkey,
+    pub payer: Pubkey,
+}
+
+impl CurveConfigInit {
+    /// Разбирает `create_config`
+    ///
+    /// # Аргументы
+    /// * `accounts` — аккаунты инструкции, не меньше [`CREATE_CONFIG_ACCOUNTS`]
+    /// * `data` — данные инструкции вместе с дискриминатором
+    pub fn parse(accounts: &[Pubkey], data: &[u8]) -> Option<Self> {
+        if !data.starts_with(&CREATE_CONFIG_DISCRIMINATOR)
+            || accounts.len() < CREATE_CONFIG_ACCOUNTS
+        {
+            return None;
+        }
+        Some(Self {
+            config: accounts[0],
+            fee_claimer: accounts[1],
+            quote_mint: accounts[3],
+            payer: accounts[4],
+        })
+    }
+}
+
+/// Создание кривой (виртуального пула), найденное в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurveInit {
+    pub pool: Pubkey,
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub pool_authority: Pubkey,
+    pub base_token_program: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl CurveInit {
+    /// Разбирает `initialize_virtual_pool_with_spl_token` и `..._with_token2022`:
+    /// у SPL-варианта между хранилищами и payer идут аккаунты метаданных
+    ///
+    /// # Аргументы
+    /// * `accounts` — аккаунты инструкции
+    /// * `data` — данные инструкции вместе с дискриминатором
+    pub fn parse(accounts: &[Pubkey], data: &[u8]) -> Option<Self> {
+        // Индекс токен-программы quote; за ней идёт токен-программа base
+        let quote_program =
+            if data.starts_with(&INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR) {
+                11
+            } else if data.starts_with(&INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR) {
+                9
+            } else {
+                return None;
+            };
+        if accounts.len() <= quote_program + 1 {
+            return None;
+        }
+        Some(Self {
+            config: accounts[0],
+            pool_authority: accounts[1],
+            creator: accounts[2],
+            base_mint: accounts[3],
+            quote_mint: accounts[4],
+            pool: accounts[5],
+            base_vault: accounts[6],
+            quote_vault: accounts[7],
+            quote_token_program: accounts[quote_program],
+            base_token_program: accounts[quote_program + 1],
+        })
+    }
+
+    /// Аккаунты свапа base → quote; пользовательские аккаунты — ATA подписанта
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            program: PoolProgram::Dbc,
+            pool: self.pool,
+            user_source: Pubkey::default(),
+            user_destination: Pubkey::default(),
+            pool_source: self.base_vault,
+            pool_destination: self.quote_vault,
+            pool_authority: self.pool_authority,
+            token_program: self.base_token_program,
+        }
+    }
+}
+
+/// Миграция завершённой кривой в пул DAMM v2, найденная в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationInit {
+    /// Виртуальный пул кривой
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// Созданный пул DAMM v2: токен A — base, токен B — quote
+    pub pool: Pubkey,
+    /// Владелец хранилищ DAMM v2
+    pub pool_authority: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    /// Кто запустил миграцию — обычно сервис Meteora, а не создатель кривой
+    pub payer: Pubkey,
+    pub base_token_program: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl MigrationInit {
+    /// Разбирает `migration_damm_v2`
+    ///
+    /// # Аргументы
+    /// * `accounts` — аккаунты инструкции, не меньше [`MIGRATION_DAMM_V2_ACCOUNTS`]
+    /// * `data` — данные инструкции вместе с дискриминатором
+    pub fn parse(accounts: &[Pubkey], data: &[u8]) -> Option<Self> {
+        if !data.starts_with(&MIGRATION_DAMM_V2_DISCRIMINATOR)
+            || accounts.len() < MIGRATION_DAMM_V2_ACCOUNTS
+        {
+            return None;
+        }
+        Some(Self {
+            curve: accounts[0],
+            config: accounts[2],
+            pool: accounts[4],
+            pool_authority: accounts[11],
+            base_mint: accounts[13],
+            quote_mint: accounts[14],
+            token_a_vault: accounts[15],
+            token_b_vault: accounts[16],
+            payer: accounts[19],
+            base_token_program: accounts[20],
+            quote_token_program: accounts[21],
+        })
+    }
+
+    /// Аккаунты свапа A → B в новом пуле DAMM v2
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            program: PoolProgram::DammV2,
+            pool: self.pool,
+            user_source: Pubkey::default(),
+            user_destination: Pubkey::default(),
+            pool_source: self.token_a_vault,
+            pool_destination: self.token_b_vault,
+            pool_authority: self.pool_authority,
+            token_program: self.base_token_program,
+        }
+    }
+}
+
+/// Поля аккаунта `VirtualPool`, нужные для свапа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VirtualPool {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_reserve: u64,
+    pub quote_reserve: u64,
+    /// Корень цены в формате Q64.64
+    pub sqrt_price: u128,
+    pub activation_point: u64,
+    /// 0 — base в SPL Token, 1 — в Token-2022
+    pub pool_type: u8,
+    pub is_migrated: bool,
+}
+
+impl VirtualPool {
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        if data.len() < VIRTUAL_POOL_LEN || !data.starts_with(&VIRTUAL_POOL_DISCRIMINATOR) {
+            bail!("Аккаунт не является кривой DBC");
+        }
+        let pubkey = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            config: pubkey(CONFIG_OFFSET),
+            creator: pubkey(CREATOR_OFFSET),
+            base_mint: pubkey(BASE_MINT_OFFSET),
+            base_vault: pubkey(BASE_VAULT_OFFSET),
+            quote_vault: pubkey(QUOTE_VAULT_OFFSET),
+            base_reserve: u64_at(BASE_RESERVE_OFFSET),
+            quote_reserve: u64_at(QUOTE_RESERVE_OFFSET),
+            sqrt_price: u128::from_le_bytes(
+                data[SQRT_PRICE_OFFSET..SQRT_PRICE_OFFSET + 16].try_into()?,
+            ),
+            activation_point: u64_at(ACTIVATION_POINT_OFFSET),
+            pool_type: data[POOL_TYPE_OFFSET],
+            is_migrated: data[IS_MIGRATED_OFFSET] != 0,
+        })
+    }
+
+    /// Сериализует поля обратно в аккаунт `VirtualPool`; остальные байты нулевые
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; VIRTUAL_POOL_LEN];
+        let mut put =
+            |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        put(0, &VIRTUAL_POOL_DISCRIMINATOR);
+        put(CONFIG_OFFSET, self.config.as_ref());
+        put(CREATOR_OFFSET, self.creator.as_ref());
+        put(BASE_MINT_OFFSET, self.base_mint.as_ref());
+        put(BASE_VAULT_OFFSET, self.base_vault.as_ref());
+        put(QUOTE_VAULT_OFFSET, self.quote_vault.as_ref());
+        put(BASE_RESERVE_OFFSET, &self.base_reserve.to_le_bytes());
+        put(QUOTE_RESERVE_OFFSET, &self.quote_reserve.to_le_bytes());
+        put(SQRT_PRICE_OFFSET, &self.sqrt_price.to_le_bytes());
+        put(
+            ACTIVATION_POINT_OFFSET,
+            &self.activation_point.to_le_bytes(),
+        );
+        put(POOL_TYPE_OFFSET, &[self.pool_type]);
+        put(IS_MIGRATED_OFFSET, &[self.is_migrated as u8]);
+        data
+    }
+}
+
+/// Состояние кривой и аккаунты, нужные инструкции `swap` в одну сторону
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbcSwap {
+    pub program: Pubkey,
+    pub pool: Pubkey,
+    pub state: VirtualPool,
+    /// Вход — quote (покупка на кривой)
+    pub quote_to_base: bool,
+    pub quote_mint: Pubkey,
+    pub base_token_program: Pubkey,
+    pub quote_token_program: Pubkey,
+}
+
+impl DbcSwap {
+    /// Загружает кривую и оба её хранилища одним запросом: mint quote и
+    /// токен-программы берутся из хранилищ, направление — по `accounts.pool_source`
+    ///
+    /// # Возвращает
+    /// Ошибку, если пул не кривая DBC, хранилища не совпадают или кривая уже мигрировала
+    pub async fn fetch(rpc: &RpcPool, accounts: &SwapAccounts) -> Result<Self> {
+        let program = program_id()?;
+        let pool = accounts.pool;
+        let keys = &[pool, accounts.pool_source, accounts.pool_destination];
+        let fetched = rpc
+            .read(|client| async move { client.get_multiple_accounts(keys).await })
+            .await
+            .with_context(|| format!("Ошибка загрузки кривой DBC {}", pool))?;
+        let account = |i: usize| fetched.get(i).and_then(Option::as_ref);
+
+        let pool_account = account(0).with_context(|| format!("Кривая {} не найдена", pool))?;
+        if pool_account.owner != program {
+            bail!("Кривая {} не принадлежит программе DBC", pool);
+        }
+        let state = VirtualPool::unpack(&pool_account.data)?;
+        if state.is_migrated {
+            bail!("Кривая {} завершена и мигрировала в пул", pool);
+        }
+
+        let quote_to_base = if accounts.pool_source == state.quote_vault {
+            true
+        } else if accounts.pool_source == state.base_vault {
+            false
+        } else {
+            bail!(
+                "Аккаунт {} не является хранилищем кривой {}",
+                accounts.pool_source,
+                pool
+            );
+        };
+        let output_vault = if quote_to_base {
+            state.base_vault
+        } else {
+            state.quote_vault
+        };
+        if accounts.pool_destination != output_vault {
+            bail!(
+                "Аккаунт {} не является хранилищем кривой {}",
+                accounts.pool_destination,
+                pool
+            );
+        }
+
+        // Токен-аккаунт хранилища: mint — первые 32 байта, владелец — токен-программа
+        let vault = |index: usize| -> Result<(Pubkey, Pubkey)> {
+            let vault =
+                account(index).with_context(|| format!("Хранилище {} не найдено", keys[index]))?;
+            let mint = vault
+                .data
+                .get(..32)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+                .with_context(|| format!("Аккаунт {} не является токен-аккаунтом", keys[index]))?;
+            Ok((mint, vault.owner))
+        };
+        let (quote_index, base_index) = if quote_to_base { (1, 2) } else { (2, 1) };
+        let (quote_mint, quote_token_program) = vault(quote_index)?;
+        let (_, base_token_program) = vault(base_index)?;
+
+        Ok(Self {
+            program,
+            pool,
+            state,
+            quote_to_base,
+            quote_mint,
+            base_token_program,
+            quote_token_program,
+        })
+    }
+
+    /// Mint и токен-программа входного и выходного токена
+    fn sides(&self) -> ((Pubkey, Pubkey), (Pubkey, Pubkey)) {
+        let base = (self.state.base_mint, self.base_token_program);
+        let quote = (self.quote_mint, self.quote_token_program);
+        if self.quote_to_base {
+            (quote, base)
+        } else {
+            (base, quote)
+        }
+    }
+
+    /// Инструкции свапа: создание выходного ATA, если он не задан, и `swap`
+    ///
+    /// # Аргументы
+    /// * `payer` — владелец пользовательских аккаунтов и подписант
+    /// * `accounts` — нулевой `user_source`/`user_destination` заменяется ATA `payer`
+    /// * `amount_in` — количество входных токенов
+    /// * `min_out` — минимальное количество выходных токенов
+    pub fn instructions(
+        &self,
+        payer: &Pubkey,
+        accounts: &SwapAccounts,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<Vec<Instruction>> {
+        let (input, output) = self.sides();
+        let (user_token_in, user_token_out, mut ixs) =
+            resolve_user_token_accounts(payer, accounts, input, output)?;
+        ixs.push(self.swap_instruction(payer, &user_token_in, &user_token_out, amount_in, min_out));
+        Ok(ixs)
+    }
+
+    /// Инструкция `swap(amount_in: u64, minimum_amount_out: u64)` с полным списком аккаунтов
+    pub fn swap_instruction(
+        &self,
+        user: &Pubkey,
+        user_token_in: &Pubkey,
+        user_token_out: &Pubkey,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(pool_authority_address(&self.program), false),
+            AccountMeta::new_readonly(self.state.config, false),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new(*user_token_in, false),
+            AccountMeta::new(*user_token_out, false),
+            AccountMeta::new(self.state.base_vault, false),
+            AccountMeta::new(self.state.quote_vault, false),
+            AccountMeta::new_readonly(self.state.base_mint, false),
+            AccountMeta::new_readonly(self.quote_mint, false),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new_readonly(self.base_token_program, false),
+            AccountMeta::new_readonly(self.quote_token_program, false),
+            // Реферального аккаунта нет — Anchor принимает адрес программы
+            AccountMeta::new_readonly(self.program, false),
+            AccountMeta::new_readonly(event_authority_address(&self.program), false),
+            AccountMeta::new_readonly(self.program, false),
+        ];
+
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+
+        Instruction {
+            program_id: self.program,
+            accounts,
+            data,
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::config::{DLMM_PROGRAM_ID, DLMM_SWAP_BIN_ARRAYS};
+use crate::rpc::RpcPool;
+use crate::swap::{resolve_user_token_accounts, PoolProgram, SwapAccounts};
+
+/// Дискриминаторы Anchor: первые 8 байт sha256 от `global:<инструкция>` и `account:<тип>`
+pub const INITIALIZE_LB_PAIR_DISCRIMINATOR: [u8; 8] = [45, 154, 237, 210, 221, 15, 166, 92];
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+pub const LB_PAIR_DISCRIMINATOR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+
+/// Бинов в одном bin array
+pub const MAX_BIN_PER_ARRAY: i64 = 70;
+
+/// Аккаунтов `initialize_lb_pair`, нужных для покупки: lb_pair, bitmap extension,
+/// mint X, mint Y, reserve X, reserve Y, oracle, preset, funder, token program
+pub const INITIALIZE_LB_PAIR_ACCOUNTS: usize = 10;
+
+/// Смещения полей в аккаунте `LbPair` (после дискриминатора идут 32 байта
+/// статических и 32 байта переменных параметров комиссии)
+const ACTIVE_ID_OFFSET: usize = 76;
+const BIN_STEP_OFFSET: usize = 80;
+const TOKEN_X_MINT_OFFSET: usize = 88;
+const TOKEN_Y_MINT_OFFSET: usize = 120;
+const RESERVE_X_OFFSET: usize = 152;
+const RESERVE_Y_OFFSET: usize = 184;
+const ORACLE_OFFSET: usize = 552;
+/// Размер аккаунта `LbPair`
+pub const LB_PAIR_LEN: usize = 904;
+
+/// Адрес программы DLMM из config.rs
+pub fn program_id() -> Result<Pubkey> {
+    Ok(Pubkey::from_str(DLMM_PROGRAM_ID)?)
+}
+
+pub fn is_initialize_lb_pair(data: &[u8]) -> bool {
+    data.starts_with(&INITIALIZE_LB_PAIR_DISCRIMINATOR)
+}
+
+/// Создание lb pair, найденное в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LbPairInit {
+    pub lb_pair: Pubkey,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+    pub funder: Pubkey,
+    pub token_program: Pubkey,
+    pub active_id: i32,
+    pub bin_step: u16,
+}
+
+impl LbPairInit {
+    /// Разбирает `initialize_lb_pair(active_id: i32, bin_step: u16)`
+    ///
+    /// # Аргументы
+    /// * `accounts` — аккаунты инструкции, не меньше [`INITIALIZE_LB_PAIR_ACCOUNTS`]
+    /// * `data` — данные инструкции вместе с дискриминатором
+    pub fn parse(accounts: &[Pubkey], data: &[u8]) -> Option<Self> {
+        if !is_initialize_lb_pair(data) || accounts.len() < INITIALIZE_LB_PAIR_ACCOUNTS {
+            return None;
+        }
+        let args = &data[INITIALIZE_LB_PAIR_DISCRIMINATOR.len()..];
+        Some(Self {
+            lb_pair: accounts[0],
+            token_x_mint: accounts[2],
+            token_y_mint: accounts[3],
+            reserve_x: accounts[4],
+            reserve_y: accounts[5],
+            oracle: accounts[6],
+            funder: accounts[8],
+            token_program: accounts[9],
+            active_id: i32::from_le_bytes(args.get(0..4)?.try_into().ok()?),
+            bin_step: u16::from_le_bytes(args.get(4..6)?.try_into().ok()?),
+        })
+    }
+
+    /// Аккаунты свапа X → Y; пользовательские аккаунты — ATA подписанта
+    pub fn swap_accounts(&self) -> SwapAccounts {
+        SwapAccounts {
+            program: PoolProgram::Dlmm,
+            pool: self.lb_pair,
+            user_source: Pubkey::default(),
+            user_destination: Pubkey::default(),
+            pool_source: self.reserve_x,
+            pool_destination: self.reserve_y,
+            pool_authority: self.lb_pair,
+            token_program: self.token_program,
+        }
+    }
+}
+
+/// Поля аккаунта `LbPair`, нужные для свапа
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LbPair {
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub token_x_mint: Pubkey,
+    pub token_y_mint: Pubkey,
+    pub reserve_x: Pubkey,
+    pub reserve_y: Pubkey,
+    pub oracle: Pubkey,
+}
+
+impl LbPair {
+    pub fn unpack(data: &[u8]) -> Result<Self> {
+        if data.len() < LB_PAIR_LEN || !data.starts_with(&LB_PAIR_DISCRIMINATOR) {
+            bail!("Аккаунт не является lb pair DLMM");
+        }
+        let pubkey = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+        Ok(Self {
+            active_id: i32::from_le_bytes(data[ACTIVE_ID_OFFSET..ACTIVE_ID_OFFSET + 4].try_into()?),
+            bin_step: u16::from_le_bytes(data[BIN_STEP_OFFSET..BIN_STEP_OFFSET + 2].try_into()?),
+            token_x_mint: pubkey(TOKEN_X_MINT_OFFSET),
+            token_y_mint: pubkey(TOKEN_Y_MINT_OFFSET),
+            reserve_x: pubkey(RESERVE_X_OFFSET),
+            reserve_y: pubkey(RESERVE_Y_OFFSET),
+            oracle: pubkey(ORACLE_OFFSET),
+        })
+    }
+
+    /// Сериализует поля обратно в аккаунт `LbPair`; остальные байты нулевые
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![0; LB_PAIR_LEN];
+        data[..8].copy_from_slice(&LB_PAIR_DISCRIMINATOR);
+        data[ACTIVE_ID_OFFSET..ACTIVE_ID_OFFSET + 4].copy_from_slice(&self.active_id.to_le_bytes());
+        data[BIN_STEP_OFFSET..BIN_STEP_OFFSET + 2].copy_from_slice(&self.bin_step.to_le_bytes());
+        for (offset, key) in [
+            (TOKEN_X_MINT_OFFSET, &self.token_x_mint),
+            (TOKEN_Y_MINT_OFFSET, &self.token_y_mint),
+            (RESERVE_X_OFFSET, &self.reserve_x),
+            (RESERVE_Y_OFFSET, &self.reserve_y),
+            (ORACLE_OFFSET, &self.oracle),
+        ] {
+            data[offset..offset + 32].copy_from_slice(key.as_ref());
+        }
+        data
+    }
+}
+
+/// Индекс bin array, в котором лежит бин `bin_id` (деление с округлением вниз)
+pub fn bin_array_index(bin_id: i32) -> i64 {
+    (bin_id as i64).div_euclid(MAX_BIN_PER_ARRAY)
+}
+
+pub fn bin_array_address(lb_pair: &Pubkey, index: i64, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bin_array", lb_pair.as_ref(), &index.to_le_bytes()],
+        program,
+    )
+    .0
+}
+
+/// Расширение битовой карты bin array — нужно пулам с бинами далеко от нуля
+pub fn bitmap_extension_address(lb_pair: &Pubkey, program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"bitmap", lb_pair.as_ref()], program).0
+}
+
+pub fn event_authority_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program).0
+}
+
+/// Bin array, через которые может пройти свап, начиная с активного.
+/// Свап X → Y сдвигает активный бин вниз, Y → X — вверх
+pub fn swap_bin_array_indexes(active_id: i32, swap_for_y: bool, count: usize) -> Vec<i64> {
+    let active = bin_array_index(active_id);
+    let step = if swap_for_y { -1 } else { 1 };
+    (0..count as i64).map(|i| active + i * step).collect()
+}
+
+/// Состояние lb pair и аккаунты, нужные инструкции `swap` в одну сторону
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlmmSwap {
+    pub program: Pubkey,
+    pub lb_pair: Pubkey,
+    pub state: LbPair,
+    /// Вход — токен X
+    pub swap_for_y: bool,
+    pub bitmap_extension: Option<Pubkey>,
+    pub token_x_program: Pubkey,
+    pub token_y_program: Pubkey,
+    /// Существующие bin array в сторону свапа, начиная с активного
+    pub bin_arrays: Vec<Pubkey>,
+}
+
+impl DlmmSwap {
+    /// Загружает lb pair из `accounts.pool` и bin array для направления
+    /// `accounts.pool_source` → `accounts.pool_destination`
+    ///
+    /// # Возвращает
+    /// Ошибку, если пул не DLMM, резервы не совпадают с пулом или нет bin array с активным бином
+    pub async fn fetch(rpc: &RpcPool, accounts: &SwapAccounts) -> Result<Self> {
+        let program = program_id()?;
+        let lb_pair = accounts.pool;
+        let data = rpc
+            .read(|client| async move { client.get_account_data(&lb_pair).await })
+            .await
+            .with_context(|| format!("Ошибка загрузки lb pair {}", lb_pair))?;
+        let state = LbPair::unpack(&data)?;
+
+        let swap_for_y = if accounts.pool_source == state.reserve_x {
+            true
+        } else if accounts.pool_source == state.reserve_y {
+            false
+        } else {
+            bail!(
+                "Аккаунт {} не является резервом lb pair {}",
+                accounts.pool_source,
+                lb_pair
+            );
+        };
+
+        let candidates: Vec<Pubkey> =
+            swap_bin_array_indexes(state.active_id, swap_for_y, DLMM_SWAP_BIN_ARRAYS)
+                .into_iter()
+                .map(|index| bin_array_address(&lb_pair, index, &program))
+                .collect();
+        let mut keys = vec![
+            bitmap_extension_address(&lb_pair, &program),
+            state.token_x_mint,
+            state.token_y_mint,
+        ];
+        keys.extend(&candidates);
+        let keys = &keys;
+        let fetched = rpc
+            .read(|client| async move { client.get_multiple_accounts(keys).await })
+            .await?;
+
+        let owner = |i: usize| fetched.get(i).and_then(Option::as_ref).map(|a| a.owner);
+        let bitmap_extension = (owner(0) == Some(program)).then_some(keys[0]);
+        let token_x_program = owner(1).context("Mint X lb pair не найден")?;
+        let token_y_program = owner(2).context("Mint Y lb pair не найден")?;
+
+        // Свап проходит только по созданным bin array подряд, начиная с активного
+        let bin_arrays: Vec<Pubkey> = candidates
+            .iter()
+            .enumerate()
+            .take_while(|(i, _)| owner(3 + i) == Some(program))
+            .map(|(_, key)| *key)
+            .collect();
+        if bin_arrays.is_empty() {
+            bail!(
+                "У lb pair {} нет bin array с активным бином {}",
+                lb_pair,
+                state.active_id
+            );
+        }
+
+        Ok(Self {
+            program,
+            lb_pair,
+            state,
+            swap_for_y,
+            bitmap_extension,
+            token_x_program,
+            token_y_program,
+            bin_arrays,
+        })
+    }
+
+    /// Mint и токен-программа входного и выходного токена
+    fn sides(&self) -> ((Pubkey, Pubkey), (Pubkey, Pubkey)) {
+        let x = (self.state.token_x_mint, self.token_x_program);
+        let y = (self.state.token_y_mint, self.token_y_program);
+        if self.swap_for_y {
+            (x, y)
+        } else {
+            (y, x)
+        }
+    }
+
+    /// Инструкции свапа: создание выходного ATA, если он не задан, и `swap`
+    ///
+    /// # Аргументы
+    /// * `payer` — владелец пользовательских аккаунтов и подписант
+    /// * `accounts` — нулевой `user_source`/`user_destination` заменяется ATA `payer`
+    /// * `amount_in` — количество входных токенов
+    /// * `min_out` — минимальное количество выходных токенов
+    pub fn instructions(
+        &self,
+        payer: &Pubkey,
+        accounts: &SwapAccounts,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Result<Vec<Instruction>> {
+        let (input, output) = self.sides();
+        let (user_token_in, user_token_out, mut ixs) =
+            resolve_user_token_accounts(payer, accounts, input, output)?;
+        ixs.push(self.swap_instruction(payer, &user_token_in, &user_token_out, amount_in, min_out));
+        Ok(ixs)
+    }
+
+    /// Инструкция `swap(amount_in: u64, min_amount_out: u64)` с полным списком аккаунтов
+    pub fn swap_instruction(
+        &self,
+        user: &Pubkey,
+        user_token_in: &Pubkey,
+        user_token_out: &Pubkey,
+        amount_in: u64,
+        min_out: u64,
+    ) -> Instruction {
+        // Отсутствующие необязательные аккаунты Anchor передаются адресом программы
+        let optional = |key: Option<Pubkey>| key.unwrap_or(self.program);
+
+        let mut accounts = vec![
+            AccountMeta::new(self.lb_pair, false),
+            AccountMeta::new_readonly(optional(self.bitmap_extension), false),
+            AccountMeta::new(self.state.reserve_x, false),
+            AccountMeta::new(self.state.reserve_y, false),
+            AccountMeta::new(*user_token_in, false),
+            AccountMeta::new(*user_token_out, false),
+            AccountMeta::new_readonly(self.state.token_x_mint, false),
+            AccountMeta::new_readonly(self.state.token_y_mint, false),
+            AccountMeta::new(self.state.oracle, false),
+            AccountMeta::new_readonly(optional(None), false), // host_fee_in
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new_readonly(self.token_x_program, false),
+            AccountMeta::new_readonly(self.token_y_program, false),
+            AccountMeta::new_readonly(event_authority_address(&self.program), false),
+            AccountMeta::new_readonly(self.program, false),
+        ];
+        accounts.extend(
+            self.bin_arrays
+                .iter()
+                .map(|key| AccountMeta::new(*key, false)),
+        );
+
+        let mut data = SWAP_DISCRIMINATOR.to_vec();
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+
+        Instruction {
+            program_id: self.program,
+            accounts,
+            data,
+        }
+    }
+}
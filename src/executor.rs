@@ -0,0 +1,144 @@
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use tokio::{
+    sync::{mpsc, Semaphore},
+    task::{JoinHandle, JoinSet},
+};
+
+use crate::geyser::PoolLaunch;
+
+/// Исполнитель покупок: принимает найденные запуски из детектора через ограниченную очередь
+/// и выполняет их параллельно, не более `workers` одновременно.
+///
+/// Детектор ждёт в [`Executor::submit`], только когда заняты все исполнители и очередь заполнена.
+/// Пул, который уже стоит в очереди или покупается, повторно не принимается.
+pub struct Executor {
+    sender: mpsc::Sender<(PoolLaunch, PendingGuard)>,
+    pending: Arc<Mutex<HashSet<Pubkey>>>,
+    dispatcher: JoinHandle<()>,
+}
+
+impl Executor {
+    /// Запускает диспетчер исполнителей
+    ///
+    /// # Аргументы
+    /// * `workers` — максимум одновременно выполняемых покупок
+    /// * `queue_capacity` — ёмкость очереди между детектором и исполнителями
+    /// * `buy` — покупка одного пула; ошибки обрабатываются внутри
+    pub fn spawn<F, Fut>(workers: usize, queue_capacity: usize, buy: F) -> Self
+    where
+        F: Fn(PoolLaunch) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (sender, mut receiver) =
+            mpsc::channel::<(PoolLaunch, PendingGuard)>(queue_capacity.max(1));
+        let pending = Arc::new(Mutex::new(HashSet::new()));
+        let semaphore = Arc::new(Semaphore::new(workers.max(1)));
+
+        let dispatcher = tokio::spawn(async move {
+            let mut tasks = JoinSet::new();
+
+            loop {
+                // Сначала ждём свободного исполнителя, и только потом забираем запуск из очереди:
+                // так очередь заполняется, когда все заняты, и детектор получает backpressure
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+                let Some((launch, guard)) = receiver.recv().await else {
+                    break;
+                };
+
+                // Пул снимается с учёта при сбросе guard — в том числе при панике покупки
+                let buy = buy(launch);
+                tasks.spawn(async move {
+                    let _guard = guard;
+                    buy.await;
+                    drop(permit);
+                });
+
+                while let Some(res) = tasks.try_join_next() {
+                    log_task_result(res);
+                }
+            }
+
+            // Очередь закрыта — дожидаемся начатых покупок
+            while let Some(res) = tasks.join_next().await {
+                log_task_result(res);
+            }
+        });
+
+        Self {
+            sender,
+            pending,
+            dispatcher,
+        }
+    }
+
+    /// Ставит запуск в очередь на покупку
+    ///
+    /// # Возвращает
+    /// `false`, если этот пул уже в очереди или покупается
+    pub async fn submit(&self, launch: PoolLaunch) -> Result<bool> {
+        let pool = launch.accounts.pool;
+        if !self.lock_pending()?.insert(pool) {
+            return Ok(false);
+        }
+
+        // Guard едет вместе с запуском: если ожидание места в очереди отменено
+        // или очередь закрыта, он сбрасывается и пул снова принимается
+        let guard = PendingGuard {
+            pending: self.pending.clone(),
+            pool,
+        };
+        if self.sender.send((launch, guard)).await.is_err() {
+            return Err(anyhow!("Исполнитель покупок остановлен"));
+        }
+        Ok(true)
+    }
+
+    /// Количество пулов, которые стоят в очереди или покупаются
+    pub fn in_flight(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or_default()
+    }
+
+    /// Закрывает очередь и ждёт завершения уже принятых покупок
+    pub async fn shutdown(self) {
+        drop(self.sender);
+        if let Err(e) = self.dispatcher.await {
+            eprintln!("Диспетчер покупок завершился с ошибкой: {:?}", e);
+        }
+    }
+
+    fn lock_pending(&self) -> Result<std::sync::MutexGuard<'_, HashSet<Pubkey>>> {
+        self.pending
+            .lock()
+            .map_err(|_| anyhow!("Список покупаемых пулов заблокирован после паники"))
+    }
+}
+
+/// Снимает пул с учёта принятых при сбросе
+struct PendingGuard {
+    pending: Arc<Mutex<HashSet<Pubkey>>>,
+    pool: Pubkey,
+}
+
+impl Drop for PendingGuard {
+    fn drop(&mut self) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.pool);
+    }
+}
+
+fn log_task_result(res: std::result::Result<(), tokio::task::JoinError>) {
+    if let Err(e) = res {
+        eprintln!("Задача покупки завершилась с ошибкой: {:?}", e);
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+use tokio::task::JoinHandle;
+
+use crate::config::{FILTER_DIR, TOKEN_METADATA_PROGRAM_ID};
+use crate::rpc::RpcPool;
+
+/// Что проверяется списком
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterKind {
+    /// Токен, который покупаем
+    Mint,
+    /// Создатель пула — первый подписант инструкции инициализации
+    Creator,
+    /// Плательщик комиссии транзакции инициализации
+    FeePayer,
+    /// Update authority метаданных токена (Metaplex)
+    UpdateAuthority,
+}
+
+impl FilterKind {
+    pub const ALL: [FilterKind; 4] = [
+        FilterKind::Mint,
+        FilterKind::Creator,
+        FilterKind::FeePayer,
+        FilterKind::UpdateAuthority,
+    ];
+
+    /// Префикс файлов списка: `<префикс>_allow.txt` и `<префикс>_deny.txt`
+    pub fn file_prefix(self) -> &'static str {
+        match self {
+            FilterKind::Mint => "mint",
+            FilterKind::Creator => "creator",
+            FilterKind::FeePayer => "fee_payer",
+            FilterKind::UpdateAuthority => "update_authority",
+        }
+    }
+}
+
+impl fmt::Display for FilterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterKind::Mint => write!(f, "токен"),
+            FilterKind::Creator => write!(f, "создатель пула"),
+            FilterKind::FeePayer => write!(f, "плательщик комиссии"),
+            FilterKind::UpdateAuthority => write!(f, "update authority"),
+        }
+    }
+}
+
+/// Причина, по которой запуск не прошёл фильтры
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilterRejection {
+    /// Адрес в чёрном списке
+    Denied { kind: FilterKind, key: Pubkey },
+    /// Белый список задан, а адреса в нём нет; `None` — адрес неизвестен
+    NotAllowed {
+        kind: FilterKind,
+        key: Option<Pubkey>,
+    },
+}
+
+impl fmt::Display for FilterRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterRejection::Denied { kind, key } => {
+                write!(f, "{} {} в чёрном списке", kind, key)
+            }
+            FilterRejection::NotAllowed {
+                kind,
+                key: Some(key),
+            } => write!(f, "{} {} не в белом списке", kind, key),
+            FilterRejection::NotAllowed { kind, key: None } => {
+                write!(f, "{} неизвестен, а белый список задан", kind)
+            }
+        }
+    }
+}
+
+/// Белые и чёрные списки адресов.
+///
+/// Чёрный список проверяется первым. Пустой белый список ничего не ограничивает,
+/// непустой пропускает только свои адреса.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterLists {
+    allow: HashMap<FilterKind, HashSet<Pubkey>>,
+    deny: HashMap<FilterKind, HashSet<Pubkey>>,
+}
+
+impl FilterLists {
+    /// Добавляет адреса в белый список
+    pub fn allow(mut self, kind: FilterKind, keys: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.allow.entry(kind).or_default().extend(keys);
+        self
+    }
+
+    /// Добавляет адреса в чёрный список
+    pub fn deny(mut self, kind: FilterKind, keys: impl IntoIterator<Item = Pubkey>) -> Self {
+        self.deny.entry(kind).or_default().extend(keys);
+        self
+    }
+
+    /// Загружает списки из каталога; отсутствующий файл — пустой список
+    ///
+    /// # Аргументы
+    /// * `dir` — каталог с файлами `<вид>_allow.txt` / `<вид>_deny.txt`:
+    ///   по адресу в строке, `#` начинает комментарий
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        Self::parse(&read_files(dir.as_ref())?)
+    }
+
+    fn parse(files: &ListFiles) -> Result<Self> {
+        let mut lists = Self::default();
+        for (kind, allow, deny) in files {
+            lists = lists
+                .allow(*kind, parse_list(allow)?)
+                .deny(*kind, parse_list(deny)?);
+        }
+        Ok(lists)
+    }
+
+    /// Задан ли для вида хотя бы один список
+    pub fn is_active(&self, kind: FilterKind) -> bool {
+        [&self.allow, &self.deny]
+            .iter()
+            .any(|lists| lists.get(&kind).is_some_and(|keys| !keys.is_empty()))
+    }
+
+    /// Проверяет адрес по спискам своего вида
+    pub fn check(&self, kind: FilterKind, key: &Pubkey) -> Result<(), FilterRejection> {
+        if self.deny.get(&kind).is_some_and(|keys| keys.contains(key)) {
+            return Err(FilterRejection::Denied { kind, key: *key });
+        }
+        match self.allow.get(&kind) {
+            Some(keys) if !keys.is_empty() && !keys.contains(key) => {
+                Err(FilterRejection::NotAllowed {
+                    kind,
+                    key: Some(*key),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Проверка для неизвестного адреса: проходит, только если белый список пуст
+    pub fn check_unknown(&self, kind: FilterKind) -> Result<(), FilterRejection> {
+        if self.allow.get(&kind).is_some_and(|keys| !keys.is_empty()) {
+            return Err(FilterRejection::NotAllowed { kind, key: None });
+        }
+        Ok(())
+    }
+}
+
+/// Размеры списков: `вид: белый/чёрный`
+impl fmt::Display for FilterLists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let len = |lists: &HashMap<FilterKind, HashSet<Pubkey>>, kind| {
+            lists.get(&kind).map_or(0, HashSet::len)
+        };
+        for (i, kind) in FilterKind::ALL.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(
+                f,
+                "{}: {}/{}",
+                kind.file_prefix(),
+                len(&self.allow, kind),
+                len(&self.deny, kind)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Путь и содержимое файла списка; `None` — файла нет
+type ListFile = (PathBuf, Option<String>);
+/// Файлы белого и чёрного списков каждого вида
+type ListFiles = Vec<(FilterKind, ListFile, ListFile)>;
+
+/// Фильтр запусков со списками, которые перечитываются при изменении файлов
+pub struct LaunchFilter {
+    dir: Option<PathBuf>,
+    lists: RwLock<Arc<FilterLists>>,
+    /// Файлы, из которых загружены текущие списки
+    loaded: Mutex<ListFiles>,
+}
+
+impl LaunchFilter {
+    /// Фильтр с фиксированными списками без файлов
+    pub fn new(lists: FilterLists) -> Self {
+        Self {
+            dir: None,
+            lists: RwLock::new(Arc::new(lists)),
+            loaded: Mutex::new(vec![]),
+        }
+    }
+
+    /// Фильтр, пропускающий всё
+    pub fn allow_all() -> Self {
+        Self::new(FilterLists::default())
+    }
+
+    /// Загружает списки из каталога
+    pub fn from_dir<P: Into<PathBuf>>(dir: P) -> Result<Self> {
+        let dir = dir.into();
+        let files = read_files(&dir)?;
+        let lists = FilterLists::parse(&files)?;
+        Ok(Self {
+            dir: Some(dir),
+            lists: RwLock::new(Arc::new(lists)),
+            loaded: Mutex::new(files),
+        })
+    }
+
+    /// Списки из `FILTER_DIR`; без каталога пропускает всё
+    pub fn from_config() -> Result<Self> {
+        match FILTER_DIR {
+            Some(dir) => {
+                Self::from_dir(dir).with_context(|| format!("Ошибка загрузки списков из {}", dir))
+            }
+            None => Ok(Self::allow_all()),
+        }
+    }
+
+    /// Текущие списки; снимок не меняется при перезагрузке
+    pub fn lists(&self) -> Arc<FilterLists> {
+        self.lists.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Перечитывает списки, если файлы изменились.
+    /// При ошибке в файле остаются прежние списки
+    ///
+    /// # Возвращает
+    /// `true`, если списки перезагружены
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let Some(dir) = &self.dir else {
+            return Ok(false);
+        };
+
+        // Списки небольшие, поэтому сравнивается содержимое, а не время изменения
+        let files = read_files(dir)?;
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        if *loaded == files {
+            return Ok(false);
+        }
+
+        let lists = FilterLists::parse(&files)?;
+        *self.lists.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(lists);
+        *loaded = files;
+        Ok(true)
+    }
+}
+
+/// Проверяет изменения файлов списков каждые `interval`
+pub fn spawn_filter_reloader(filter: Arc<LaunchFilter>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match filter.reload_if_changed() {
+                Ok(true) => println!("Списки фильтров перезагружены ({})", filter.lists()),
+                Ok(false) => {}
+                Err(e) => eprintln!("Ошибка перезагрузки списков, оставлены прежние: {:#}", e),
+            }
+        }
+    })
+}
+
+/// Update authority метаданных токена
+///
+/// # Возвращает
+/// `None`, если у токена нет аккаунта метаданных Metaplex
+pub async fn fetch_update_authority(rpc: &RpcPool, mint: &Pubkey) -> Result<Option<Pubkey>> {
+    let program = Pubkey::from_str(TOKEN_METADATA_PROGRAM_ID)?;
+    let (metadata, _) =
+        Pubkey::find_program_address(&[b"metadata", program.as_ref(), mint.as_ref()], &program);
+
+    let account = rpc
+        .read(|client| async move {
+            client
+                .get_account_with_commitment(&metadata, client.commitment())
+                .await
+        })
+        .await?
+        .value;
+
+    // key (1 байт), затем update_authority (32 байта)
+    Ok(account
+        .filter(|account| account.owner == program)
+        .and_then(|account| Pubkey::try_from(account.data.get(1..33)?).ok()))
+}
+
+fn read_files(dir: &Path) -> Result<ListFiles> {
+    let read = |suffix: &str, kind: FilterKind| -> Result<ListFile> {
+        let path = dir.join(format!("{}_{}.txt", kind.file_prefix(), suffix));
+        match fs::read_to_string(&path) {
+            Ok(text) => Ok((path, Some(text))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok((path, None)),
+            Err(e) => Err(e).with_context(|| format!("Ошибка чтения {}", path.display())),
+        }
+    };
+
+    FilterKind::ALL
+        .into_iter()
+        .map(|kind| Ok((kind, read("allow", kind)?, read("deny", kind)?)))
+        .collect()
+}
+
+fn parse_list((path, text): &ListFile) -> Result<Vec<Pubkey>> {
+    let Some(text) = text else {
+        return Ok(vec![]);
+    };
+
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            (!line.is_empty()).then_some((i + 1, line))
+        })
+        .map(|(number, line)| {
+            Pubkey::from_str(line).with_context(|| {
+                format!("Невалидный адрес в {}:{}: {}", path.display(), number, line)
+            })
+        })
+        .collect()
+}
@@ -1,151 +1,1054 @@
 use std::{
-    collections::HashSet,
+    collections::HashMap,
+    fmt,
+    future::Future,
     str::FromStr,
-    sync::{Arc, RwLock},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Result;
-use futures_util::sink::SinkExt;
-use prost::Message;
-use solana_client::nonblocking::rpc_client::RpcClient;
+use futures_util::{sink::SinkExt, Stream, StreamExt};
 use solana_sdk::{
-    message::VersionedMessage,
-    pubkey::Pubkey,
-    signature::Keypair,
-    transaction::{VersionedTransaction},
+    commitment_config::CommitmentConfig, native_token::lamports_to_sol, pubkey::Pubkey,
+    signature::Signature, transaction::VersionedTransaction,
 };
-use tokio_stream::StreamExt;
 use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
-use yellowstone_grpc_proto::prelude::{
-    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest, SubscribeUpdate,
+use yellowstone_grpc_proto::{
+    convert_from::{create_pubkey_vec, create_tx_versioned},
+    prelude::{
+        subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+        SubscribeRequestFilterBlocksMeta, SubscribeUpdate, SubscribeUpdateTransactionInfo,
+        TokenBalance,
+    },
 };
 
-use crate::config::{GRPC_URL, RELAYER_TIP_LAMPORTS, WSOL_MINT};
-use crate::swap::execute_swap;
+use crate::config::{
+    BUY_MIN_OUT, BUY_STRATEGY, DBC_CURVE_STRATEGY, DBC_MIGRATION_STRATEGY, EXECUTOR_QUEUE_CAPACITY,
+    EXECUTOR_WORKERS, GEYSER_RECORD_DIR, GEYSER_RECORD_MAX_FILE_BYTES,
+    GEYSER_RECORD_QUEUE_CAPACITY, METEORA_PROGRAM_ID, PENDING_EXPIRY_SECS, RELAYER_TIP_LAMPORTS,
+    RPC_CONFIRM_TIMEOUT_MS, WSOL_MINT,
+};
+use crate::context::BotContext;
+use crate::damm_v2::{self, PoolInitDammV2};
+use crate::dbc::{self, CurveConfigInit, CurveInit, MigrationInit};
+use crate::dlmm::{self, LbPairInit};
+use crate::executor::Executor;
+use crate::filters::{
+    fetch_update_authority, FilterKind, FilterLists, FilterRejection, LaunchFilter,
+};
+use crate::rpc::RpcPool;
+use crate::sizing::{BuySizing, LiquidityRejection, PhaseRejection, PhaseSizing};
+use crate::store::{PendingSubmission, Store};
+use crate::swap::{
+    build_transaction, fetch_fill_amount, submit_swap, swap_instructions, PoolProgram, SwapAccounts,
+};
+use crate::wallets::Wallet;
+
+pub mod recorder;
+
+use recorder::{BackgroundRecorder, GeyserRecorder};
 
 fn is_initialize_instruction(data: &[u8]) -> bool {
     !data.is_empty() && data[0] == 2
 }
 
-/// Подписывается на поток транзакций из Geyser GRPC и отслеживает
-/// появление новых пулов с участием WSOL, чтобы автоматически выполнить swap
+/// Длина данных инициализации с количествами: код операции и два u64
+const INITIALIZE_WITH_AMOUNTS_LEN: usize = 17;
+
+/// Количества token_a и token_b из аргументов инициализации
+///
+/// Раскладка та же, что у swap (`SwapInstructionData` в swap.rs): однобайтовый код
+/// операции `2`, затем `token_a_amount` и `token_b_amount` как u64 little-endian.
+/// Данные другой длины не разбираются — тогда количества берутся из резервов пула.
+fn initial_amounts_from_data(data: &[u8]) -> Option<(u64, u64)> {
+    if data.len() != INITIALIZE_WITH_AMOUNTS_LEN {
+        return None;
+    }
+    let amount =
+        |range: std::ops::Range<usize>| Some(u64::from_le_bytes(data.get(range)?.try_into().ok()?));
+    Some((amount(1..9)?, amount(9..17)?))
+}
+
+/// Суммарный баланс `mint` на аккаунтах владельца `owner`
+///
+/// Возвращает `None`, если таких аккаунтов нет или сумма не помещается в u64 —
+/// балансы приходят из чужой транзакции, и переполнение означает неизвестную ликвидность.
+pub fn owner_token_balance(balances: &[TokenBalance], owner: &str, mint: &str) -> Option<u64> {
+    balances
+        .iter()
+        .filter(|b| b.owner == owner && b.mint == mint)
+        .map(|b| {
+            b.ui_token_amount
+                .as_ref()
+                .and_then(|a| a.amount.parse::<u64>().ok())
+                .unwrap_or(0)
+        })
+        .try_fold(None, |total: Option<u64>, amount| {
+            Some(Some(total.unwrap_or(0).checked_add(amount)?))
+        })?
+}
+
+/// Фаза запуска токена, в которой найден пул
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchPhase {
+    /// Новый пул AMM, DLMM или DAMM v2
+    Pool,
+    /// Новая кривая Dynamic Bonding Curve с конфигом (лаунчпадом) `config`
+    Curve { config: Pubkey },
+    /// Пул DAMM v2, в который мигрировала завершённая кривая `curve`
+    Migration { curve: Pubkey },
+}
+
+impl LaunchPhase {
+    /// Стратегия кошельков для покупок в этой фазе
+    pub fn strategy(&self) -> &'static str {
+        match self {
+            LaunchPhase::Pool => BUY_STRATEGY,
+            LaunchPhase::Curve { .. } => DBC_CURVE_STRATEGY,
+            LaunchPhase::Migration { .. } => DBC_MIGRATION_STRATEGY,
+        }
+    }
+}
+
+impl fmt::Display for LaunchPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchPhase::Pool => write!(f, "новый пул"),
+            LaunchPhase::Curve { config } => write!(f, "кривая DBC (конфиг {})", config),
+            LaunchPhase::Migration { curve } => write!(f, "миграция кривой {}", curve),
+        }
+    }
+}
+
+/// Инициализация пула Meteora (AMM, DLMM, DAMM v2 или кривой DBC), найденная в транзакции
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolInit {
+    pub slot: u64,
+    pub signature: Signature,
+    pub token_a: Pubkey,
+    pub token_b: Pubkey,
+    /// Подписант, вносящий ликвидность: первый подписант инструкции или,
+    /// для DLMM и DAMM v2, funder/payer из её аккаунтов
+    pub creator: Pubkey,
+    pub fee_payer: Pubkey,
+    /// Внесённые при создании количества token_a и token_b, если их удалось определить
+    pub initial_amounts: Option<(u64, u64)>,
+    pub accounts: SwapAccounts,
+    pub phase: LaunchPhase,
+}
+
+/// Новый пул с WSOL, в котором решено покупать
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolLaunch {
+    pub slot: u64,
+    pub signature: Signature,
+    pub target_mint: Pubkey,
+    pub creator: Pubkey,
+    /// Начальная WSOL-ликвидность пула, лампорты
+    pub wsol_liquidity: Option<u64>,
+    /// Сумма покупки с учётом ликвидности, лампорты
+    pub amount_in: u64,
+    pub accounts: SwapAccounts,
+    pub phase: LaunchPhase,
+}
+
+/// Причина, по которой пул пропущен
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// В паре нет WSOL
+    NoWsol,
+    /// Пул уже обрабатывался (в том числе до перезапуска)
+    AlreadySeen,
+    /// Токен или создатель пула не прошёл белые/чёрные списки
+    Filtered(FilterRejection),
+    /// Начальная ликвидность вне диапазона или неизвестна
+    Liquidity(LiquidityRejection),
+    /// Фаза запуска выключена в конфигурации
+    Phase(PhaseRejection),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::NoWsol => write!(f, "в паре нет WSOL"),
+            SkipReason::AlreadySeen => write!(f, "пул уже обработан"),
+            SkipReason::Filtered(reason) => write!(f, "{}", reason),
+            SkipReason::Liquidity(reason) => write!(f, "{}", reason),
+            SkipReason::Phase(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Решение конвейера по найденной инициализации пула
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    Buy(Box<PoolLaunch>),
+    Skip { pool: Pubkey, reason: SkipReason },
+}
+
+/// Получатель решений конвейера: live-покупка, replay или тестовый сборщик
+pub trait DecisionHandler {
+    fn handle(&mut self, decision: Decision) -> impl Future<Output = ()> + Send;
+}
+
+/// Слот и транзакция из обновления Geyser, адреса из её lookup-таблиц
+/// (сначала writable, затем readonly)
+fn decode_transaction(
+    update: &SubscribeUpdate,
+) -> Option<(
+    u64,
+    &SubscribeUpdateTransactionInfo,
+    VersionedTransaction,
+    Vec<Pubkey>,
+)> {
+    let Some(UpdateOneof::Transaction(tx_update)) = &update.update_oneof else {
+        return None;
+    };
+    let tx_info = tx_update.transaction.as_ref()?;
+    let versioned_tx = create_tx_versioned(tx_info.transaction.clone()?).ok()?;
+
+    // Адреса, подгруженные из lookup-таблиц
+    let mut loaded_addresses = vec![];
+    if let Some(meta) = &tx_info.meta {
+        for loaded in [
+            &meta.loaded_writable_addresses,
+            &meta.loaded_readonly_addresses,
+        ] {
+            if let Ok(pubkeys) = create_pubkey_vec(loaded.clone()) {
+                loaded_addresses.extend(pubkeys);
+            }
+        }
+    }
+
+    Some((tx_update.slot, tx_info, versioned_tx, loaded_addresses))
+}
+
+/// Извлекает создания конфигов кривых DBC (лаунчпадов) из одного обновления Geyser
+pub fn detect_curve_configs(update: &SubscribeUpdate) -> Vec<CurveConfigInit> {
+    let (Some((_, _, tx, loaded_addresses)), Ok(program)) =
+        (decode_transaction(update), dbc::program_id())
+    else {
+        return vec![];
+    };
+    let mut keys = tx.message.static_account_keys().to_vec();
+    keys.extend(loaded_addresses);
+
+    tx.message
+        .instructions()
+        .iter()
+        .filter(|instr| keys.get(instr.program_id_index as usize) == Some(&program))
+        .filter_map(|instr| {
+            let accounts = instr
+                .accounts
+                .iter()
+                .map(|&i| keys.get(i as usize).copied())
+                .collect::<Option<Vec<_>>>()?;
+            CurveConfigInit::parse(&accounts, &instr.data)
+        })
+        .collect()
+}
+
+/// Извлекает инициализации пулов Meteora из одного обновления Geyser
+///
+/// Не обращается к сети и хранилищу, поэтому одинаково работает для live-потока и записи.
+pub fn detect_pool_inits(update: &SubscribeUpdate, meteora_program: &Pubkey) -> Vec<PoolInit> {
+    let Some((slot, tx_info, versioned_tx, loaded_addresses)) = decode_transaction(update) else {
+        return vec![];
+    };
+
+    let mut inits =
+        detect_pool_inits_in_transaction(slot, &versioned_tx, &loaded_addresses, meteora_program);
+
+    // Если в аргументах количеств нет — берём резервы пула после транзакции
+    if let Some(meta) = &tx_info.meta {
+        for init in inits.iter_mut().filter(|i| i.initial_amounts.is_none()) {
+            let authority = init.accounts.pool_authority.to_string();
+            let reserve = |mint: &Pubkey| {
+                owner_token_balance(&meta.post_token_balances, &authority, &mint.to_string())
+            };
+            init.initial_amounts = reserve(&init.token_a).zip(reserve(&init.token_b));
+        }
+    }
+
+    inits
+}
+
+/// Извлекает инициализации пулов Meteora из уже декодированной транзакции
+///
+/// # Аргументы
+/// * `slot` — слот, в котором транзакция попала в блок
+/// * `tx` — транзакция
+/// * `loaded_addresses` — адреса из lookup-таблиц (сначала writable, затем readonly)
+/// * `meteora_program` — адрес программы Meteora
+pub fn detect_pool_inits_in_transaction(
+    slot: u64,
+    tx: &VersionedTransaction,
+    loaded_addresses: &[Pubkey],
+    meteora_program: &Pubkey,
+) -> Vec<PoolInit> {
+    // Полный список аккаунтов: статические ключи и адреса из lookup-таблиц
+    let mut keys = tx.message.static_account_keys().to_vec();
+    keys.extend_from_slice(loaded_addresses);
+    let key = |index: u8| keys.get(index as usize).copied();
+    let is_signer = |index: u8| tx.message.is_signer(index as usize);
+    let fee_payer = keys.first().copied().unwrap_or_default();
+
+    let dlmm_program = dlmm::program_id().ok();
+    let damm_v2_program = damm_v2::program_id().ok();
+    let dbc_program = dbc::program_id().ok();
+
+    let mut inits = vec![];
+    for instr in tx.message.instructions() {
+        let accs = &instr.accounts;
+        let resolve = |count: usize| -> Option<Vec<Pubkey>> {
+            accs.get(..count)?.iter().map(|&i| key(i)).collect()
+        };
+        let program = key(instr.program_id_index);
+
+        let (token_a, token_b, initial_amounts, accounts, creator, phase) =
+            if program == Some(*meteora_program) && is_initialize_instruction(&instr.data) {
+                let Some(k) = resolve(10) else {
+                    continue;
+                };
+                (
+                    k[8],
+                    k[9],
+                    initial_amounts_from_data(&instr.data),
+                    SwapAccounts {
+                        program: PoolProgram::Amm,
+                        pool: k[2],
+                        user_source: k[0],
+                        user_destination: k[1],
+                        pool_source: k[2],
+                        pool_destination: k[3],
+                        pool_authority: k[4],
+                        token_program: k[5],
+                    },
+                    None,
+                    LaunchPhase::Pool,
+                )
+            } else if program.is_some() && program == dlmm_program {
+                // Ликвидность в DLMM добавляется отдельными инструкциями — количества берутся из резервов
+                let Some(init) = resolve(dlmm::INITIALIZE_LB_PAIR_ACCOUNTS)
+                    .and_then(|k| LbPairInit::parse(&k, &instr.data))
+                else {
+                    continue;
+                };
+                (
+                    init.token_x_mint,
+                    init.token_y_mint,
+                    None,
+                    init.swap_accounts(),
+                    Some(init.funder),
+                    LaunchPhase::Pool,
+                )
+            } else if program.is_some() && program == damm_v2_program {
+                // Аргументы задают ликвидность и цену, а не количества — они тоже берутся из хранилищ
+                let Some(init) =
+                    resolve(accs.len()).and_then(|k| PoolInitDammV2::parse(&k, &instr.data))
+                else {
+                    continue;
+                };
+                (
+                    init.token_a_mint,
+                    init.token_b_mint,
+                    None,
+                    init.swap_accounts(),
+                    Some(init.payer),
+                    LaunchPhase::Pool,
+                )
+            } else if program.is_some() && program == dbc_program {
+                // Количества кривой и пула после миграции берутся из хранилищ, как у DLMM
+                let Some(k) = resolve(accs.len()) else {
+                    continue;
+                };
+                if let Some(curve) = CurveInit::parse(&k, &instr.data) {
+                    (
+                        curve.base_mint,
+                        curve.quote_mint,
+                        None,
+                        curve.swap_accounts(),
+                        Some(curve.creator),
+                        LaunchPhase::Curve {
+                            config: curve.config,
+                        },
+                    )
+                } else if let Some(migration) = MigrationInit::parse(&k, &instr.data) {
+                    (
+                        migration.base_mint,
+                        migration.quote_mint,
+                        None,
+                        migration.swap_accounts(),
+                        Some(migration.payer),
+                        LaunchPhase::Migration {
+                            curve: migration.curve,
+                        },
+                    )
+                } else {
+                    continue;
+                }
+            } else {
+                continue;
+            };
+
+        // В DAMM v2 первым подписывает mint NFT позиции, поэтому адаптеры указывают создателя сами
+        let creator = creator.unwrap_or_else(|| {
+            accs.iter()
+                .find(|&&i| is_signer(i))
+                .and_then(|&i| key(i))
+                .unwrap_or(fee_payer)
+        });
+
+        inits.push(PoolInit {
+            slot,
+            signature: tx.signatures.first().copied().unwrap_or_default(),
+            token_a,
+            token_b,
+            creator,
+            fee_payer,
+            initial_amounts,
+            accounts,
+            phase,
+        });
+    }
+
+    inits
+}
+
+/// Принимает решение по инициализации пула: покупать ли в нём
+///
+/// # Аргументы
+/// * `init` — найденная инициализация
+/// * `wsol_mint` — адрес WSOL
+/// * `filters` — белые и чёрные списки токенов, создателей и плательщиков
+/// * `sizing` — допустимая начальная ликвидность и размер покупки
+/// * `store` — хранилище для исключения повторных покупок
+pub fn decide(
+    init: &PoolInit,
+    wsol_mint: &Pubkey,
+    filters: &FilterLists,
+    sizing: &BuySizing,
+    store: &Store,
+) -> Result<Decision> {
+    let pool = init.accounts.pool;
+
+    // Определение, есть ли WSOL в паре токенов
+    let (target_mint, wsol_liquidity) = if init.token_a == *wsol_mint {
+        (init.token_b, init.initial_amounts.map(|(a, _)| a))
+    } else if init.token_b == *wsol_mint {
+        (init.token_a, init.initial_amounts.map(|(_, b)| b))
+    } else {
+        return Ok(Decision::Skip {
+            pool,
+            reason: SkipReason::NoWsol,
+        });
+    };
+
+    // Списки проверяются до отметки пула, чтобы отфильтрованный пул не попал в хранилище
+    let checks = [
+        (FilterKind::Mint, &target_mint),
+        (FilterKind::Creator, &init.creator),
+        (FilterKind::FeePayer, &init.fee_payer),
+    ];
+    if let Err(reason) = checks
+        .into_iter()
+        .try_for_each(|(kind, key)| filters.check(kind, key))
+    {
+        return Ok(Decision::Skip {
+            pool,
+            reason: SkipReason::Filtered(reason),
+        });
+    }
+
+    // У DLMM, DAMM v2 и DBC направление свапа задаётся порядком хранилищ: покупка идёт из хранилища WSOL
+    let accounts = match init.accounts.program {
+        PoolProgram::Dlmm | PoolProgram::DammV2 | PoolProgram::Dbc
+            if init.token_b == *wsol_mint =>
+        {
+            init.accounts.reversed()
+        }
+        _ => init.accounts,
+    };
+
+    let amount_in = match sizing.amount_for(wsol_liquidity) {
+        Ok(amount) => amount,
+        Err(reason) => {
+            return Ok(Decision::Skip {
+                pool,
+                reason: SkipReason::Liquidity(reason),
+            })
+        }
+    };
+
+    // Проверка, был ли пул уже обработан
+    if !store.mark_pool_seen(&pool, &target_mint)? {
+        return Ok(Decision::Skip {
+            pool,
+            reason: SkipReason::AlreadySeen,
+        });
+    }
+
+    Ok(Decision::Buy(Box::new(PoolLaunch {
+        slot: init.slot,
+        signature: init.signature,
+        target_mint,
+        creator: init.creator,
+        wsol_liquidity,
+        amount_in,
+        accounts,
+        phase: init.phase,
+    })))
+}
+
+/// Конвейер обнаружения: декодирует каждое обновление, принимает решения
+/// и передаёт их обработчику
 ///
 /// # Аргументы
-/// * `rpc_client` — клиент RPC для взаимодействия с Solana
-/// * `payer` — ключ, с которого отправляется транзакция swap
+/// * `updates` — любой поток обновлений: live-подписка Geyser или запись с диска
+/// * `store` — хранилище для исключения повторных покупок
+/// * `filter` — белые и чёрные списки; списки берутся заново для каждого обновления
+/// * `handler` — получатель решений
+pub async fn run_pipeline<S, E, H>(
+    mut updates: S,
+    store: &Store,
+    filter: &LaunchFilter,
+    handler: &mut H,
+) -> Result<()>
+where
+    S: Stream<Item = Result<SubscribeUpdate, E>> + Unpin,
+    E: fmt::Debug,
+    H: DecisionHandler,
+{
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let meteora_program = Pubkey::from_str(METEORA_PROGRAM_ID)?;
+    let phases = PhaseSizing::from_config()?;
+
+    while let Some(resp) = updates.next().await {
+        let update = match resp {
+            Ok(update) => update,
+            Err(e) => {
+                println!("Ошибка в потоке транзакций: {:?}", e);
+                continue;
+            }
+        };
+
+        for config in detect_curve_configs(&update) {
+            println!(
+                "Новый конфиг кривых DBC: {} (quote {}, комиссии получает {})",
+                config.config, config.quote_mint, config.fee_claimer
+            );
+        }
+
+        let filters = filter.lists();
+        for init in detect_pool_inits(&update, &meteora_program) {
+            let decision = match phases.for_phase(&init.phase) {
+                // Ошибка хранилища (например, занятая база) пропускает пул, а не останавливает поток
+                Ok(sizing) => match decide(&init, &wsol_mint, &filters, &sizing, store) {
+                    Ok(decision) => decision,
+                    Err(e) => {
+                        eprintln!("Пул {} пропущен: {:?}", init.accounts.pool, e);
+                        continue;
+                    }
+                },
+                Err(reason) => Decision::Skip {
+                    pool: init.accounts.pool,
+                    reason: SkipReason::Phase(reason),
+                },
+            };
+            handler.handle(decision).await;
+        }
+    }
+
+    Ok(())
+}
+
+/// Собирает и подписывает транзакцию покупки с кошелька `wallet`
 ///
 /// # Возвращает
-/// `Result<()>` — успешное выполнение или ошибка при подписке / swap
-pub async fn monitor_liquidity_additions(
-    rpc_client: Arc<RpcClient>,
-    payer: Arc<Keypair>,
+/// Аккаунты свапа, переписанные под кошелёк (они записываются для продажи), и транзакцию
+async fn build_buy(
+    ctx: &BotContext,
+    wallet: &Wallet,
+    launch: &PoolLaunch,
+    min_out: u64,
+) -> Result<(SwapAccounts, VersionedTransaction)> {
+    let BotContext { rpc, blockhash, .. } = ctx;
+
+    // Blockhash берётся из фонового кэша; RPC — только если кэш пуст или устарел
+    let blockhash = match blockhash.get() {
+        Ok(hash) => hash,
+        Err(e) => {
+            eprintln!("{:#}, запрашиваем через RPC", e);
+            rpc.read(|client| async move { client.get_latest_blockhash().await })
+                .await?
+        }
+    };
+    let accounts = wallet.swap_accounts(&launch.accounts, &launch.target_mint)?;
+    let mut instructions = wallet.prepare_instructions(&accounts, &launch.target_mint)?;
+    instructions.extend(
+        swap_instructions(rpc, &wallet.pubkey(), &accounts, launch.amount_in, min_out).await?,
+    );
+    let tx = build_transaction(
+        wallet.signer.as_ref(),
+        instructions,
+        RELAYER_TIP_LAMPORTS,
+        blockhash,
+    )
+    .await?;
+    Ok((accounts, tx))
+}
+
+/// Отправляет подписанную покупку `tx` и записывает отправку,
+/// подтверждение и исполнение в хранилище
+async fn buy_pool(
+    ctx: &BotContext,
+    reservation: &mut BuyReservation<'_>,
+    wallet: &Wallet,
+    accounts: &SwapAccounts,
+    target_mint: &Pubkey,
+    amount_in: u64,
+    tx: VersionedTransaction,
 ) -> Result<()> {
-    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let BotContext {
+        rpc, relays, store, ..
+    } = ctx;
+    let signature = tx.signatures[0];
 
-    let grpc_builder = GeyserGrpcClient::build_from_static(GRPC_URL)
-        .tls_config(ClientTlsConfig::new().with_native_roots())?;
+    store.record_submission(&signature, &accounts.pool, amount_in)?;
+    store.record_buy_wallet(&signature, &wallet.pubkey())?;
+    // Аккаунты нужны для продажи, в том числе покупки, подтверждённой после перезапуска
+    store.record_pool_accounts(accounts, target_mint)?;
 
-    // Подключение к GRPC серверу и подписка
-    let mut client = grpc_builder.connect().await?;
-    let (mut sender, mut stream) = client.subscribe().await?;
+    reservation.sent = true;
+    if let Err(e) = submit_swap(rpc, relays, &tx, RELAYER_TIP_LAMPORTS).await {
+        eprintln!("Swap отправлен с ошибками: {:?}", e);
+    }
 
-    sender
-        .send(SubscribeRequest {
-            commitment: Some(CommitmentLevel::Processed as i32),
-            ..Default::default()
+    // Статус берём из RPC: транзакция могла попасть в блок через ретранслятор,
+    // даже если один из каналов вернул ошибку
+    let timeout = Duration::from_millis(RPC_CONFIRM_TIMEOUT_MS);
+    match rpc.wait_for_confirmation(&signature, timeout).await? {
+        Some(Ok(())) => {
+            let amount_out = record_confirmed_buy(
+                rpc,
+                store,
+                &signature,
+                &accounts.pool,
+                target_mint,
+                &wallet.pubkey(),
+                amount_in,
+            )
+            .await?;
+            println!("Покупка исполнена: {} ({} токенов)", signature, amount_out);
+        }
+        Some(Err(e)) => {
+            store.record_confirmation(&signature, Some(&e.to_string()))?;
+        }
+        None => {
+            println!("Транзакция {} ещё не подтверждена", signature);
+        }
+    }
+
+    Ok(())
+}
+
+/// Записывает подтверждённую покупку: подтверждение и количество токенов,
+/// полученное кошельком `owner`
+///
+/// Количество запрашивается до записи подтверждения, поэтому при ошибке RPC
+/// покупка остаётся неподтверждённой и сверяется после перезапуска.
+async fn record_confirmed_buy(
+    rpc: &RpcPool,
+    store: &Store,
+    signature: &Signature,
+    pool: &Pubkey,
+    target_mint: &Pubkey,
+    owner: &Pubkey,
+    amount_in: u64,
+) -> Result<u64> {
+    let amount_out = rpc
+        .read(|client| async move {
+            fetch_fill_amount(&client, signature, owner, target_mint).await
         })
         .await?;
+    store.record_confirmation(signature, None)?;
+    store.record_fill(signature, pool, target_mint, amount_in, amount_out)?;
+    Ok(amount_out)
+}
 
-    println!("Ожидание транзакций от Meteora Pools...");
+/// Итог сверки покупок, оставшихся неподтверждёнными до перезапуска
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// Подтверждены и записаны как исполненные
+    pub filled: usize,
+    /// Отклонены или так и не попали в блок
+    pub failed: usize,
+    /// Статус пока неизвестен — остаются неподтверждёнными
+    pub unresolved: usize,
+}
 
-    let seen_pools: Arc<RwLock<HashSet<Pubkey>>> = Arc::new(RwLock::new(HashSet::new()));
+/// Сверяет с сетью покупки, отправленные до перезапуска, и записывает их
+/// подтверждение и исполнение так же, как покупка в live-режиме
+///
+/// Статус ищется и в истории узла; ненайденная покупка старше
+/// `PENDING_EXPIRY_SECS` считается не попавшей в блок.
+///
+/// # Аргументы
+/// * `pending` — неподтверждённые отправки из [`Store::recover`]
+/// * `default_wallet` — кошелёк покупок, записанных без кошелька
+pub async fn reconcile_pending(
+    rpc: &RpcPool,
+    store: &Store,
+    pending: &[PendingSubmission],
+    default_wallet: &Pubkey,
+) -> Result<Reconciliation> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let mut result = Reconciliation::default();
 
-    // Основной цикл обработки входящих транзакций
-    while let Some(resp) = stream.next().await {
-        match resp {
-            Ok(SubscribeUpdate {
-                update_oneof: Some(UpdateOneof::Transaction(tx_info)),
-                ..
-            }) => {
-                if let Some(tx) = &tx_info.transaction {
-                    let mut buf = Vec::new();
-                    tx.encode(&mut buf)?;
-
-                    if let Ok(versioned_tx) =
-                        bincode::deserialize::<VersionedTransaction>(&buf)
-                    {
-                        if let VersionedMessage::V0(message) = &versioned_tx.message {
-                            for instr in &message.instructions {
-                                if is_initialize_instruction(&instr.data) {
-                                    let keys = &message.account_keys;
-                                    let accs = &instr.accounts;
-
-                                    if accs.len() < 10 {
-                                        continue;
-                                    }
-
-                                    // Определение, есть ли WSOL в паре токенов
-                                    let token_a = keys[accs[8] as usize];
-                                    let token_b = keys[accs[9] as usize];
-
-                                    let (target_mint, is_valid_pair) = if token_a == wsol_mint {
-                                        (token_b, true)
-                                    } else if token_b == wsol_mint {
-                                        (token_a, true)
-                                    } else {
-                                        (Pubkey::default(), false)
-                                    };
-
-                                    if !is_valid_pair {
-                                        continue;
-                                    }
-
-                                    // Проверка, был ли пул уже обработан
-                                    let pool = keys[accs[2] as usize];
-                                    let mut seen = seen_pools.write().unwrap();
-                                    if seen.contains(&pool) {
-                                        continue;
-                                    }
-                                    seen.insert(pool);
-
-                                    println!("Новый пул с WSOL: {}", pool);
-                                    println!("Токен к покупке: {}", target_mint);
-
-                                    let user_source = keys[accs[0] as usize];
-                                    let user_dest = keys[accs[1] as usize];
-                                    let pool_source = keys[accs[2] as usize];
-                                    let pool_dest = keys[accs[3] as usize];
-                                    let pool_auth = keys[accs[4] as usize];
-                                    let token_prog = keys[accs[5] as usize];
-
-                                    // Выполнение свапа
-                                    let _ = execute_swap(
-                                        rpc_client.clone(),
-                                        &payer,
-                                        pool,
-                                        user_source,
-                                        user_dest,
-                                        pool_source,
-                                        pool_dest,
-                                        pool_auth,
-                                        token_prog,
-                                        1_000_000,
-                                        1,
-                                        RELAYER_TIP_LAMPORTS,
-                                    )
-                                    .await;
-                                }
-                            }
-                        }
+    for submission in pending {
+        let signature = &submission.signature;
+        let status = rpc
+            .read(|client| async move {
+                client
+                    .get_signature_status_with_commitment_and_history(
+                        signature,
+                        CommitmentConfig::confirmed(),
+                        true,
+                    )
+                    .await
+            })
+            .await;
+
+        match status {
+            Ok(Some(Ok(()))) => {
+                let Some(target_mint) = store.seen_pool_mint(&submission.pool)? else {
+                    eprintln!(
+                        "Покупка {} не сверена: пул {} не найден в хранилище",
+                        signature, submission.pool
+                    );
+                    result.unresolved += 1;
+                    continue;
+                };
+                let owner = store.buy_wallet(signature)?.unwrap_or(*default_wallet);
+                match record_confirmed_buy(
+                    rpc,
+                    store,
+                    signature,
+                    &submission.pool,
+                    &target_mint,
+                    &owner,
+                    submission.amount_in,
+                )
+                .await
+                {
+                    Ok(amount_out) => {
+                        println!(
+                            "Покупка {} исполнена до перезапуска ({} токенов)",
+                            signature, amount_out
+                        );
+                        result.filled += 1;
+                    }
+                    Err(e) => {
+                        eprintln!("Покупка {} не сверена: {:?}", signature, e);
+                        result.unresolved += 1;
                     }
                 }
             }
+            Ok(Some(Err(e))) => {
+                store.record_confirmation(signature, Some(&e.to_string()))?;
+                result.failed += 1;
+            }
+            Ok(None) if now - submission.submitted_at >= PENDING_EXPIRY_SECS => {
+                store.record_confirmation(signature, Some("транзакция не попала в блок"))?;
+                result.failed += 1;
+            }
+            Ok(None) => result.unresolved += 1,
             Err(e) => {
-                println!("Ошибка в потоке транзакций: {:?}", e);
+                eprintln!("Не удалось получить статус покупки {}: {:?}", signature, e);
+                result.unresolved += 1;
             }
-            _ => {}
         }
     }
 
-    Ok(())
+    Ok(result)
+}
+
+/// Покупает в пуле из найденного запуска; ошибки только логируются
+async fn buy_launch(ctx: &BotContext, launch: PoolLaunch) {
+    let pool = launch.accounts.pool;
+
+    println!("Новый пул с WSOL: {} ({})", pool, launch.phase);
+    println!("Токен к покупке: {}", launch.target_mint);
+    if let Some(liquidity) = launch.wsol_liquidity {
+        println!(
+            "Начальная ликвидность: {} SOL, покупка на {} SOL",
+            lamports_to_sol(liquidity),
+            lamports_to_sol(launch.amount_in)
+        );
+    }
+
+    // Пул отмечен увиденным ещё в `decide`. Если покупка не отправлена из-за ошибки RPC
+    // или лимитов, отметка снимается — иначе пул уже никогда не купить.
+    // Отказы фильтров и репутации окончательные, их отметка остаётся
+    let unmark = || unmark_pool(&ctx.store, &pool);
+
+    // Update authority требует запроса метаданных, поэтому проверяется только при заданных списках
+    let filters = ctx.filter.lists();
+    if filters.is_active(FilterKind::UpdateAuthority) {
+        let checked = match fetch_update_authority(&ctx.rpc, &launch.target_mint).await {
+            Ok(Some(authority)) => filters.check(FilterKind::UpdateAuthority, &authority),
+            Ok(None) => filters.check_unknown(FilterKind::UpdateAuthority),
+            Err(e) => {
+                eprintln!(
+                    "Покупка в пуле {} отменена: не удалось получить метаданные токена: {:?}",
+                    pool, e
+                );
+                unmark();
+                return;
+            }
+        };
+        if let Err(reason) = checked {
+            println!("Пул {} пропущен: {}", pool, reason);
+            return;
+        }
+    }
+
+    // История создателя загружается через RPC, поэтому проверяется после дешёвых фильтров
+    if let Some(reputation) = &ctx.reputation {
+        match reputation.assess(&launch.creator, &pool).await {
+            Ok(assessed) if assessed.score < reputation.params().min_score => {
+                println!(
+                    "Пул {} пропущен: создатель {}, {}",
+                    pool, launch.creator, assessed
+                );
+                return;
+            }
+            Ok(assessed) => println!("Создатель {}: {}", launch.creator, assessed),
+            Err(e) => {
+                eprintln!(
+                    "Покупка в пуле {} отменена: не удалось оценить создателя: {:?}",
+                    pool, e
+                );
+                unmark();
+                return;
+            }
+        }
+    }
+
+    // Проверка лимитов риска до отправки
+    let approved = ctx
+        .risk
+        .approve_from_store(pool, launch.amount_in, &ctx.store);
+    match approved {
+        Ok(Ok(())) => {}
+        Ok(Err(reason)) => {
+            println!(
+                "Покупка в пуле {} отклонена риск-менеджером: {}",
+                pool, reason
+            );
+            unmark();
+            return;
+        }
+        Err(e) => {
+            eprintln!(
+                "Покупка в пуле {} отменена: не удалось оценить риск: {:?}",
+                pool, e
+            );
+            unmark();
+            return;
+        }
+    }
+    // Дальше резервы и отметка пула снимаются при сбросе, в том числе при панике
+    let mut reservation = BuyReservation {
+        ctx,
+        pool,
+        wallet: false,
+        sent: false,
+    };
+
+    // Кошелёк выбирается после общих лимитов: его бюджет — дополнительное ограничение
+    let assigned = ctx.store.wallet_exposure().map(|exposure| {
+        ctx.wallets
+            .assign(pool, launch.amount_in, launch.phase.strategy(), &exposure)
+    });
+    let wallet = match assigned {
+        Ok(Ok(wallet)) => wallet,
+        Ok(Err(reason)) => {
+            println!("Покупка в пуле {} отклонена: {}", pool, reason);
+            return;
+        }
+        Err(e) => {
+            eprintln!(
+                "Покупка в пуле {} отменена: не удалось оценить бюджеты кошельков: {:?}",
+                pool, e
+            );
+            return;
+        }
+    };
+    reservation.wallet = true;
+    println!("Кошелёк покупки: {}", wallet.pubkey());
+
+    // Выполнение свапа
+    let result = match build_buy(ctx, &wallet, &launch, BUY_MIN_OUT).await {
+        Ok((accounts, tx)) => {
+            buy_pool(
+                ctx,
+                &mut reservation,
+                &wallet,
+                &accounts,
+                &launch.target_mint,
+                launch.amount_in,
+                tx,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    drop(reservation);
+
+    if let Err(e) = result {
+        eprintln!("Ошибка покупки в пуле {}: {:?}", pool, e);
+    }
+}
+
+/// Резервы покупки в риск-менеджере и пуле кошельков, снимаемые при сбросе
+///
+/// Если транзакция так и не отправлена, покупка не занимает лимит за окно
+/// ([`crate::risk::RiskManager::cancel`]), а отметка пула снимается
+struct BuyReservation<'a> {
+    ctx: &'a BotContext,
+    pool: Pubkey,
+    /// Пул кошельков зарезервировал бюджет под покупку
+    wallet: bool,
+    /// Транзакция передана на отправку
+    sent: bool,
+}
+
+impl Drop for BuyReservation<'_> {
+    fn drop(&mut self) {
+        if self.wallet {
+            self.ctx.wallets.release(&self.pool);
+        }
+        if self.sent {
+            self.ctx.risk.release(&self.pool);
+        } else {
+            self.ctx.risk.cancel(&self.pool);
+            unmark_pool(&self.ctx.store, &self.pool);
+        }
+    }
+}
+
+fn unmark_pool(store: &Store, pool: &Pubkey) {
+    if let Err(e) = store.unmark_pool_seen(pool) {
+        eprintln!("Не удалось снять отметку пула {}: {:?}", pool, e);
+    }
+}
+
+/// Обработчик live-режима: передаёт запуски исполнителю, не дожидаясь покупки
+struct LiveBuyer<'a> {
+    executor: &'a Executor,
+}
+
+impl DecisionHandler for LiveBuyer<'_> {
+    async fn handle(&mut self, decision: Decision) {
+        let launch = match decision {
+            Decision::Buy(launch) => launch,
+            // Пулы без WSOL и повторы встречаются постоянно, их не логируем
+            Decision::Skip {
+                reason: SkipReason::NoWsol | SkipReason::AlreadySeen,
+                ..
+            } => return,
+            Decision::Skip { pool, reason } => {
+                println!("Пул {} пропущен: {}", pool, reason);
+                return;
+            }
+        };
+        let pool = launch.accounts.pool;
+
+        match self.executor.submit(*launch).await {
+            Ok(true) => {}
+            Ok(false) => println!("Пул {} уже покупается", pool),
+            Err(e) => eprintln!("Не удалось поставить пул {} в очередь: {:?}", pool, e),
+        }
+    }
+}
+
+/// Подписывается на поток транзакций из Geyser GRPC и отслеживает
+/// появление новых пулов с участием WSOL, чтобы автоматически выполнить swap
+///
+/// # Аргументы
+/// * `endpoint` — адрес Geyser gRPC (`https://` — с TLS, `http://` — без)
+/// * `ctx` — RPC, ретрансляторы, кошельки и хранилище бота
+///
+/// # Возвращает
+/// `Result<()>` — успешное выполнение (поток закрыт сервером) или ошибка при подписке
+pub async fn monitor_liquidity_additions(endpoint: &str, ctx: BotContext) -> Result<()> {
+    let mut grpc_builder = GeyserGrpcClient::build_from_shared(endpoint.to_string())?;
+    if endpoint.starts_with("https://") {
+        grpc_builder = grpc_builder.tls_config(ClientTlsConfig::new().with_native_roots())?;
+    }
+
+    // Подключение к GRPC серверу и подписка
+    let mut client = grpc_builder.connect().await?;
+    let (mut sender, stream) = client.subscribe().await?;
+
+    sender
+        .send(SubscribeRequest {
+            // block-meta нужен кэшу blockhash
+            blocks_meta: HashMap::from([(
+                "blockhash".to_string(),
+                SubscribeRequestFilterBlocksMeta::default(),
+            )]),
+            commitment: Some(CommitmentLevel::Processed as i32),
+            ..Default::default()
+        })
+        .await?;
+
+    // Запись потока на диск для последующего разбора пропущенных пулов — в своём потоке,
+    // чтобы диск не задерживал детектор
+    let recorder = match GEYSER_RECORD_DIR {
+        Some(dir) => Some(BackgroundRecorder::spawn(
+            GeyserRecorder::new(dir, "geyser", GEYSER_RECORD_MAX_FILE_BYTES)?,
+            GEYSER_RECORD_QUEUE_CAPACITY,
+        )),
+        None => None,
+    };
+
+    println!("Ожидание транзакций от Meteora Pools...");
+
+    let blockhash = ctx.blockhash.clone();
+    let recording = recorder.as_ref();
+    let updates = stream.inspect(move |resp| {
+        let Ok(update) = resp else {
+            return;
+        };
+        blockhash.observe(update);
+        if let Some(recorder) = recording {
+            recorder.record(update);
+        }
+    });
+
+    let store = ctx.store.clone();
+    let filter = ctx.filter.clone();
+    let executor = Executor::spawn(EXECUTOR_WORKERS, EXECUTOR_QUEUE_CAPACITY, move |launch| {
+        let ctx = ctx.clone();
+        async move { buy_launch(&ctx, launch).await }
+    });
+
+    let result = run_pipeline(
+        updates,
+        &store,
+        &filter,
+        &mut LiveBuyer {
+            executor: &executor,
+        },
+    )
+    .await;
+
+    // Поток закрыт — даём завершиться уже начатым покупкам и дописываем запись
+    executor.shutdown().await;
+    if let Some(recorder) = recorder {
+        recorder.shutdown().await?;
+    }
+    result
 }
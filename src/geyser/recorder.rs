@@ -0,0 +1,353 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use prost::Message;
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+};
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+use crate::config::GEYSER_RECORD_FLUSH_INTERVAL_MS;
+
+/// Сигнатура в начале каждого файла записи
+const MAGIC: &[u8; 8] = b"GSRREC01";
+/// Расширение файлов записи
+const EXTENSION: &str = "rec";
+/// Защита от битых файлов: кадр не может быть больше 64 МиБ
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Обновление Geyser вместе с моментом его получения
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedUpdate {
+    /// Время получения в микросекундах с UNIX epoch
+    pub received_at_micros: u64,
+    pub update: SubscribeUpdate,
+}
+
+/// Пишет поток `SubscribeUpdate` на диск.
+///
+/// Формат файла: `MAGIC`, затем кадры `[u32 LE длина][deflate(u64 LE время получения + protobuf)]`.
+/// Когда текущий файл превышает `max_file_bytes`, открывается следующий.
+/// Имена файлов `<prefix>-<время создания, мкс>-<номер>.rec` сортируются в порядке записи.
+/// Буфер сбрасывается на диск не реже раза в `flush_interval`, чтобы запись пережила падение.
+pub struct GeyserRecorder {
+    dir: PathBuf,
+    prefix: String,
+    max_file_bytes: u64,
+    flush_interval: Duration,
+    last_flush: Instant,
+    sequence: u64,
+    current: Option<(BufWriter<File>, u64)>,
+}
+
+impl GeyserRecorder {
+    /// Создаёт рекордер, пишущий в каталог `dir` (создаётся при необходимости)
+    pub fn new<P: AsRef<Path>>(dir: P, prefix: &str, max_file_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Невозможно создать каталог записи {}", dir.display()))?;
+
+        Ok(Self {
+            dir,
+            prefix: prefix.to_string(),
+            max_file_bytes,
+            flush_interval: Duration::from_millis(GEYSER_RECORD_FLUSH_INTERVAL_MS),
+            last_flush: Instant::now(),
+            sequence: 0,
+            current: None,
+        })
+    }
+
+    /// Интервал сброса буфера на диск; `Duration::ZERO` — после каждого кадра
+    pub fn with_flush_interval(mut self, interval: Duration) -> Self {
+        self.flush_interval = interval;
+        self
+    }
+
+    /// Записывает обновление с текущим временем получения
+    pub fn record(&mut self, update: &SubscribeUpdate) -> Result<()> {
+        self.record_at(update, now_micros())
+    }
+
+    /// Записывает обновление с заданным временем получения
+    pub fn record_at(&mut self, update: &SubscribeUpdate, received_at_micros: u64) -> Result<()> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&received_at_micros.to_le_bytes())?;
+        encoder.write_all(&update.encode_to_vec())?;
+        let frame = encoder.finish()?;
+
+        let (writer, written) = self.writer()?;
+        writer.write_all(&(frame.len() as u32).to_le_bytes())?;
+        writer.write_all(&frame)?;
+        *written += 4 + frame.len() as u64;
+
+        if *written >= self.max_file_bytes {
+            self.rotate()?;
+        } else if self.last_flush.elapsed() >= self.flush_interval {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Сбрасывает буфер текущего файла на диск
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some((writer, _)) = self.current.as_mut() {
+            writer.flush()?;
+        }
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    /// Закрывает текущий файл; следующая запись откроет новый
+    pub fn rotate(&mut self) -> Result<()> {
+        if let Some((mut writer, _)) = self.current.take() {
+            writer.flush()?;
+        }
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    fn writer(&mut self) -> Result<&mut (BufWriter<File>, u64)> {
+        if self.current.is_none() {
+            let name = format!(
+                "{}-{:020}-{:06}.{}",
+                self.prefix,
+                now_micros(),
+                self.sequence,
+                EXTENSION
+            );
+            self.sequence += 1;
+
+            let path = self.dir.join(name);
+            let file = File::create(&path)
+                .with_context(|| format!("Невозможно создать файл записи {}", path.display()))?;
+            let mut writer = BufWriter::new(file);
+            writer.write_all(MAGIC)?;
+            self.current = Some((writer, MAGIC.len() as u64));
+        }
+
+        Ok(self.current.as_mut().expect("файл записи открыт выше"))
+    }
+}
+
+impl Drop for GeyserRecorder {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// [`GeyserRecorder`] в отдельном блокирующем потоке
+///
+/// Сжатие и запись на диск не задерживают поток Geyser: обновления уходят в ограниченную
+/// очередь, а при её переполнении не записываются (счётчик — [`BackgroundRecorder::dropped`]).
+pub struct BackgroundRecorder {
+    sender: mpsc::Sender<(SubscribeUpdate, u64)>,
+    dropped: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+
+impl BackgroundRecorder {
+    /// Запускает запись через `recorder` в `spawn_blocking` с очередью на `capacity` обновлений
+    pub fn spawn(mut recorder: GeyserRecorder, capacity: usize) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<(SubscribeUpdate, u64)>(capacity);
+        let task = tokio::task::spawn_blocking(move || {
+            while let Some((update, received_at_micros)) = receiver.blocking_recv() {
+                if let Err(e) = recorder.record_at(&update, received_at_micros) {
+                    eprintln!("Ошибка записи потока Geyser: {:?}", e);
+                }
+            }
+            if let Err(e) = recorder.flush() {
+                eprintln!("Ошибка записи потока Geyser: {:?}", e);
+            }
+        });
+
+        Self {
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+            task,
+        }
+    }
+
+    /// Ставит обновление в очередь записи с текущим временем получения, не блокируясь
+    ///
+    /// # Возвращает
+    /// `false`, если очередь переполнена и обновление не будет записано
+    pub fn record(&self, update: &SubscribeUpdate) -> bool {
+        match self.sender.try_send((update.clone(), now_micros())) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                let dropped = self.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                if dropped.is_power_of_two() {
+                    eprintln!(
+                        "Очередь записи Geyser переполнена, не записано обновлений: {}",
+                        dropped
+                    );
+                }
+                false
+            }
+            Err(TrySendError::Closed(_)) => false,
+        }
+    }
+
+    /// Сколько обновлений не записано из-за переполнения очереди
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Дописывает очередь, сбрасывает файл на диск и останавливает поток записи
+    pub async fn shutdown(self) -> Result<()> {
+        drop(self.sender);
+        self.task
+            .await
+            .context("Поток записи Geyser завершился с ошибкой")
+    }
+}
+
+/// Последовательно читает кадры одного файла записи
+///
+/// Оборванный последний кадр (запись прервана падением процесса) считается концом файла.
+pub struct RecordingReader<R: Read> {
+    inner: R,
+    finished: bool,
+}
+
+impl RecordingReader<BufReader<File>> {
+    /// Открывает файл записи и проверяет его сигнатуру
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Невозможно открыть запись {}", path.display()))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Создаёт читатель поверх произвольного источника байт
+    pub fn new(mut inner: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        inner
+            .read_exact(&mut magic)
+            .context("Файл записи слишком короткий")?;
+        if &magic != MAGIC {
+            bail!("Неизвестный формат файла записи");
+        }
+
+        Ok(Self {
+            inner,
+            finished: false,
+        })
+    }
+
+    fn read_frame(&mut self) -> Result<Option<RecordedUpdate>> {
+        let mut len_buf = [0u8; 4];
+        if !self.read_or_eof(&mut len_buf)? {
+            return Ok(None);
+        }
+
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > MAX_FRAME_LEN {
+            bail!("Слишком большой кадр в записи: {} байт", len);
+        }
+
+        let mut frame = vec![0u8; len];
+        if !self.read_or_eof(&mut frame)? {
+            eprintln!("Запись обрывается посреди кадра, остаток файла пропущен");
+            return Ok(None);
+        }
+
+        let mut payload = Vec::new();
+        DeflateDecoder::new(frame.as_slice()).read_to_end(&mut payload)?;
+        if payload.len() < 8 {
+            bail!("Кадр записи без метки времени");
+        }
+
+        let (ts, body) = payload.split_at(8);
+        Ok(Some(RecordedUpdate {
+            received_at_micros: u64::from_le_bytes(ts.try_into()?),
+            update: SubscribeUpdate::decode(body)?,
+        }))
+    }
+
+    /// Заполняет `buf` целиком; `false` — файл закончился раньше
+    fn read_or_eof(&mut self, buf: &mut [u8]) -> Result<bool> {
+        match self.inner.read_exact(buf) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = Result<RecordedUpdate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.read_frame() {
+            Ok(Some(update)) => Some(Ok(update)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Возвращает файлы записи из каталога в порядке их создания
+pub fn recording_files<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir.as_ref())
+        .with_context(|| format!("Невозможно прочитать каталог {}", dir.as_ref().display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some(EXTENSION))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+/// Итерирует все обновления записи по пути `path`: одному файлу или каталогу с ротацией
+pub fn read_recording<P: AsRef<Path>>(
+    path: P,
+) -> Result<Box<dyn Iterator<Item = Result<RecordedUpdate>>>> {
+    let path = path.as_ref();
+    if path.is_file() {
+        return Ok(Box::new(RecordingReader::open(path)?));
+    }
+    if !path.is_dir() {
+        return Err(anyhow!("Запись {} не найдена", path.display()));
+    }
+
+    let iter =
+        recording_files(path)?
+            .into_iter()
+            .flat_map(|file| match RecordingReader::open(&file) {
+                Ok(reader) => Box::new(reader) as Box<dyn Iterator<Item = Result<RecordedUpdate>>>,
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            });
+    Ok(Box::new(iter))
+}
+
+fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or_default()
+}
@@ -1,6 +1,9 @@
-use solana_sdk::signature::Keypair;
-use anyhow::{Result, Context};
+use anyhow::{Context, Result};
 use bs58;
+use solana_sdk::signature::Keypair;
+use zeroize::Zeroizing;
+
+use crate::secrets::SecretSource;
 
 /// Декодирует приватный ключ в формате base58 в объект `Keypair`.
 ///
@@ -14,10 +17,20 @@ use bs58;
 /// - `Err` — если строка невалидная или формат несовместим с `Keypair::from_bytes`
 ///
 pub fn read_keypair_from_base58_string(key_str: &str) -> Result<Keypair> {
-    let data = bs58::decode(key_str)
-        .into_vec()
-        .context("Невозможно декодировать base58 строку")?;
+    // Декодированные байты ключа затираются после создания Keypair
+    let data = Zeroizing::new(
+        bs58::decode(key_str)
+            .into_vec()
+            .context("Невозможно декодировать base58 строку")?,
+    );
 
     let keypair = Keypair::from_bytes(&data).context("Невалидный формат ключа")?;
     Ok(keypair)
 }
+
+/// Загружает секрет из источника и декодирует его как base58-ключ
+pub fn load_keypair(source: &SecretSource) -> Result<Keypair> {
+    let secret = source.load()?;
+    read_keypair_from_base58_string(secret.expose())
+        .with_context(|| format!("Ошибка загрузки ключа из {}", source))
+}
@@ -5,3 +5,23 @@ pub mod wsol;
 pub mod config;
 pub mod keyloader;
 pub mod geyser;
+pub mod store;
+pub mod replay;
+pub mod backtest;
+pub mod relay;
+pub mod context;
+pub mod cli;
+pub mod executor;
+pub mod blockhash;
+pub mod rpc;
+pub mod tpu;
+pub mod risk;
+pub mod filters;
+pub mod reputation;
+pub mod sizing;
+pub mod wallets;
+pub mod signer;
+pub mod secrets;
+pub mod dlmm;
+pub mod damm_v2;
+pub mod dbc;
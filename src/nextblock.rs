@@ -1,36 +1,119 @@
-use anyhow::Result;
-use reqwest::Client;
+use futures_util::future::BoxFuture;
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    Client,
+};
 use serde_json::json;
 
-use crate::config::{NEXTBLOCK_URL, NEXTBLOCK_API_KEY};
+use crate::config::{NEXTBLOCK_API_KEY, NEXTBLOCK_URL};
+use crate::relay::{
+    parse_relay_response, shared_http_client, warm_up_connection, Relay, RelayAck, RelayError,
+};
+use crate::secrets::Secret;
 
-/// Отправляет сериализованную транзакцию в NextBlock релейер
-///
-/// # Аргументы:
-/// - `tx_base64`: транзакция в base64
-/// - `tip`: для приоритизации в блоке
-///
-/// # Возвращает:
-/// - `Ok(())` при успешной отправке
-/// - `Err` при ошибке сети или некорректном ответе
-pub async fn send_to_nextblock(tx_base64: &str, tip: u64) -> Result<()> {
-    let client = Client::new();
-
-    let body = json!({
-        "tx": tx_base64,
-        "meta": {
-            "tip": tip.to_string()
+const RELAY_NAME: &str = "NextBlock";
+
+/// Клиент NextBlock с настраиваемым адресом и ключом
+#[derive(Debug, Clone)]
+pub struct NextBlockRelay {
+    url: String,
+    api_key: Secret,
+    client: Client,
+}
+
+impl NextBlockRelay {
+    /// Ретранслятор на общем HTTP-клиенте процесса
+    pub fn new(url: impl Into<String>, api_key: impl Into<Secret>) -> Self {
+        Self::with_client(shared_http_client(), url, api_key)
+    }
+
+    /// Ретранслятор на переданном HTTP-клиенте (например, из [`crate::relay::RelayRegistry`])
+    pub fn with_client(
+        client: Client,
+        url: impl Into<String>,
+        api_key: impl Into<Secret>,
+    ) -> Self {
+        Self {
+            url: url.into(),
+            api_key: api_key.into(),
+            client,
         }
-    });
+    }
+
+    /// Адрес и ключ из config.rs
+    pub fn from_config() -> anyhow::Result<Self> {
+        Self::from_config_with_client(shared_http_client())
+    }
+
+    /// Адрес и ключ из config.rs на переданном HTTP-клиенте
+    pub fn from_config_with_client(client: Client) -> anyhow::Result<Self> {
+        Ok(Self::with_client(client, NEXTBLOCK_URL, NEXTBLOCK_API_KEY.load()?))
+    }
 
-    let res = client
-        .post(NEXTBLOCK_URL)
-        .header("Authorization", format!("Bearer {}", NEXTBLOCK_API_KEY))
-        .json(&body)
-        .send()
-        .await?;
+    /// Отправляет сериализованную транзакцию в NextBlock релейер
+    ///
+    /// # Аргументы:
+    /// - `tx_base64`: транзакция в base64
+    /// - `tip`: для приоритизации в блоке
+    ///
+    /// # Возвращает:
+    /// - `Ok(RelayAck)` с подписью или UUID заявки при успешной отправке
+    /// - `Err(RelayError)` при ошибочном HTTP-статусе, ошибке в ответе или сбое сети
+    pub async fn send(&self, tx_base64: &str, tip: u64) -> Result<RelayAck, RelayError> {
+        let body = json!({
+            "tx": tx_base64,
+            "meta": {
+                "tip": tip.to_string()
+            }
+        });
 
-    println!("NextBlock response: {:?}", res.text().await?);
+        let res = self
+            .client
+            .post(&self.url)
+            .header(AUTHORIZATION, self.authorization()?)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| RelayError::transport(RELAY_NAME, e))?;
 
+        // Ретранслятор может повторить ключ в тексте ошибки
+        parse_relay_response(RELAY_NAME, res)
+            .await
+            .map_err(|e| e.scrub(&self.api_key))
+    }
+
+    fn authorization(&self) -> Result<HeaderValue, RelayError> {
+        self.api_key
+            .bearer_header()
+            .map_err(|e| RelayError::Auth {
+                relay: RELAY_NAME.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+impl Relay for NextBlockRelay {
+    fn name(&self) -> &str {
+        RELAY_NAME
+    }
+
+    fn send<'a>(
+        &'a self,
+        tx_base64: &'a str,
+        tip: u64,
+    ) -> BoxFuture<'a, Result<RelayAck, RelayError>> {
+        Box::pin(NextBlockRelay::send(self, tx_base64, tip))
+    }
+
+    fn warm_up(&self) -> BoxFuture<'_, anyhow::Result<()>> {
+        Box::pin(warm_up_connection(&self.client, &self.url))
+    }
+}
+
+/// Отправляет транзакцию в NextBlock по адресу и ключу из config.rs
+///
+/// См. [`NextBlockRelay::send`].
+pub async fn send_to_nextblock(tx_base64: &str, tip: u64) -> anyhow::Result<()> {
+    NextBlockRelay::from_config()?.send(tx_base64, tip).await?;
     Ok(())
 }
@@ -0,0 +1,495 @@
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use anyhow::Result;
+use futures_util::future::{join_all, BoxFuture};
+use reqwest::{header::RETRY_AFTER, Client, Response, StatusCode, Url};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::bloxroute::BloxrouteRelay;
+use crate::config::{BLOXROUTE_TIMEOUT_MS, NEXTBLOCK_TIMEOUT_MS};
+use crate::nextblock::NextBlockRelay;
+use crate::secrets::Secret;
+
+pub mod guard;
+pub mod routing;
+
+use guard::{GuardedRelay, RelayPolicy, RelayStats};
+use routing::RelayRouter;
+
+/// Подтверждение приёма транзакции ретранслятором
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayAck {
+    /// Подпись транзакции, если ретранслятор её вернул
+    pub signature: Option<String>,
+    /// Идентификатор заявки у ретранслятора
+    pub uuid: Option<String>,
+}
+
+/// Ошибка отправки через ретранслятор
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayError {
+    /// Ключ API отклонён (401/403)
+    Auth { relay: String, message: String },
+    /// Превышен лимит запросов (429)
+    RateLimited {
+        relay: String,
+        retry_after: Option<Duration>,
+    },
+    /// Ретранслятор принял запрос, но отклонил транзакцию
+    Rejected {
+        relay: String,
+        code: Option<i64>,
+        message: String,
+    },
+    /// Сбой соединения или ошибка сервера (5xx)
+    Transport { relay: String, message: String },
+    /// Ретранслятор не ответил за отведённое время
+    Timeout { relay: String, after: Duration },
+    /// Отправка не выполнялась: автомат защиты разомкнут после серии сбоев
+    CircuitOpen { relay: String, retry_in: Duration },
+    /// Успешный статус, но ответ не удалось разобрать
+    InvalidResponse { relay: String, body: String },
+}
+
+impl RelayError {
+    /// Имя ретранслятора, вернувшего ошибку
+    pub fn relay(&self) -> &str {
+        match self {
+            RelayError::Auth { relay, .. }
+            | RelayError::RateLimited { relay, .. }
+            | RelayError::Rejected { relay, .. }
+            | RelayError::Transport { relay, .. }
+            | RelayError::Timeout { relay, .. }
+            | RelayError::CircuitOpen { relay, .. }
+            | RelayError::InvalidResponse { relay, .. } => relay,
+        }
+    }
+
+    /// Имеет ли смысл повторить отправку той же транзакции
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            RelayError::RateLimited { .. }
+                | RelayError::Transport { .. }
+                | RelayError::Timeout { .. }
+        )
+    }
+
+    pub(crate) fn transport(relay: &str, err: impl fmt::Display) -> Self {
+        RelayError::Transport {
+            relay: relay.to_string(),
+            message: err.to_string(),
+        }
+    }
+
+    /// Заменяет вхождения секрета в текстах ошибки на `***`
+    pub fn scrub(self, secret: &Secret) -> Self {
+        match self {
+            RelayError::Auth { relay, message } => RelayError::Auth {
+                relay,
+                message: secret.scrub(&message),
+            },
+            RelayError::Rejected {
+                relay,
+                code,
+                message,
+            } => RelayError::Rejected {
+                relay,
+                code,
+                message: secret.scrub(&message),
+            },
+            RelayError::Transport { relay, message } => RelayError::Transport {
+                relay,
+                message: secret.scrub(&message),
+            },
+            RelayError::InvalidResponse { relay, body } => RelayError::InvalidResponse {
+                relay,
+                body: secret.scrub(&body),
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for RelayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayError::Auth { relay, message } => {
+                write!(f, "{}: ключ API отклонён: {}", relay, message)
+            }
+            RelayError::RateLimited { relay, retry_after } => match retry_after {
+                Some(d) => write!(f, "{}: лимит запросов, повтор через {:?}", relay, d),
+                None => write!(f, "{}: лимит запросов", relay),
+            },
+            RelayError::Rejected {
+                relay,
+                code,
+                message,
+            } => match code {
+                Some(code) => write!(f, "{}: транзакция отклонена ({}): {}", relay, code, message),
+                None => write!(f, "{}: транзакция отклонена: {}", relay, message),
+            },
+            RelayError::Transport { relay, message } => {
+                write!(f, "{}: ошибка соединения: {}", relay, message)
+            }
+            RelayError::Timeout { relay, after } => {
+                write!(f, "{}: нет ответа за {:?}", relay, after)
+            }
+            RelayError::CircuitOpen { relay, retry_in } => {
+                write!(
+                    f,
+                    "{}: отключён после серии сбоев ещё на {:?}",
+                    relay, retry_in
+                )
+            }
+            RelayError::InvalidResponse { relay, body } => {
+                write!(f, "{}: неразборчивый ответ: {}", relay, body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RelayError {}
+
+/// Поле `error` в ответе: строка или объект с кодом
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ErrorField {
+    Message(String),
+    Detailed {
+        code: Option<i64>,
+        message: Option<String>,
+    },
+}
+
+/// Тело ответа ретранслятора. Bloxroute и NextBlock возвращают `signature` (NextBlock также `uuid`),
+/// а ошибки — либо в поле `error`, либо парой `code`/`message` на верхнем уровне
+#[derive(Debug, Default, Deserialize)]
+struct RelayReply {
+    signature: Option<String>,
+    uuid: Option<String>,
+    error: Option<ErrorField>,
+    code: Option<i64>,
+    message: Option<String>,
+}
+
+impl RelayReply {
+    /// Код и текст ошибки, если ответ её содержит
+    fn error(&self) -> Option<(Option<i64>, String)> {
+        match &self.error {
+            Some(ErrorField::Message(message)) => Some((self.code, message.clone())),
+            Some(ErrorField::Detailed { code, message }) => Some((
+                code.or(self.code),
+                message
+                    .clone()
+                    .unwrap_or_else(|| "без описания".to_string()),
+            )),
+            None => match (self.code, &self.message) {
+                (Some(code), Some(message)) if code != 0 => Some((Some(code), message.clone())),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Разбирает HTTP-ответ ретранслятора: проверяет статус и тело, классифицирует ошибки
+pub(crate) async fn parse_relay_response(
+    relay: &str,
+    res: Response,
+) -> std::result::Result<RelayAck, RelayError> {
+    let status = res.status();
+    let retry_after = res
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let text = res
+        .text()
+        .await
+        .map_err(|e| RelayError::transport(relay, e))?;
+    // Тело целиком не печатается: в нём может быть что угодно, вплоть до эха запроса
+    let reply = serde_json::from_str::<RelayReply>(&text);
+    let describe = |reply: &std::result::Result<RelayReply, serde_json::Error>| {
+        reply
+            .as_ref()
+            .ok()
+            .and_then(RelayReply::error)
+            .map(|(_, message)| message)
+            .unwrap_or_else(|| truncate_body(&text))
+    };
+
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            return Err(RelayError::Auth {
+                relay: relay.to_string(),
+                message: describe(&reply),
+            })
+        }
+        StatusCode::TOO_MANY_REQUESTS => {
+            return Err(RelayError::RateLimited {
+                relay: relay.to_string(),
+                retry_after,
+            })
+        }
+        s if s.is_server_error() => {
+            return Err(RelayError::Transport {
+                relay: relay.to_string(),
+                message: format!("HTTP {}: {}", s.as_u16(), describe(&reply)),
+            })
+        }
+        s if !s.is_success() => {
+            return Err(RelayError::Rejected {
+                relay: relay.to_string(),
+                code: reply
+                    .as_ref()
+                    .ok()
+                    .and_then(RelayReply::error)
+                    .and_then(|(code, _)| code)
+                    .or(Some(s.as_u16() as i64)),
+                message: describe(&reply),
+            })
+        }
+        _ => {}
+    }
+
+    let reply = reply.map_err(|_| RelayError::InvalidResponse {
+        relay: relay.to_string(),
+        body: truncate_body(&text),
+    })?;
+    if let Some((code, message)) = reply.error() {
+        return Err(RelayError::Rejected {
+            relay: relay.to_string(),
+            code,
+            message,
+        });
+    }
+
+    Ok(RelayAck {
+        signature: reply.signature,
+        uuid: reply.uuid,
+    })
+}
+
+/// Начало тела ответа для сообщения об ошибке
+fn truncate_body(text: &str) -> String {
+    const MAX_CHARS: usize = 200;
+    match text.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Собирает HTTP-клиент для ретрансляторов: пул соединений с keep-alive,
+/// HTTP/2 (через ALPN) с ping'ами, чтобы соединение не закрылось в простое
+pub fn build_http_client() -> Client {
+    Client::builder()
+        .pool_max_idle_per_host(8)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(30))
+        .tcp_nodelay(true)
+        .http2_keep_alive_interval(Duration::from_secs(15))
+        .http2_keep_alive_timeout(Duration::from_secs(10))
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .unwrap_or_else(|e| {
+            eprintln!(
+                "Не удалось настроить HTTP-клиент, используется стандартный: {:?}",
+                e
+            );
+            Client::new()
+        })
+}
+
+/// Общий на процесс HTTP-клиент ретрансляторов: соединения переиспользуются между вызовами
+pub fn shared_http_client() -> Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(build_http_client).clone()
+}
+
+/// Открывает (или поддерживает) соединение с хостом ретранслятора лёгким HEAD-запросом
+///
+/// Статус ответа не важен — ошибкой считается только сбой соединения.
+pub(crate) async fn warm_up_connection(client: &Client, url: &str) -> Result<()> {
+    let root = Url::parse(url)?.join("/")?;
+    client.head(root).send().await?;
+    Ok(())
+}
+
+/// MEV-ретранслятор, принимающий base64-сериализованную транзакцию
+pub trait Relay: Send + Sync {
+    /// Имя ретранслятора для логов
+    fn name(&self) -> &str;
+
+    /// Отправляет транзакцию с чаевыми `tip`
+    fn send<'a>(
+        &'a self,
+        tx_base64: &'a str,
+        tip: u64,
+    ) -> BoxFuture<'a, std::result::Result<RelayAck, RelayError>>;
+
+    /// Прогревает соединение, чтобы отправка не тратила время на DNS, TCP и TLS
+    fn warm_up(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Счётчики отправок и состояние автомата защиты, если ретранслятор их ведёт
+    fn stats(&self) -> Option<RelayStats> {
+        None
+    }
+}
+
+/// Набор ретрансляторов, через которые рассылается каждая транзакция
+///
+/// Хранит общий HTTP-клиент, который получают создаваемые через реестр ретрансляторы.
+/// С маршрутизатором ([`RelayRegistry::with_router`]) транзакция уходит только в ретрансляторы,
+/// подходящие ближайшим лидерам.
+#[derive(Clone)]
+pub struct RelayRegistry {
+    relays: Vec<Arc<dyn Relay>>,
+    http: Client,
+    router: Option<Arc<RelayRouter>>,
+}
+
+impl Default for RelayRegistry {
+    fn default() -> Self {
+        Self {
+            relays: vec![],
+            http: shared_http_client(),
+            router: None,
+        }
+    }
+}
+
+impl RelayRegistry {
+    /// Пустой реестр
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bloxroute и NextBlock с адресами и ключами из config.rs,
+    /// каждый со своим таймаутом, повторами и автоматом защиты.
+    /// Ретранслятор, ключ которого не загрузился, пропускается с предупреждением
+    pub fn from_config() -> Self {
+        let mut registry = Self::new();
+        let http = registry.http_client().clone();
+
+        match BloxrouteRelay::from_config_with_client(http.clone()) {
+            Ok(bloxroute) => {
+                registry = registry.with(Arc::new(GuardedRelay::new(
+                    Arc::new(bloxroute),
+                    RelayPolicy::from_config(Duration::from_millis(BLOXROUTE_TIMEOUT_MS)),
+                )));
+            }
+            Err(e) => eprintln!("Bloxroute отключён: {:#}", e),
+        }
+        match NextBlockRelay::from_config_with_client(http) {
+            Ok(nextblock) => {
+                registry = registry.with(Arc::new(GuardedRelay::new(
+                    Arc::new(nextblock),
+                    RelayPolicy::from_config(Duration::from_millis(NEXTBLOCK_TIMEOUT_MS)),
+                )));
+            }
+            Err(e) => eprintln!("NextBlock отключён: {:#}", e),
+        }
+        registry
+    }
+
+    /// Заменяет общий HTTP-клиент для ретрансляторов, добавляемых после вызова
+    pub fn with_http_client(mut self, http: Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// HTTP-клиент, общий для ретрансляторов реестра
+    pub fn http_client(&self) -> &Client {
+        &self.http
+    }
+
+    /// Добавляет ретранслятор
+    pub fn with(mut self, relay: Arc<dyn Relay>) -> Self {
+        self.relays.push(relay);
+        self
+    }
+
+    pub fn relays(&self) -> &[Arc<dyn Relay>] {
+        &self.relays
+    }
+
+    /// Включает выбор ретрансляторов и чаевых по ближайшим лидерам
+    pub fn with_router(mut self, router: RelayRouter) -> Self {
+        self.router = Some(Arc::new(router));
+        self
+    }
+
+    pub fn router(&self) -> Option<&RelayRouter> {
+        self.router.as_deref()
+    }
+
+    /// Одновременно отправляет транзакцию во все ретрансляторы
+    ///
+    /// С маршрутизатором — только в ретрансляторы из его плана и с чаевыми из плана
+    /// вместо `tip`.
+    ///
+    /// # Возвращает
+    /// Результат каждого ретранслятора вместе с его именем, в порядке регистрации
+    pub async fn broadcast(
+        &self,
+        tx_base64: &str,
+        tip: u64,
+    ) -> Vec<(String, std::result::Result<RelayAck, RelayError>)> {
+        let plan = self.router.as_ref().map(|router| router.plan());
+        let selected = self.relays.iter().filter_map(|relay| match &plan {
+            Some(plan) => Some((relay, plan.tip_for(relay.name())?)),
+            None => Some((relay, tip)),
+        });
+
+        join_all(selected.map(|(relay, tip)| async move {
+            (relay.name().to_string(), relay.send(tx_base64, tip).await)
+        }))
+        .await
+    }
+}
+
+impl RelayRegistry {
+    /// Счётчики и состояние автомата защиты каждого ретранслятора, который их ведёт
+    pub fn stats(&self) -> Vec<(String, RelayStats)> {
+        self.relays
+            .iter()
+            .filter_map(|relay| Some((relay.name().to_string(), relay.stats()?)))
+            .collect()
+    }
+
+    /// Прогревает соединения со всеми ретрансляторами
+    ///
+    /// # Возвращает
+    /// Результат каждого ретранслятора вместе с его именем, в порядке регистрации
+    pub async fn warm_up(&self) -> Vec<(String, Result<()>)> {
+        join_all(
+            self.relays
+                .iter()
+                .map(|relay| async move { (relay.name().to_string(), relay.warm_up().await) }),
+        )
+        .await
+    }
+
+    /// Прогревает соединения сразу и затем каждые `interval`, пока задача не будет остановлена
+    pub fn spawn_warmer(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                for (name, res) in self.warm_up().await {
+                    if let Err(e) = res {
+                        eprintln!("Не удалось прогреть соединение с {}: {:?}", name, e);
+                    }
+                }
+            }
+        })
+    }
+}
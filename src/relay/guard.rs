@@ -0,0 +1,291 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use futures_util::future::BoxFuture;
+
+use super::{Relay, RelayAck, RelayError};
+use crate::config::{
+    RELAY_BREAKER_COOLDOWN_MS, RELAY_BREAKER_THRESHOLD, RELAY_MAX_RETRIES, RELAY_RETRY_BACKOFF_MS,
+};
+
+/// Таймаут, повторы и параметры автомата защиты для одного ретранслятора
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayPolicy {
+    /// Сколько ждать ответа на одну попытку
+    pub timeout: Duration,
+    /// Сколько раз повторить отправку при временной ошибке
+    pub max_retries: u32,
+    /// Пауза перед повтором; растёт линейно с номером попытки
+    pub retry_backoff: Duration,
+    /// Сколько неудачных отправок подряд размыкают автомат
+    pub failure_threshold: u32,
+    /// На сколько ретранслятор отключается после размыкания
+    pub cooldown: Duration,
+}
+
+impl RelayPolicy {
+    /// Повторы и автомат защиты из config.rs с заданным таймаутом
+    pub fn from_config(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            max_retries: RELAY_MAX_RETRIES,
+            retry_backoff: Duration::from_millis(RELAY_RETRY_BACKOFF_MS),
+            failure_threshold: RELAY_BREAKER_THRESHOLD,
+            cooldown: Duration::from_millis(RELAY_BREAKER_COOLDOWN_MS),
+        }
+    }
+}
+
+/// Состояние автомата защиты
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Отправки идут как обычно
+    Closed,
+    /// Отправки не выполняются до `until`
+    Open { until: Instant },
+    /// Пауза истекла: следующая отправка пробная
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CircuitState::Closed => write!(f, "замкнут"),
+            CircuitState::Open { .. } => write!(f, "разомкнут"),
+            CircuitState::HalfOpen => write!(f, "пробный"),
+        }
+    }
+}
+
+/// Счётчики ретранслятора для логов и метрик
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelayStats {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Отправки, дошедшие до ретранслятора (без учёта повторов)
+    pub sent: u64,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub timeouts: u64,
+    pub retries: u64,
+    /// Отправки, отклонённые разомкнутым автоматом
+    pub short_circuited: u64,
+}
+
+struct GuardState {
+    stats: RelayStats,
+    /// В состоянии `HalfOpen` пропускается только одна пробная отправка
+    trial_in_flight: bool,
+}
+
+/// Обёртка над ретранслятором: ограничивает время ответа, повторяет отправку
+/// при временных ошибках и отключает ретранслятор после серии сбоев.
+///
+/// Отказ в самой транзакции ([`RelayError::Rejected`]) сбоем не считается —
+/// ретранслятор ответил, значит он работает.
+pub struct GuardedRelay {
+    inner: Arc<dyn Relay>,
+    policy: RelayPolicy,
+    state: Mutex<GuardState>,
+}
+
+impl GuardedRelay {
+    pub fn new(inner: Arc<dyn Relay>, policy: RelayPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            state: Mutex::new(GuardState {
+                stats: RelayStats {
+                    state: CircuitState::Closed,
+                    consecutive_failures: 0,
+                    sent: 0,
+                    succeeded: 0,
+                    failed: 0,
+                    timeouts: 0,
+                    retries: 0,
+                    short_circuited: 0,
+                },
+                trial_in_flight: false,
+            }),
+        }
+    }
+
+    pub fn policy(&self) -> &RelayPolicy {
+        &self.policy
+    }
+
+    /// Текущие счётчики и состояние автомата
+    pub fn snapshot(&self) -> RelayStats {
+        self.lock_state().stats
+    }
+
+    /// Отправляет транзакцию с таймаутом и повторами, если автомат это разрешает
+    pub async fn send_guarded(&self, tx_base64: &str, tip: u64) -> Result<RelayAck, RelayError> {
+        let mut trial = TrialGuard {
+            relay: self,
+            armed: self.acquire()?,
+        };
+
+        let mut attempt = 0;
+        let result = loop {
+            let res =
+                match tokio::time::timeout(self.policy.timeout, self.inner.send(tx_base64, tip))
+                    .await
+                {
+                    Ok(res) => res,
+                    Err(_) => {
+                        self.lock_state().stats.timeouts += 1;
+                        Err(RelayError::Timeout {
+                            relay: self.inner.name().to_string(),
+                            after: self.policy.timeout,
+                        })
+                    }
+                };
+
+            match res {
+                Err(e) if e.is_retryable() && attempt < self.policy.max_retries => {
+                    attempt += 1;
+                    self.lock_state().stats.retries += 1;
+                    eprintln!("{}, повтор {}/{}", e, attempt, self.policy.max_retries);
+
+                    let backoff = match &e {
+                        RelayError::RateLimited {
+                            retry_after: Some(after),
+                            ..
+                        } => (*after).min(self.policy.timeout),
+                        _ => self.policy.retry_backoff * attempt,
+                    };
+                    tokio::time::sleep(backoff).await;
+                }
+                other => break other,
+            }
+        };
+
+        trial.armed = false;
+        self.record(&result);
+        result
+    }
+
+    /// Проверяет автомат перед отправкой; при истёкшей паузе переводит его в пробный режим
+    ///
+    /// # Возвращает
+    /// `true`, если эта отправка пробная
+    fn acquire(&self) -> Result<bool, RelayError> {
+        let mut guard = self.lock_state();
+        let now = Instant::now();
+
+        match guard.stats.state {
+            CircuitState::Open { until } if now < until => {
+                guard.stats.short_circuited += 1;
+                return Err(RelayError::CircuitOpen {
+                    relay: self.inner.name().to_string(),
+                    retry_in: until - now,
+                });
+            }
+            CircuitState::Open { .. } => {
+                self.transition(&mut guard, CircuitState::HalfOpen);
+            }
+            CircuitState::HalfOpen if guard.trial_in_flight => {
+                guard.stats.short_circuited += 1;
+                return Err(RelayError::CircuitOpen {
+                    relay: self.inner.name().to_string(),
+                    retry_in: Duration::ZERO,
+                });
+            }
+            _ => {}
+        }
+
+        let trial = guard.stats.state == CircuitState::HalfOpen;
+        if trial {
+            guard.trial_in_flight = true;
+        }
+        guard.stats.sent += 1;
+        Ok(trial)
+    }
+
+    /// Учитывает результат отправки в счётчиках и автомате
+    fn record(&self, result: &Result<RelayAck, RelayError>) {
+        let mut guard = self.lock_state();
+        guard.trial_in_flight = false;
+
+        match result {
+            Ok(_) | Err(RelayError::Rejected { .. }) => {
+                if result.is_ok() {
+                    guard.stats.succeeded += 1;
+                } else {
+                    guard.stats.failed += 1;
+                }
+                guard.stats.consecutive_failures = 0;
+                if guard.stats.state != CircuitState::Closed {
+                    self.transition(&mut guard, CircuitState::Closed);
+                }
+            }
+            Err(_) => {
+                guard.stats.failed += 1;
+                guard.stats.consecutive_failures += 1;
+                let trip = guard.stats.state == CircuitState::HalfOpen
+                    || guard.stats.consecutive_failures >= self.policy.failure_threshold;
+                if trip {
+                    let until = Instant::now() + self.policy.cooldown;
+                    self.transition(&mut guard, CircuitState::Open { until });
+                }
+            }
+        }
+    }
+
+    fn transition(&self, guard: &mut GuardState, next: CircuitState) {
+        println!(
+            "Автомат защиты {}: {} -> {} (сбоев подряд: {})",
+            self.inner.name(),
+            guard.stats.state,
+            next,
+            guard.stats.consecutive_failures
+        );
+        guard.stats.state = next;
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, GuardState> {
+        // Состояние — простые счётчики, после паники их можно использовать как есть
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Снимает флаг пробной отправки, если `send_guarded` не дошёл до [`GuardedRelay::record`]:
+/// футура отменена или реле запаниковало. Автомат остаётся полуоткрытым до следующей пробы
+struct TrialGuard<'a> {
+    relay: &'a GuardedRelay,
+    armed: bool,
+}
+
+impl Drop for TrialGuard<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.relay.lock_state().trial_in_flight = false;
+        }
+    }
+}
+
+impl Relay for GuardedRelay {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn send<'a>(
+        &'a self,
+        tx_base64: &'a str,
+        tip: u64,
+    ) -> BoxFuture<'a, Result<RelayAck, RelayError>> {
+        Box::pin(self.send_guarded(tx_base64, tip))
+    }
+
+    fn warm_up(&self) -> BoxFuture<'_, anyhow::Result<()>> {
+        self.inner.warm_up()
+    }
+
+    fn stats(&self) -> Option<RelayStats> {
+        Some(self.snapshot())
+    }
+}
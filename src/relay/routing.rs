@@ -0,0 +1,124 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::config::{RELAY_DEFAULT_ROUTE, RELAY_LEADER_ROUTES, RELAY_ROUTING_LEADERS};
+use crate::tpu::leaders::LeaderTracker;
+
+/// Ретрансляторы и чаевые для одного валидатора: имя ретранслятора → чаевые
+pub type Route = BTreeMap<String, u64>;
+
+/// Выбор ретрансляторов для конкретной транзакции
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayPlan {
+    /// Ближайшие лидеры, по которым составлен план; пусто, если расписание неизвестно
+    pub leaders: Vec<Pubkey>,
+    /// Ретрансляторы, через которые стоит отправить транзакцию, и чаевые для каждого
+    pub route: Route,
+}
+
+impl RelayPlan {
+    /// Чаевые для ретранслятора или `None`, если он в план не входит
+    pub fn tip_for(&self, relay: &str) -> Option<u64> {
+        self.route.get(relay).copied()
+    }
+}
+
+/// Выбирает ретрансляторы и чаевые по ближайшим лидерам.
+///
+/// Для каждого из `lookahead_leaders` ближайших лидеров берётся его маршрут
+/// (или маршрут по умолчанию), маршруты объединяются, а чаевые ретранслятора,
+/// встретившегося у нескольких лидеров, берутся наибольшие. Так ретранслятор,
+/// который доходит только до части валидаторов, используется лишь когда один из них скоро ведёт блок.
+pub struct RelayRouter {
+    leaders: Arc<LeaderTracker>,
+    default_route: Route,
+    by_validator: HashMap<Pubkey, Route>,
+    lookahead_leaders: usize,
+}
+
+impl RelayRouter {
+    /// Создаёт маршрутизатор без маршрутов для отдельных валидаторов
+    ///
+    /// # Аргументы
+    /// * `leaders` — расписание лидеров
+    /// * `default_route` — ретрансляторы и чаевые для лидеров без собственного маршрута
+    /// * `lookahead_leaders` — сколько ближайших лидеров учитывать
+    pub fn new<S: Into<String>>(
+        leaders: Arc<LeaderTracker>,
+        default_route: impl IntoIterator<Item = (S, u64)>,
+        lookahead_leaders: usize,
+    ) -> Self {
+        Self {
+            leaders,
+            default_route: to_route(default_route),
+            by_validator: HashMap::new(),
+            lookahead_leaders: lookahead_leaders.max(1),
+        }
+    }
+
+    /// Задаёт маршрут для валидатора
+    pub fn with_validator<S: Into<String>>(
+        mut self,
+        validator: Pubkey,
+        route: impl IntoIterator<Item = (S, u64)>,
+    ) -> Self {
+        self.by_validator.insert(validator, to_route(route));
+        self
+    }
+
+    /// Маршруты из `RELAY_DEFAULT_ROUTE` и `RELAY_LEADER_ROUTES`
+    pub fn from_config(leaders: Arc<LeaderTracker>) -> Result<Self> {
+        let mut router = Self::new(
+            leaders,
+            RELAY_DEFAULT_ROUTE.iter().copied(),
+            RELAY_ROUTING_LEADERS,
+        );
+        for (validator, route) in RELAY_LEADER_ROUTES {
+            let validator = Pubkey::from_str(validator).with_context(|| {
+                format!("Невалидный валидатор в RELAY_LEADER_ROUTES: {}", validator)
+            })?;
+            router = router.with_validator(validator, route.iter().copied());
+        }
+        Ok(router)
+    }
+
+    /// Маршрут валидатора с учётом маршрута по умолчанию
+    pub fn route_for(&self, validator: &Pubkey) -> &Route {
+        self.by_validator
+            .get(validator)
+            .unwrap_or(&self.default_route)
+    }
+
+    /// Составляет план отправки по ближайшим лидерам
+    pub fn plan(&self) -> RelayPlan {
+        let leaders = self.leaders.upcoming_leaders(self.lookahead_leaders);
+        if leaders.is_empty() {
+            return RelayPlan {
+                leaders,
+                route: self.default_route.clone(),
+            };
+        }
+
+        let mut route = Route::new();
+        for leader in &leaders {
+            for (relay, tip) in self.route_for(leader) {
+                let entry = route.entry(relay.clone()).or_default();
+                *entry = (*entry).max(*tip);
+            }
+        }
+        RelayPlan { leaders, route }
+    }
+}
+
+fn to_route<S: Into<String>>(route: impl IntoIterator<Item = (S, u64)>) -> Route {
+    route
+        .into_iter()
+        .map(|(relay, tip)| (relay.into(), tip))
+        .collect()
+}
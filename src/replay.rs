@@ -0,0 +1,127 @@
+use std::{path::Path, time::Duration};
+
+use anyhow::Result;
+use futures_util::{stream, Stream};
+use solana_sdk::{hash::Hash, transaction::VersionedTransaction};
+use tokio::time::Instant;
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+use crate::config::{BUY_MIN_OUT, RELAYER_TIP_LAMPORTS};
+use crate::filters::LaunchFilter;
+use crate::geyser::{
+    recorder::{read_recording, RecordedUpdate},
+    run_pipeline, Decision, DecisionHandler,
+};
+use crate::signer::TxSigner;
+use crate::store::Store;
+use crate::swap::build_swap_transaction;
+
+/// Скорость воспроизведения записи
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaySpeed {
+    /// С исходными интервалами между обновлениями
+    Original,
+    /// Без пауз
+    AsFastAsPossible,
+}
+
+/// Решение конвейера при воспроизведении вместе с транзакцией, которая была бы отправлена
+#[derive(Debug, Clone)]
+pub struct ReplayDecision {
+    pub decision: Decision,
+    pub transaction: Option<VersionedTransaction>,
+}
+
+/// Собирает решения и строит транзакции покупки, ничего не отправляя
+struct ReplayCollector<'a> {
+    payer: &'a dyn TxSigner,
+    decisions: Vec<ReplayDecision>,
+}
+
+impl DecisionHandler for ReplayCollector<'_> {
+    async fn handle(&mut self, decision: Decision) {
+        let transaction = match &decision {
+            // Blockhash не влияет на решение, поэтому берём нулевой — так результат детерминирован
+            Decision::Buy(launch) => match build_swap_transaction(
+                self.payer,
+                &launch.accounts,
+                launch.amount_in,
+                BUY_MIN_OUT,
+                RELAYER_TIP_LAMPORTS,
+                Hash::default(),
+            )
+            .await
+            {
+                Ok(tx) => Some(tx),
+                Err(e) => {
+                    eprintln!(
+                        "Ошибка сборки транзакции для {}: {:?}",
+                        launch.accounts.pool, e
+                    );
+                    None
+                }
+            },
+            Decision::Skip { .. } => None,
+        };
+
+        self.decisions.push(ReplayDecision {
+            decision,
+            transaction,
+        });
+    }
+}
+
+/// Прогоняет запись потока Geyser через конвейер обнаружения
+///
+/// Используется чистое хранилище в памяти и фильтр без списков,
+/// поэтому результат зависит только от записи.
+///
+/// # Аргументы
+/// * `path` — файл записи или каталог с ротированными файлами
+/// * `speed` — скорость воспроизведения
+/// * `payer` — подписант построенных транзакций
+///
+/// # Возвращает
+/// Все решения конвейера в порядке их принятия
+pub async fn replay_recording<P: AsRef<Path>>(
+    path: P,
+    speed: ReplaySpeed,
+    payer: &dyn TxSigner,
+) -> Result<Vec<ReplayDecision>> {
+    let store = Store::open_in_memory(0)?;
+    let mut collector = ReplayCollector {
+        payer,
+        decisions: vec![],
+    };
+
+    let updates = paced_updates(read_recording(path)?, speed);
+
+    run_pipeline(updates, &store, &LaunchFilter::allow_all(), &mut collector).await?;
+    Ok(collector.decisions)
+}
+
+/// Превращает итератор записи в поток; в режиме `Original` выдерживает исходные интервалы
+fn paced_updates(
+    recorded: Box<dyn Iterator<Item = Result<RecordedUpdate>>>,
+    speed: ReplaySpeed,
+) -> impl Stream<Item = Result<SubscribeUpdate>> + Unpin {
+    let started = Instant::now();
+
+    Box::pin(stream::unfold(
+        (recorded, None::<u64>),
+        move |(mut recorded, first_ts)| async move {
+            let (item, first_ts) = match recorded.next()? {
+                Ok(rec) => {
+                    let first = first_ts.unwrap_or(rec.received_at_micros);
+                    if speed == ReplaySpeed::Original {
+                        let offset = rec.received_at_micros.saturating_sub(first);
+                        tokio::time::sleep_until(started + Duration::from_micros(offset)).await;
+                    }
+                    (Ok(rec.update), Some(first))
+                }
+                Err(e) => (Err(e), first_ts),
+            };
+            Some((item, (recorded, first_ts)))
+        },
+    ))
+}
@@ -0,0 +1,404 @@
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use solana_client::{
+    rpc_client::GetConfirmedSignaturesForAddress2Config, rpc_config::RpcTransactionConfig,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionEncoding,
+    UiTransactionTokenBalance,
+};
+
+use crate::config::{
+    METEORA_PROGRAM_ID, REPUTATION_CACHE_TTL_SECS, REPUTATION_FUNDER_PENALTY,
+    REPUTATION_HISTORY_LIMIT, REPUTATION_LAUNCH_PENALTY, REPUTATION_MIN_SCORE,
+    REPUTATION_RUG_PENALTY, REPUTATION_RUG_WINDOW_SECS, WSOL_MINT,
+};
+use crate::geyser::detect_pool_inits_in_transaction;
+use crate::rpc::RpcPool;
+use crate::store::{DeployerHistory, Store};
+
+/// Сколько транзакций истории запрашивается одновременно
+const FETCH_CONCURRENCY: usize = 8;
+
+/// Параметры оценки создателей пулов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReputationParams {
+    /// Покупать только при оценке не ниже этой
+    pub min_score: u8,
+    /// Сколько последних транзакций создателя просматривать
+    pub history_limit: usize,
+    /// Сколько секунд история в хранилище считается свежей
+    pub cache_ttl_secs: i64,
+    /// Изъятие ликвидности быстрее этого после запуска считается rug-ом, секунды
+    pub rug_window_secs: i64,
+    /// Штраф за каждый прежний запуск
+    pub launch_penalty: u8,
+    /// Штраф за каждый rug
+    pub rug_penalty: u8,
+    /// Штраф, если тот же источник средств пополнял создателей с rug-ами
+    pub funder_penalty: u8,
+}
+
+impl ReputationParams {
+    /// Параметры из config.rs
+    pub fn from_config() -> Self {
+        Self {
+            min_score: REPUTATION_MIN_SCORE,
+            history_limit: REPUTATION_HISTORY_LIMIT,
+            cache_ttl_secs: REPUTATION_CACHE_TTL_SECS,
+            rug_window_secs: REPUTATION_RUG_WINDOW_SECS,
+            launch_penalty: REPUTATION_LAUNCH_PENALTY,
+            rug_penalty: REPUTATION_RUG_PENALTY,
+            funder_penalty: REPUTATION_FUNDER_PENALTY,
+        }
+    }
+}
+
+/// Изменение баланса токен-аккаунта в транзакции
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+}
+
+/// Транзакция из истории кошелька — только то, что нужно для оценки
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletTx {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub tx: VersionedTransaction,
+    /// Адреса из lookup-таблиц (сначала writable, затем readonly)
+    pub loaded_addresses: Vec<Pubkey>,
+    pub token_balances: Vec<TokenBalanceChange>,
+    /// Изменение баланса SOL каждого аккаунта в порядке ключей
+    pub lamport_changes: Vec<(Pubkey, i128)>,
+}
+
+impl WalletTx {
+    /// Сводит транзакцию из `getTransaction`; неуспешные и нераскодированные пропускаются
+    pub fn from_encoded(encoded: EncodedConfirmedTransactionWithStatusMeta) -> Option<Self> {
+        let tx = encoded.transaction.transaction.decode()?;
+        let meta = encoded.transaction.meta?;
+        if meta.err.is_some() {
+            return None;
+        }
+
+        let loaded_addresses: Vec<Pubkey> = Option::from(meta.loaded_addresses)
+            .map(|loaded: UiLoadedAddresses| {
+                loaded
+                    .writable
+                    .iter()
+                    .chain(&loaded.readonly)
+                    .filter_map(|s| Pubkey::from_str(s).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut keys = tx.message.static_account_keys().to_vec();
+        keys.extend_from_slice(&loaded_addresses);
+        let lamport_changes = keys
+            .iter()
+            .zip(meta.pre_balances.iter().zip(&meta.post_balances))
+            .map(|(key, (pre, post))| (*key, *post as i128 - *pre as i128))
+            .collect();
+
+        let token_balances = token_balance_changes(
+            Option::from(meta.pre_token_balances).unwrap_or_default(),
+            Option::from(meta.post_token_balances).unwrap_or_default(),
+        );
+
+        Some(Self {
+            slot: encoded.slot,
+            block_time: encoded.block_time,
+            tx,
+            loaded_addresses,
+            token_balances,
+            lamport_changes,
+        })
+    }
+}
+
+/// Восстанавливает историю создателя по его транзакциям
+///
+/// # Аргументы
+/// * `creator` — кошелёк создателя
+/// * `txs` — транзакции от старых к новым
+/// * `complete` — в `txs` вся история кошелька, поэтому первая транзакция — пополнение
+/// * `current_pool` — пул, по которому идёт оценка; в прежние запуски не засчитывается
+/// * `rug_window_secs` — изъятие ликвидности быстрее этого считается rug-ом
+pub fn analyze_history(
+    creator: &Pubkey,
+    txs: &[WalletTx],
+    complete: bool,
+    current_pool: &Pubkey,
+    rug_window_secs: i64,
+) -> Result<DeployerHistory> {
+    let meteora_program = Pubkey::from_str(METEORA_PROGRAM_ID)?;
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+
+    // authority пула → (токен, время запуска, время изъятия ликвидности)
+    let mut launches: HashMap<Pubkey, (Pubkey, Option<i64>, Option<i64>)> = HashMap::new();
+
+    for wallet_tx in txs {
+        let inits = detect_pool_inits_in_transaction(
+            wallet_tx.slot,
+            &wallet_tx.tx,
+            &wallet_tx.loaded_addresses,
+            &meteora_program,
+        );
+        for init in inits {
+            if init.creator != *creator || init.accounts.pool == *current_pool {
+                continue;
+            }
+            let target_mint = if init.token_a == wsol_mint {
+                init.token_b
+            } else {
+                init.token_a
+            };
+            launches.entry(init.accounts.pool_authority).or_insert((
+                target_mint,
+                wallet_tx.block_time,
+                None,
+            ));
+        }
+
+        // Изъятие ликвидности — оба резерва пула уменьшились, как в бэктесте
+        for (authority, (target_mint, _, pulled_at)) in launches.iter_mut() {
+            if pulled_at.is_some() {
+                continue;
+            }
+            let decreased = |mint: &Pubkey| {
+                wallet_tx
+                    .token_balances
+                    .iter()
+                    .any(|b| b.owner == *authority && b.mint == *mint && b.post < b.pre)
+            };
+            if decreased(&wsol_mint) && decreased(target_mint) {
+                *pulled_at = Some(wallet_tx.block_time.unwrap_or_default());
+            }
+        }
+    }
+
+    let pulls: Vec<i64> = launches
+        .values()
+        .filter_map(|(_, launched_at, pulled_at)| Some((*pulled_at)? - (*launched_at)?))
+        .collect();
+
+    Ok(DeployerHistory {
+        creator: *creator,
+        launches: launches.len() as u32,
+        rugs: pulls.iter().filter(|&&secs| secs < rug_window_secs).count() as u32,
+        fastest_pull_secs: pulls.iter().min().copied(),
+        funder: if complete {
+            txs.first().and_then(|first| funder_of(creator, first))
+        } else {
+            None
+        },
+    })
+}
+
+/// Кошелёк, больше всех потерявший SOL в транзакции, где создатель их получил
+fn funder_of(creator: &Pubkey, tx: &WalletTx) -> Option<Pubkey> {
+    let received = tx
+        .lamport_changes
+        .iter()
+        .any(|(key, change)| key == creator && *change > 0);
+    if !received {
+        return None;
+    }
+    tx.lamport_changes
+        .iter()
+        .filter(|(key, change)| key != creator && *change < 0)
+        .min_by_key(|(_, change)| *change)
+        .map(|(key, _)| *key)
+}
+
+/// Оценка 0..=100: чем больше прежних запусков и rug-ов, тем ниже
+pub fn score(history: &DeployerHistory, funder_rugs: u32, params: &ReputationParams) -> u8 {
+    let penalty = history.launches as u64 * params.launch_penalty as u64
+        + history.rugs as u64 * params.rug_penalty as u64
+        + if funder_rugs > 0 {
+            params.funder_penalty as u64
+        } else {
+            0
+        };
+    100 - penalty.min(100) as u8
+}
+
+/// Репутация создателя пула
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reputation {
+    pub history: DeployerHistory,
+    /// rug-и других создателей с тем же источником средств
+    pub funder_rugs: u32,
+    pub score: u8,
+}
+
+impl fmt::Display for Reputation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let h = &self.history;
+        write!(
+            f,
+            "оценка {}: запусков {}, rug-ов {}",
+            self.score, h.launches, h.rugs
+        )?;
+        if let Some(secs) = h.fastest_pull_secs {
+            write!(f, ", самое быстрое изъятие ликвидности {} с", secs)?;
+        }
+        if let Some(funder) = h.funder {
+            write!(
+                f,
+                ", пополнен с {} (rug-ов у связанных: {})",
+                funder, self.funder_rugs
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Оценивает создателей пулов по их истории в сети.
+///
+/// История загружается через RPC один раз и хранится в [`Store`] `cache_ttl_secs` секунд,
+/// поэтому повторные запуски того же кошелька проверяются без обращения к сети.
+pub struct DeployerReputation {
+    rpc: Arc<RpcPool>,
+    store: Arc<Store>,
+    params: ReputationParams,
+}
+
+impl DeployerReputation {
+    pub fn new(rpc: Arc<RpcPool>, store: Arc<Store>, params: ReputationParams) -> Self {
+        Self { rpc, store, params }
+    }
+
+    pub fn params(&self) -> &ReputationParams {
+        &self.params
+    }
+
+    /// Оценивает создателя пула
+    ///
+    /// # Аргументы
+    /// * `creator` — создатель из init-транзакции
+    /// * `pool` — оцениваемый пул; в прежние запуски не засчитывается
+    pub async fn assess(&self, creator: &Pubkey, pool: &Pubkey) -> Result<Reputation> {
+        let history = match self.store.deployer(creator, self.params.cache_ttl_secs)? {
+            Some(history) => history,
+            None => {
+                let history = self.fetch_history(creator, pool).await?;
+                self.store.record_deployer(&history)?;
+                history
+            }
+        };
+
+        let funder_rugs = match &history.funder {
+            Some(funder) => self.store.funded_rugs(funder, creator)?,
+            None => 0,
+        };
+        Ok(Reputation {
+            score: score(&history, funder_rugs, &self.params),
+            history,
+            funder_rugs,
+        })
+    }
+
+    /// Загружает последние `history_limit` транзакций создателя и разбирает их
+    async fn fetch_history(&self, creator: &Pubkey, pool: &Pubkey) -> Result<DeployerHistory> {
+        let limit = self.params.history_limit;
+        let config = || GetConfirmedSignaturesForAddress2Config {
+            limit: Some(limit),
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..Default::default()
+        };
+        let signatures = self
+            .rpc
+            .read(|client| async move {
+                client
+                    .get_signatures_for_address_with_config(creator, config())
+                    .await
+            })
+            .await?;
+        let complete = signatures.len() < limit;
+
+        // Ответ идёт от новых к старым
+        let signatures: Vec<Signature> = signatures
+            .iter()
+            .rev()
+            .filter(|s| s.err.is_none())
+            .map(|s| {
+                Signature::from_str(&s.signature)
+                    .map_err(|e| anyhow!("Невалидная подпись {}: {}", s.signature, e))
+            })
+            .collect::<Result<_>>()?;
+
+        let tx_config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let txs: Vec<WalletTx> = stream::iter(signatures)
+            .map(|signature| async move {
+                let encoded = self
+                    .rpc
+                    .read(|client| async move {
+                        client
+                            .get_transaction_with_config(&signature, tx_config)
+                            .await
+                    })
+                    .await?;
+                Ok::<_, anyhow::Error>(WalletTx::from_encoded(encoded))
+            })
+            .buffered(FETCH_CONCURRENCY)
+            .try_filter_map(|tx| async move { Ok(tx) })
+            .try_collect()
+            .await?;
+
+        analyze_history(creator, &txs, complete, pool, self.params.rug_window_secs)
+    }
+}
+
+fn token_balance_changes(
+    pre: Vec<UiTransactionTokenBalance>,
+    post: Vec<UiTransactionTokenBalance>,
+) -> Vec<TokenBalanceChange> {
+    let parse = |b: &UiTransactionTokenBalance| -> Option<(u8, Pubkey, Pubkey, u64)> {
+        let owner: Option<String> = b.owner.clone().into();
+        Some((
+            b.account_index,
+            Pubkey::from_str(&owner?).ok()?,
+            Pubkey::from_str(&b.mint).ok()?,
+            b.ui_token_amount.amount.parse().ok()?,
+        ))
+    };
+
+    let mut changes: Vec<TokenBalanceChange> = vec![];
+    let mut by_index: HashMap<u8, usize> = HashMap::new();
+    for (index, owner, mint, amount) in pre.iter().filter_map(parse) {
+        by_index.insert(index, changes.len());
+        changes.push(TokenBalanceChange {
+            owner,
+            mint,
+            pre: amount,
+            post: 0,
+        });
+    }
+    for (index, owner, mint, amount) in post.iter().filter_map(parse) {
+        match by_index.get(&index) {
+            Some(&i) => changes[i].post = amount,
+            None => changes.push(TokenBalanceChange {
+                owner,
+                mint,
+                pre: 0,
+                post: amount,
+            }),
+        }
+    }
+    changes
+}
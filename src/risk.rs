@@ -0,0 +1,318 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey};
+
+use crate::config::{
+    PENDING_EXPIRY_SECS, RISK_BUY_WINDOW_MS, RISK_MAX_BUYS_PER_WINDOW,
+    RISK_MAX_DAILY_LOSS_LAMPORTS, RISK_MAX_EXPOSURE_LAMPORTS, RISK_MAX_OPEN_POSITIONS,
+    RISK_MAX_TRADE_LAMPORTS,
+};
+use crate::store::Store;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Лимиты риска
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RiskLimits {
+    /// Максимум лампортов на одну покупку
+    pub max_trade_lamports: u64,
+    /// Максимум лампортов в открытых позициях и покупках в процессе
+    pub max_exposure_lamports: u64,
+    /// Максимум одновременно открытых позиций
+    pub max_open_positions: usize,
+    /// Максимум покупок за `buy_window`
+    pub max_buys_per_window: usize,
+    pub buy_window: Duration,
+    /// Реализованный убыток за UTC-сутки, после которого покупки останавливаются
+    pub max_daily_loss_lamports: u64,
+}
+
+impl RiskLimits {
+    /// Лимиты из config.rs
+    pub fn from_config() -> Self {
+        Self {
+            max_trade_lamports: RISK_MAX_TRADE_LAMPORTS,
+            max_exposure_lamports: RISK_MAX_EXPOSURE_LAMPORTS,
+            max_open_positions: RISK_MAX_OPEN_POSITIONS,
+            max_buys_per_window: RISK_MAX_BUYS_PER_WINDOW,
+            buy_window: Duration::from_millis(RISK_BUY_WINDOW_MS),
+            max_daily_loss_lamports: RISK_MAX_DAILY_LOSS_LAMPORTS,
+        }
+    }
+}
+
+/// Уже занятый риск по данным хранилища
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Exposure {
+    /// Позиции с непроданными токенами
+    pub open_positions: usize,
+    /// Себестоимость непроданных токенов, лампорты
+    pub open_cost_lamports: u64,
+    /// Отправленные покупки без подтверждения, моложе `PENDING_EXPIRY_SECS`: пул → сумма
+    pub pending: HashMap<Pubkey, u64>,
+    /// Реализованная прибыль за текущие UTC-сутки (отрицательная — убыток)
+    pub realized_pnl_today: i64,
+    /// Номер текущих UTC-суток от начала эпохи unix
+    pub day: i64,
+}
+
+impl Exposure {
+    /// Считает открытые позиции, неподтверждённые покупки и реализованный результат за сегодня
+    pub fn from_store(store: &Store) -> Result<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        let day = now / SECONDS_PER_DAY;
+
+        let open: Vec<_> = store
+            .positions()?
+            .into_iter()
+            .filter(|p| p.tokens_held() > 0)
+            .collect();
+
+        // Более старые отправки уже не попадут в блок, см. `reconcile_pending`
+        let mut pending = HashMap::new();
+        for submission in store.pending_submissions()? {
+            if now - submission.submitted_at < PENDING_EXPIRY_SECS {
+                *pending.entry(submission.pool).or_default() += submission.amount_in;
+            }
+        }
+
+        Ok(Self {
+            open_positions: open.len(),
+            open_cost_lamports: open.iter().map(|p| p.held_cost()).sum(),
+            pending,
+            realized_pnl_today: store.realized_pnl_since(day * SECONDS_PER_DAY)?,
+            day,
+        })
+    }
+}
+
+/// Причина, по которой риск-менеджер отклонил покупку
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RiskRejection {
+    /// Сумма покупки больше лимита на сделку
+    TradeTooLarge { amount: u64, max: u64 },
+    /// Покупка превысит лимит вложенных средств
+    ExposureLimit {
+        exposure: u64,
+        amount: u64,
+        max: u64,
+    },
+    /// Открыто максимальное количество позиций
+    TooManyPositions { open: usize, max: usize },
+    /// Слишком много покупок за окно
+    BuyRateLimit { buys: usize, window: Duration },
+    /// Дневной убыток достиг лимита — покупки остановлены до конца суток
+    DailyLossLimit { loss: u64, max: u64 },
+}
+
+impl fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskRejection::TradeTooLarge { amount, max } => write!(
+                f,
+                "сумма {} SOL больше лимита на сделку {} SOL",
+                lamports_to_sol(*amount),
+                lamports_to_sol(*max)
+            ),
+            RiskRejection::ExposureLimit {
+                exposure,
+                amount,
+                max,
+            } => write!(
+                f,
+                "в позициях уже {} SOL, с покупкой на {} SOL превысим лимит {} SOL",
+                lamports_to_sol(*exposure),
+                lamports_to_sol(*amount),
+                lamports_to_sol(*max)
+            ),
+            RiskRejection::TooManyPositions { open, max } => {
+                write!(f, "открыто {} позиций из {}", open, max)
+            }
+            RiskRejection::BuyRateLimit { buys, window } => {
+                write!(f, "уже {} покупок за {:?}", buys, window)
+            }
+            RiskRejection::DailyLossLimit { loss, max } => write!(
+                f,
+                "дневной убыток {} SOL достиг лимита {} SOL, покупки остановлены",
+                lamports_to_sol(*loss),
+                lamports_to_sol(*max)
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// Одобренные покупки, которые ещё выполняются: пул → сумма и время одобрения
+    in_flight: HashMap<Pubkey, (u64, Instant)>,
+    /// Время одобренных покупок внутри окна
+    recent_buys: VecDeque<Instant>,
+    /// Сутки, в которые уже сообщено об остановке покупок
+    halted_day: Option<i64>,
+}
+
+/// Проверяет каждую покупку по лимитам риска.
+///
+/// Позиции, неподтверждённые покупки и дневной результат берутся из хранилища
+/// ([`Exposure::from_store`]), а покупки, которые одобрены, но ещё не исполнены,
+/// учитываются в памяти до [`RiskManager::release`].
+pub struct RiskManager {
+    limits: RiskLimits,
+    state: Mutex<State>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Одобряет покупку и резервирует под неё лимиты
+    ///
+    /// # Аргументы
+    /// * `pool` — пул покупки; резерв снимается через [`RiskManager::release`]
+    /// * `amount` — сумма покупки в лампортах
+    /// * `exposure` — занятый риск по данным хранилища
+    ///
+    /// # Возвращает
+    /// Причину отказа, если покупка нарушает хотя бы один лимит
+    pub fn approve(
+        &self,
+        pool: Pubkey,
+        amount: u64,
+        exposure: &Exposure,
+    ) -> Result<(), RiskRejection> {
+        self.check(&mut self.lock_state(), pool, amount, exposure)
+    }
+
+    /// Как [`RiskManager::approve`], но занятый риск читается из хранилища под той же
+    /// блокировкой: покупка, завершившаяся между чтением и проверкой, не выпадает из учёта
+    ///
+    /// # Возвращает
+    /// Ошибку хранилища или результат проверки лимитов
+    pub fn approve_from_store(
+        &self,
+        pool: Pubkey,
+        amount: u64,
+        store: &Store,
+    ) -> Result<Result<(), RiskRejection>> {
+        let mut state = self.lock_state();
+        let exposure = Exposure::from_store(store)?;
+        Ok(self.check(&mut state, pool, amount, &exposure))
+    }
+
+    fn check(
+        &self,
+        state: &mut State,
+        pool: Pubkey,
+        amount: u64,
+        exposure: &Exposure,
+    ) -> Result<(), RiskRejection> {
+        let limits = &self.limits;
+
+        let loss = exposure.realized_pnl_today.min(0).unsigned_abs();
+        if loss >= limits.max_daily_loss_lamports {
+            if state.halted_day != Some(exposure.day) {
+                state.halted_day = Some(exposure.day);
+                eprintln!(
+                    "Покупки остановлены до конца суток: убыток {} SOL",
+                    lamports_to_sol(loss)
+                );
+            }
+            return Err(RiskRejection::DailyLossLimit {
+                loss,
+                max: limits.max_daily_loss_lamports,
+            });
+        }
+
+        if amount > limits.max_trade_lamports {
+            return Err(RiskRejection::TradeTooLarge {
+                amount,
+                max: limits.max_trade_lamports,
+            });
+        }
+
+        // Выполняемая покупка может быть уже записана как неподтверждённая — считаем её один раз
+        let mut reserved = exposure.pending.clone();
+        for (reserved_pool, (reserved_amount, _)) in &state.in_flight {
+            reserved.insert(*reserved_pool, *reserved_amount);
+        }
+
+        let open = exposure.open_positions + reserved.len();
+        if open >= limits.max_open_positions {
+            return Err(RiskRejection::TooManyPositions {
+                open,
+                max: limits.max_open_positions,
+            });
+        }
+
+        let current = exposure.open_cost_lamports + reserved.values().sum::<u64>();
+        if current + amount > limits.max_exposure_lamports {
+            return Err(RiskRejection::ExposureLimit {
+                exposure: current,
+                amount,
+                max: limits.max_exposure_lamports,
+            });
+        }
+
+        let now = Instant::now();
+        while state
+            .recent_buys
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= limits.buy_window)
+        {
+            state.recent_buys.pop_front();
+        }
+        if state.recent_buys.len() >= limits.max_buys_per_window {
+            return Err(RiskRejection::BuyRateLimit {
+                buys: state.recent_buys.len(),
+                window: limits.buy_window,
+            });
+        }
+
+        state.recent_buys.push_back(now);
+        state.in_flight.insert(pool, (amount, now));
+        Ok(())
+    }
+
+    /// Снимает резерв покупки после её завершения — успешного или нет
+    pub fn release(&self, pool: &Pubkey) {
+        self.lock_state().in_flight.remove(pool);
+    }
+
+    /// Снимает резерв покупки, которая так и не была отправлена:
+    /// в отличие от [`RiskManager::release`], она не занимает и лимит покупок за окно
+    pub fn cancel(&self, pool: &Pubkey) {
+        let mut state = self.lock_state();
+        let Some((_, approved_at)) = state.in_flight.remove(pool) else {
+            return;
+        };
+        if let Some(i) = state.recent_buys.iter().position(|at| *at == approved_at) {
+            state.recent_buys.remove(i);
+        }
+    }
+
+    /// Количество одобренных, но ещё не завершённых покупок
+    pub fn in_flight(&self) -> usize {
+        self.lock_state().in_flight.len()
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,312 @@
+use std::{
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use futures_util::future::join_all;
+use solana_client::{
+    client_error::Result as ClientResult, nonblocking::rpc_client::RpcClient,
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    signature::Signature,
+    transaction::{self, VersionedTransaction},
+};
+
+use crate::config::{
+    RPC_CONFIRM_POLL_MS, RPC_ENDPOINTS, RPC_ENDPOINT_COOLDOWN_MS, RPC_ENDPOINT_FAILURE_THRESHOLD,
+};
+use crate::secrets::{redact_url, url_secrets, Secret};
+
+/// Для чего используется RPC-узел
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcRole {
+    /// Только чтение: blockhash, балансы, статусы транзакций
+    Read,
+    /// Только рассылка транзакций
+    Send,
+    /// И чтение, и рассылка
+    ReadSend,
+}
+
+impl RpcRole {
+    pub fn reads(self) -> bool {
+        matches!(self, RpcRole::Read | RpcRole::ReadSend)
+    }
+
+    pub fn sends(self) -> bool {
+        matches!(self, RpcRole::Send | RpcRole::ReadSend)
+    }
+}
+
+impl fmt::Display for RpcRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcRole::Read => write!(f, "чтение"),
+            RpcRole::Send => write!(f, "отправка"),
+            RpcRole::ReadSend => write!(f, "чтение и отправка"),
+        }
+    }
+}
+
+/// Здоровье RPC-узла по последним запросам
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EndpointStats {
+    /// Сглаженная задержка успешных запросов; `None`, пока успешных запросов не было
+    pub latency: Option<Duration>,
+    pub succeeded: u64,
+    pub failed: u64,
+    pub consecutive_failures: u32,
+    /// До этого момента узел опрашивается последним
+    pub unhealthy_until: Option<Instant>,
+}
+
+impl EndpointStats {
+    fn is_healthy(&self, now: Instant) -> bool {
+        self.unhealthy_until.is_none_or(|until| now >= until)
+    }
+}
+
+struct Endpoint {
+    /// Адрес для логов — без ключа доступа
+    url: String,
+    /// Ключи из адреса узла, которые вырезаются из текстов ошибок
+    secrets: Vec<Secret>,
+    role: RpcRole,
+    client: Arc<RpcClient>,
+    stats: Mutex<EndpointStats>,
+}
+
+impl Endpoint {
+    fn scrub(&self, text: &str) -> String {
+        self.secrets
+            .iter()
+            .fold(text.to_string(), |text, secret| secret.scrub(&text))
+    }
+
+    fn lock_stats(&self) -> MutexGuard<'_, EndpointStats> {
+        self.stats.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Набор RPC-узлов с ролями.
+///
+/// Чтения идут в самый быстрый здоровый узел и при ошибке переходят к следующему;
+/// узел, ошибившийся `failure_threshold` раз подряд, на `cooldown` уходит в конец очереди.
+/// Транзакции рассылаются сразу во все узлы с ролью отправки без preflight-симуляции.
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl RpcPool {
+    /// Создаёт пул из списка узлов с уровнем подтверждения "confirmed"
+    ///
+    /// # Возвращает
+    /// Ошибку, если нет ни одного узла для чтения или для отправки
+    pub fn new<S: Into<String>>(endpoints: impl IntoIterator<Item = (S, RpcRole)>) -> Result<Self> {
+        Self::from_clients(endpoints.into_iter().map(|(url, role)| {
+            let client = RpcClient::new_with_commitment(url.into(), CommitmentConfig::confirmed());
+            (Arc::new(client), role)
+        }))
+    }
+
+    /// Создаёт пул из готовых клиентов
+    pub fn from_clients(
+        clients: impl IntoIterator<Item = (Arc<RpcClient>, RpcRole)>,
+    ) -> Result<Self> {
+        let endpoints: Vec<Endpoint> = clients
+            .into_iter()
+            .map(|(client, role)| Endpoint {
+                url: redact_url(&client.url()),
+                secrets: url_secrets(&client.url()),
+                role,
+                client,
+                stats: Mutex::new(EndpointStats::default()),
+            })
+            .collect();
+
+        if !endpoints.iter().any(|e| e.role.reads()) {
+            bail!("Не задан ни один RPC-узел для чтения");
+        }
+        if !endpoints.iter().any(|e| e.role.sends()) {
+            bail!("Не задан ни один RPC-узел для отправки");
+        }
+
+        Ok(Self {
+            endpoints,
+            failure_threshold: RPC_ENDPOINT_FAILURE_THRESHOLD,
+            cooldown: Duration::from_millis(RPC_ENDPOINT_COOLDOWN_MS),
+        })
+    }
+
+    /// Пул из `RPC_ENDPOINTS`
+    pub fn from_config() -> Result<Self> {
+        Self::new(RPC_ENDPOINTS.iter().copied())
+    }
+
+    /// Меняет порог ошибок подряд и время, на которое узел отодвигается в конец очереди
+    pub fn with_health_policy(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Клиент лучшего узла для чтения — для кода, которому нужен `RpcClient` целиком
+    pub fn client(&self) -> Arc<RpcClient> {
+        self.read_order()[0].client.clone()
+    }
+
+    /// Выполняет чтение, переходя к следующему узлу при ошибке
+    ///
+    /// # Аргументы
+    /// * `op` — запрос к одному узлу; вызывается заново для каждого следующего узла
+    ///
+    /// # Возвращает
+    /// Первый успешный результат или ошибки всех узлов
+    pub async fn read<T, E, F, Fut>(&self, op: F) -> Result<T>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, E>>,
+        E: Into<anyhow::Error>,
+    {
+        let mut errors = vec![];
+        for endpoint in self.read_order() {
+            let started = Instant::now();
+            match op(endpoint.client.clone()).await {
+                Ok(value) => {
+                    self.record_success(endpoint, started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) => {
+                    let e = endpoint.scrub(&e.into().to_string());
+                    eprintln!("Ошибка чтения через RPC {}: {}", endpoint.url, e);
+                    self.record_failure(endpoint);
+                    errors.push(format!("{}: {}", endpoint.url, e));
+                }
+            }
+        }
+        Err(anyhow!("Все RPC-узлы вернули ошибку: {:?}", errors))
+    }
+
+    /// Рассылает транзакцию во все узлы с ролью отправки без preflight-симуляции
+    ///
+    /// # Возвращает
+    /// Результат каждого узла вместе с его адресом без ключа, в порядке конфигурации.
+    /// Тексты ошибок перед выводом пропускаются через [`RpcPool::scrub`]
+    pub async fn broadcast(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> Vec<(String, ClientResult<Signature>)> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+
+        join_all(
+            self.endpoints
+                .iter()
+                .filter(|e| e.role.sends())
+                .map(|endpoint| async move {
+                    let started = Instant::now();
+                    let res = endpoint
+                        .client
+                        .send_transaction_with_config(tx, config)
+                        .await;
+                    match &res {
+                        Ok(_) => self.record_success(endpoint, started.elapsed()),
+                        Err(_) => self.record_failure(endpoint),
+                    }
+                    (endpoint.url.clone(), res)
+                }),
+        )
+        .await
+    }
+
+    /// Ждёт, пока транзакция будет подтверждена или отклонена
+    ///
+    /// Неудачный опрос не прерывает ожидание: следующий повторяется до истечения `timeout`
+    ///
+    /// # Возвращает
+    /// `None`, если за `timeout` статус так и не появился; ошибку, если не удался последний опрос
+    pub async fn wait_for_confirmation(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Result<Option<transaction::Result<()>>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let status = self
+                .read(|rpc| async move { rpc.get_signature_status(signature).await })
+                .await;
+            match status {
+                Ok(Some(status)) => return Ok(Some(status)),
+                Ok(None) if Instant::now() >= deadline => return Ok(None),
+                Err(e) if Instant::now() >= deadline => return Err(e),
+                Ok(None) => {}
+                Err(e) => eprintln!("Статус {} не получен, опрос продолжается: {}", signature, e),
+            }
+            tokio::time::sleep(Duration::from_millis(RPC_CONFIRM_POLL_MS)).await;
+        }
+    }
+
+    /// Вырезает из текста ключи доступа, встречающиеся в адресах узлов
+    pub fn scrub(&self, text: &str) -> String {
+        self.endpoints
+            .iter()
+            .fold(text.to_string(), |text, endpoint| endpoint.scrub(&text))
+    }
+
+    /// Адрес без ключа, роль и здоровье каждого узла в порядке конфигурации
+    pub fn stats(&self) -> Vec<(String, RpcRole, EndpointStats)> {
+        self.endpoints
+            .iter()
+            .map(|e| (e.url.clone(), e.role, *e.lock_stats()))
+            .collect()
+    }
+
+    /// Узлы для чтения: сначала здоровые по возрастанию задержки, затем остальные.
+    /// Узел без замеров считается быстрым, чтобы его задержка была измерена
+    fn read_order(&self) -> Vec<&Endpoint> {
+        let now = Instant::now();
+        let mut order: Vec<(&Endpoint, EndpointStats)> = self
+            .endpoints
+            .iter()
+            .filter(|e| e.role.reads())
+            .map(|e| (e, *e.lock_stats()))
+            .collect();
+        order.sort_by_key(|(_, stats)| (!stats.is_healthy(now), stats.latency.unwrap_or_default()));
+        order.into_iter().map(|(e, _)| e).collect()
+    }
+
+    fn record_success(&self, endpoint: &Endpoint, elapsed: Duration) {
+        let mut stats = endpoint.lock_stats();
+        stats.succeeded += 1;
+        stats.consecutive_failures = 0;
+        stats.unhealthy_until = None;
+        stats.latency = Some(match stats.latency {
+            Some(prev) => prev.mul_f64(0.8) + elapsed.mul_f64(0.2),
+            None => elapsed,
+        });
+    }
+
+    fn record_failure(&self, endpoint: &Endpoint) {
+        let mut stats = endpoint.lock_stats();
+        stats.failed += 1;
+        stats.consecutive_failures += 1;
+        if stats.consecutive_failures >= self.failure_threshold {
+            if stats.is_healthy(Instant::now()) {
+                eprintln!(
+                    "RPC {} отключён на {:?} после {} ошибок подряд",
+                    endpoint.url, self.cooldown, stats.consecutive_failures
+                );
+            }
+            stats.unhealthy_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::{fmt, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use reqwest::{header::HeaderValue, Url};
+use zeroize::Zeroizing;
+
+/// Чем заменяется секрет в логах и сообщениях об ошибках
+pub const REDACTED: &str = "***";
+
+/// Секрет короче этого не вырезается из текста: иначе пострадают случайные совпадения
+const MIN_SCRUB_LEN: usize = 4;
+
+/// Откуда загружается секрет
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretSource {
+    /// Переменная окружения с таким именем
+    Env(&'static str),
+    /// Файл; пробелы и перевод строки по краям отбрасываются
+    File(&'static str),
+    /// Значение прямо в config.rs — только для адресов и тестовых значений
+    Inline(&'static str),
+}
+
+impl SecretSource {
+    /// Загружает секрет; в ошибке указывается источник, но не значение
+    pub fn load(&self) -> Result<Secret> {
+        let value = match self {
+            SecretSource::Env(name) => Zeroizing::new(
+                std::env::var(name)
+                    .with_context(|| format!("Переменная окружения {} не задана", name))?,
+            ),
+            SecretSource::File(path) => Zeroizing::new(
+                fs::read_to_string(Path::new(path))
+                    .with_context(|| format!("Ошибка чтения секрета из {}", path))?,
+            ),
+            SecretSource::Inline(value) => Zeroizing::new(value.to_string()),
+        };
+
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            bail!("Секрет из {} пуст", self);
+        }
+        Ok(Secret::new(trimmed))
+    }
+}
+
+impl fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretSource::Env(name) => write!(f, "${}", name),
+            SecretSource::File(path) => write!(f, "файла {}", path),
+            SecretSource::Inline(_) => write!(f, "config.rs"),
+        }
+    }
+}
+
+/// Строка, которую нельзя выводить: ключ API или приватный ключ.
+///
+/// `Debug` и `Display` печатают `***`, память затирается при удалении.
+/// Значение доступно только явно через [`Secret::expose`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(Zeroizing<String>);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(Zeroizing::new(value.into()))
+    }
+
+    /// Значение секрета — только для передачи туда, где оно нужно
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Заменяет вхождения секрета в тексте на `***`
+    pub fn scrub(&self, text: &str) -> String {
+        if self.0.len() < MIN_SCRUB_LEN {
+            return text.to_string();
+        }
+        text.replace(self.0.as_str(), REDACTED)
+    }
+
+    /// Заголовок `Authorization: Bearer …`, помеченный как чувствительный:
+    /// HTTP-клиент не выводит его в отладочной печати
+    pub fn bearer_header(&self) -> Result<HeaderValue> {
+        let value = Zeroizing::new(format!("Bearer {}", self.expose()));
+        let mut header =
+            HeaderValue::from_str(&value).context("Ключ API содержит недопустимые символы")?;
+        header.set_sensitive(true);
+        Ok(header)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret({})", REDACTED)
+    }
+}
+
+impl fmt::Display for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Части URL, в которых обычно передают ключи: пароль и значения параметров запроса
+pub fn url_secrets(url: &str) -> Vec<Secret> {
+    let Ok(parsed) = Url::parse(url) else {
+        return vec![];
+    };
+    parsed
+        .password()
+        .map(Secret::new)
+        .into_iter()
+        .chain(parsed.query_pairs().map(|(_, value)| Secret::new(value)))
+        .filter(|secret| !secret.expose().is_empty())
+        .collect()
+}
+
+/// Заменяет в тексте секреты из URL — например, в ошибке клиента, который к нему обращался
+pub fn scrub_url_secrets(url: &str, text: &str) -> String {
+    url_secrets(url)
+        .iter()
+        .fold(text.to_string(), |text, secret| secret.scrub(&text))
+}
+
+/// URL для логов: пароль и значения параметров запроса заменены на `***`
+pub fn redact_url(url: &str) -> String {
+    scrub_url_secrets(url, url)
+}
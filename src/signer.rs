@@ -0,0 +1,257 @@
+use std::{fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::future::{try_join_all, BoxFuture};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixStream,
+};
+
+use crate::config::{REMOTE_SIGNER, REMOTE_SIGNER_TIMEOUT_MS};
+
+/// Подписант транзакций: ключ в процессе бота или внешний сервис подписи
+pub trait TxSigner: Send + Sync {
+    /// Адрес, подпись которого ставится в транзакцию
+    fn pubkey(&self) -> Pubkey;
+
+    /// Подписывает сериализованное сообщение транзакции
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, Result<Signature>>;
+
+    /// Ключ, если он хранится в процессе бота; нужен для TLS-сертификата QUIC
+    fn keypair(&self) -> Option<&Keypair> {
+        None
+    }
+}
+
+impl TxSigner for Keypair {
+    fn pubkey(&self) -> Pubkey {
+        Signer::pubkey(self)
+    }
+
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, Result<Signature>> {
+        Box::pin(async move { Ok(self.try_sign_message(message)?) })
+    }
+
+    fn keypair(&self) -> Option<&Keypair> {
+        Some(self)
+    }
+}
+
+/// Подписывает транзакцию всеми подписантами параллельно
+///
+/// # Аргументы
+/// * `tx` — неподписанная транзакция; плательщик — первый подписант сообщения
+/// * `blockhash` — недавний blockhash
+/// * `signers` — подписанты в любом порядке; каждый должен быть среди подписантов сообщения
+///
+/// # Возвращает
+/// Транзакцию, в которой заполнены все подписи
+pub async fn sign_transaction(
+    mut tx: Transaction,
+    blockhash: Hash,
+    signers: &[&dyn TxSigner],
+) -> Result<Transaction> {
+    tx.message.recent_blockhash = blockhash;
+    let required = tx.message.header.num_required_signatures as usize;
+    let message = tx.message_data();
+
+    let positions = signers
+        .iter()
+        .map(|signer| {
+            let pubkey = signer.pubkey();
+            tx.message.account_keys[..required]
+                .iter()
+                .position(|key| *key == pubkey)
+                .with_context(|| format!("{} не подписывает эту транзакцию", pubkey))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let signatures =
+        try_join_all(signers.iter().map(|signer| signer.sign_message(&message))).await?;
+
+    tx.signatures = vec![Signature::default(); required];
+    for (position, signature) in positions.into_iter().zip(signatures) {
+        tx.signatures[position] = signature;
+    }
+    if let Some(missing) = tx
+        .signatures
+        .iter()
+        .position(|signature| *signature == Signature::default())
+    {
+        bail!(
+            "Нет подписи {} среди подписантов",
+            tx.message.account_keys[missing]
+        );
+    }
+    Ok(tx)
+}
+
+/// Адрес сервиса подписи
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerEndpoint {
+    /// `POST` JSON-запроса на URL
+    Http(Url),
+    /// Строка JSON-запроса в Unix-сокет, строка JSON-ответа обратно
+    Unix(PathBuf),
+}
+
+impl FromStr for SignerEndpoint {
+    type Err = anyhow::Error;
+
+    /// `unix:/путь/к/сокету` или `http(s)://…`
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(SignerEndpoint::Unix(PathBuf::from(path)));
+        }
+        let url =
+            Url::parse(s).with_context(|| format!("Невалидный адрес сервиса подписи {}", s))?;
+        match url.scheme() {
+            "http" | "https" => Ok(SignerEndpoint::Http(url)),
+            scheme => Err(anyhow!("Неподдерживаемая схема сервиса подписи {}", scheme)),
+        }
+    }
+}
+
+impl fmt::Display for SignerEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerEndpoint::Http(url) => write!(f, "{}", url),
+            SignerEndpoint::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Запрос к сервису подписи
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignRequest {
+    /// Адрес ключа, которым нужно подписать
+    pub pubkey: String,
+    /// Сообщение транзакции в base64
+    pub message: String,
+}
+
+/// Ответ сервиса подписи: подпись в base58 или текст ошибки
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Подписант, который передаёт сообщения локальному сервису подписи:
+/// ключи хранятся в отдельном процессе без доступа к сети
+pub struct RemoteSigner {
+    pubkey: Pubkey,
+    endpoint: SignerEndpoint,
+    client: Client,
+    timeout: Duration,
+}
+
+impl RemoteSigner {
+    pub fn new(endpoint: SignerEndpoint, pubkey: Pubkey, timeout: Duration) -> Self {
+        Self {
+            pubkey,
+            endpoint,
+            client: Client::new(),
+            timeout,
+        }
+    }
+
+    /// Сервис из `REMOTE_SIGNER`
+    ///
+    /// # Возвращает
+    /// `None`, если сервис подписи не задан
+    pub fn from_config(pubkey: Pubkey) -> Result<Option<Self>> {
+        REMOTE_SIGNER
+            .map(|endpoint| {
+                Ok(Self::new(
+                    endpoint.parse()?,
+                    pubkey,
+                    Duration::from_millis(REMOTE_SIGNER_TIMEOUT_MS),
+                ))
+            })
+            .transpose()
+    }
+
+    pub fn endpoint(&self) -> &SignerEndpoint {
+        &self.endpoint
+    }
+
+    async fn request(&self, request: &SignRequest) -> Result<SignResponse> {
+        match &self.endpoint {
+            SignerEndpoint::Http(url) => {
+                let response = self.client.post(url.clone()).json(request).send().await?;
+                let status = response.status();
+                let body = response.text().await?;
+                // Сервис может вернуть JSON с ошибкой и при неуспешном статусе
+                match serde_json::from_str::<SignResponse>(&body) {
+                    Ok(parsed) if status.is_success() || parsed.error.is_some() => Ok(parsed),
+                    _ if !status.is_success() => bail!("HTTP {}: {}", status, body),
+                    parsed => parsed.with_context(|| format!("Невалидный ответ: {}", body)),
+                }
+            }
+            SignerEndpoint::Unix(path) => {
+                let mut stream = UnixStream::connect(path)
+                    .await
+                    .with_context(|| format!("Нет соединения с {}", path.display()))?;
+                let mut line = serde_json::to_vec(request)?;
+                line.push(b'\n');
+                stream.write_all(&line).await?;
+
+                let mut response = String::new();
+                BufReader::new(stream).read_line(&mut response).await?;
+                serde_json::from_str(response.trim())
+                    .with_context(|| format!("Невалидный ответ: {}", response.trim()))
+            }
+        }
+    }
+}
+
+impl TxSigner for RemoteSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> BoxFuture<'a, Result<Signature>> {
+        Box::pin(async move {
+            let request = SignRequest {
+                pubkey: self.pubkey.to_string(),
+                message: general_purpose::STANDARD.encode(message),
+            };
+            let response = tokio::time::timeout(self.timeout, self.request(&request))
+                .await
+                .map_err(|_| anyhow!("нет ответа за {:?}", self.timeout))
+                .and_then(|response| response)
+                .with_context(|| format!("Ошибка сервиса подписи {}", self.endpoint))?;
+
+            if let Some(error) = response.error {
+                bail!("Сервис подписи {} отказал: {}", self.endpoint, error);
+            }
+            let signature = response
+                .signature
+                .context("Сервис подписи не вернул подпись")?;
+            let signature = Signature::from_str(&signature)
+                .with_context(|| format!("Невалидная подпись {}", signature))?;
+
+            // Подпись не тем ключом отклонили бы валидаторы — проверяем сразу
+            if !signature.verify(self.pubkey.as_ref(), message) {
+                bail!(
+                    "Сервис подписи {} вернул подпись не от {}",
+                    self.endpoint,
+                    self.pubkey
+                );
+            }
+            Ok(signature)
+        })
+    }
+}
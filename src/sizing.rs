@@ -0,0 +1,194 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::Result;
+use solana_sdk::{native_token::lamports_to_sol, pubkey::Pubkey};
+
+use crate::config::{
+    BUY_AMOUNT_LAMPORTS, BUY_LIQUIDITY_BPS, DBC_CURVE_BUY_AMOUNT_LAMPORTS, DBC_CURVE_CONFIGS,
+    DBC_CURVE_ENABLED, DBC_MIGRATION_BUY_AMOUNT_LAMPORTS, DBC_MIGRATION_ENABLED,
+    DBC_MIGRATION_MAX_LIQUIDITY_LAMPORTS, DBC_MIGRATION_MIN_LIQUIDITY_LAMPORTS,
+    MAX_POOL_LIQUIDITY_LAMPORTS, MIN_POOL_LIQUIDITY_LAMPORTS,
+};
+use crate::geyser::LaunchPhase;
+
+/// Пул не прошёл по начальной WSOL-ликвидности
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidityRejection {
+    /// Начальная ликвидность в лампортах; `None` — не удалось определить
+    pub liquidity: Option<u64>,
+    pub min: u64,
+    pub max: u64,
+}
+
+impl fmt::Display for LiquidityRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.liquidity {
+            Some(liquidity) => write!(
+                f,
+                "ликвидность {} SOL вне диапазона {}..={} SOL",
+                lamports_to_sol(liquidity),
+                lamports_to_sol(self.min),
+                lamports_to_sol(self.max)
+            ),
+            None => write!(f, "начальная ликвидность неизвестна, а диапазон задан"),
+        }
+    }
+}
+
+/// Допустимая начальная ликвидность пула и размер покупки относительно неё
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuySizing {
+    /// Минимальная начальная WSOL-ликвидность, лампорты
+    pub min_liquidity: u64,
+    /// Максимальная начальная WSOL-ликвидность, лампорты
+    pub max_liquidity: u64,
+    /// Покупка как доля ликвидности в б.п.; `None` — всегда `max_amount`
+    pub liquidity_bps: Option<u64>,
+    /// Сумма покупки без доли и её верхняя граница с долей, лампорты
+    pub max_amount: u64,
+}
+
+impl BuySizing {
+    /// Параметры из config.rs
+    pub fn from_config() -> Self {
+        Self {
+            min_liquidity: MIN_POOL_LIQUIDITY_LAMPORTS,
+            max_liquidity: MAX_POOL_LIQUIDITY_LAMPORTS,
+            liquidity_bps: BUY_LIQUIDITY_BPS,
+            max_amount: BUY_AMOUNT_LAMPORTS,
+        }
+    }
+
+    /// Фиксированная сумма без ограничений по ликвидности
+    pub fn fixed(amount: u64) -> Self {
+        Self {
+            min_liquidity: 0,
+            max_liquidity: u64::MAX,
+            liquidity_bps: None,
+            max_amount: amount,
+        }
+    }
+
+    /// Задан ли хоть какой-то диапазон ликвидности
+    pub fn is_bounded(&self) -> bool {
+        self.min_liquidity > 0 || self.max_liquidity < u64::MAX
+    }
+
+    /// Проверяет ликвидность и считает сумму покупки
+    ///
+    /// # Аргументы
+    /// * `liquidity` — начальная WSOL-ликвидность пула, если известна
+    ///
+    /// # Возвращает
+    /// Сумму покупки в лампортах или причину пропуска пула.
+    /// Пул с неизвестной ликвидностью пропускается, только если диапазон задан
+    pub fn amount_for(&self, liquidity: Option<u64>) -> Result<u64, LiquidityRejection> {
+        let rejection = LiquidityRejection {
+            liquidity,
+            min: self.min_liquidity,
+            max: self.max_liquidity,
+        };
+
+        let Some(liquidity) = liquidity else {
+            return if self.is_bounded() {
+                Err(rejection)
+            } else {
+                Ok(self.max_amount)
+            };
+        };
+        if !(self.min_liquidity..=self.max_liquidity).contains(&liquidity) {
+            return Err(rejection);
+        }
+
+        Ok(match self.liquidity_bps {
+            Some(bps) => {
+                let share = liquidity as u128 * bps as u128 / 10_000;
+                u64::try_from(share)
+                    .unwrap_or(u64::MAX)
+                    .clamp(1, self.max_amount.max(1))
+            }
+            None => self.max_amount,
+        })
+    }
+}
+
+/// Фаза запуска выключена или кривая не из разрешённого конфига
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseRejection {
+    CurveDisabled,
+    CurveConfig(Pubkey),
+    MigrationDisabled,
+}
+
+impl fmt::Display for PhaseRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PhaseRejection::CurveDisabled => write!(f, "покупки на кривой выключены"),
+            PhaseRejection::CurveConfig(config) => {
+                write!(f, "конфиг кривой {} не в DBC_CURVE_CONFIGS", config)
+            }
+            PhaseRejection::MigrationDisabled => write!(f, "покупки после миграции выключены"),
+        }
+    }
+}
+
+/// Размер покупки для каждой фазы запуска: новый пул, кривая DBC и пул после миграции
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseSizing {
+    pub pool: BuySizing,
+    /// `None` — покупки на кривой выключены
+    pub curve: Option<BuySizing>,
+    /// Разрешённые конфиги кривых; пусто — любые
+    pub curve_configs: Vec<Pubkey>,
+    /// `None` — покупки после миграции выключены
+    pub migration: Option<BuySizing>,
+}
+
+impl PhaseSizing {
+    /// Параметры из config.rs
+    ///
+    /// На кривой WSOL в начале нет, поэтому её покупка не зависит от ликвидности.
+    pub fn from_config() -> Result<Self> {
+        Ok(Self {
+            pool: BuySizing::from_config(),
+            curve: DBC_CURVE_ENABLED.then(|| BuySizing::fixed(DBC_CURVE_BUY_AMOUNT_LAMPORTS)),
+            curve_configs: DBC_CURVE_CONFIGS
+                .iter()
+                .map(|config| Pubkey::from_str(config))
+                .collect::<Result<_, _>>()?,
+            migration: DBC_MIGRATION_ENABLED.then_some(BuySizing {
+                min_liquidity: DBC_MIGRATION_MIN_LIQUIDITY_LAMPORTS,
+                max_liquidity: DBC_MIGRATION_MAX_LIQUIDITY_LAMPORTS,
+                liquidity_bps: None,
+                max_amount: DBC_MIGRATION_BUY_AMOUNT_LAMPORTS,
+            }),
+        })
+    }
+
+    /// Одинаковый размер во всех фазах, без списка конфигов
+    pub fn uniform(sizing: BuySizing) -> Self {
+        Self {
+            pool: sizing,
+            curve: Some(sizing),
+            curve_configs: vec![],
+            migration: Some(sizing),
+        }
+    }
+
+    /// Размер покупки для фазы или причина, по которой фаза не покупается
+    pub fn for_phase(&self, phase: &LaunchPhase) -> Result<BuySizing, PhaseRejection> {
+        match phase {
+            LaunchPhase::Pool => Ok(self.pool),
+            LaunchPhase::Curve { config } => {
+                let sizing = self.curve.ok_or(PhaseRejection::CurveDisabled)?;
+                if !self.curve_configs.is_empty() && !self.curve_configs.contains(config) {
+                    return Err(PhaseRejection::CurveConfig(*config));
+                }
+                Ok(sizing)
+            }
+            LaunchPhase::Migration { .. } => {
+                self.migration.ok_or(PhaseRejection::MigrationDisabled)
+            }
+        }
+    }
+}
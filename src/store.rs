@@ -0,0 +1,787 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::Path,
+    str::FromStr,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Result};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use crate::swap::SwapAccounts;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS seen_pools (
+    pool        TEXT PRIMARY KEY,
+    target_mint TEXT NOT NULL,
+    seen_at     INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS submissions (
+    signature    TEXT PRIMARY KEY,
+    pool         TEXT NOT NULL,
+    amount_in    INTEGER NOT NULL,
+    submitted_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS confirmations (
+    signature    TEXT PRIMARY KEY,
+    success      INTEGER NOT NULL,
+    error        TEXT,
+    confirmed_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS fills (
+    signature  TEXT PRIMARY KEY,
+    pool       TEXT NOT NULL,
+    mint       TEXT NOT NULL,
+    amount_in  INTEGER NOT NULL,
+    amount_out INTEGER NOT NULL,
+    filled_at  INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pool_accounts (
+    pool             TEXT PRIMARY KEY,
+    target_mint      TEXT NOT NULL,
+    user_source      TEXT NOT NULL,
+    user_destination TEXT NOT NULL,
+    pool_source      TEXT NOT NULL,
+    pool_destination TEXT NOT NULL,
+    pool_authority   TEXT NOT NULL,
+    token_program    TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sells (
+    signature  TEXT PRIMARY KEY,
+    pool       TEXT NOT NULL,
+    mint       TEXT NOT NULL,
+    amount_in  INTEGER NOT NULL,
+    amount_out INTEGER NOT NULL,
+    sold_at    INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS deployers (
+    creator           TEXT PRIMARY KEY,
+    launches          INTEGER NOT NULL,
+    rugs              INTEGER NOT NULL,
+    fastest_pull_secs INTEGER,
+    funder            TEXT,
+    checked_at        INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS deployers_funder ON deployers (funder);
+CREATE TABLE IF NOT EXISTS buy_wallets (
+    signature TEXT PRIMARY KEY,
+    wallet    TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pool_programs (
+    pool    TEXT PRIMARY KEY,
+    program TEXT NOT NULL
+);
+";
+
+/// Отправленная транзакция, для которой ещё не записано подтверждение
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingSubmission {
+    pub signature: Signature,
+    pub pool: Pubkey,
+    pub amount_in: u64,
+    pub submitted_at: i64,
+}
+
+/// Исполненная покупка
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub signature: Signature,
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub filled_at: i64,
+}
+
+/// Открытая позиция по токену: сумма покупок за вычетом продаж
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub mint: Pubkey,
+    /// Пул последней покупки
+    pub pool: Pubkey,
+    /// Потрачено лампортов на покупки
+    pub sol_spent: u64,
+    /// Получено лампортов от продаж
+    pub sol_received: u64,
+    pub tokens_bought: u64,
+    pub tokens_sold: u64,
+}
+
+impl Position {
+    /// Количество токенов, которое ещё не продано
+    pub fn tokens_held(&self) -> u64 {
+        self.tokens_bought.saturating_sub(self.tokens_sold)
+    }
+
+    /// Стоимость непроданных токенов по средней цене покупки, в лампортах
+    pub fn held_cost(&self) -> u64 {
+        if self.tokens_bought == 0 {
+            return 0;
+        }
+        (self.sol_spent as u128 * self.tokens_held() as u128 / self.tokens_bought as u128) as u64
+    }
+}
+
+/// История кошелька, создающего пулы
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeployerHistory {
+    pub creator: Pubkey,
+    /// Сколько пулов кошелёк запускал раньше
+    pub launches: u32,
+    /// Сколько из них лишились ликвидности быстрее окна rug-а
+    pub rugs: u32,
+    /// Самое быстрое изъятие ликвидности после запуска, секунды
+    pub fastest_pull_secs: Option<i64>,
+    /// Кошелёк, с которого создатель получил первые SOL; `None`, если неизвестен
+    pub funder: Option<Pubkey>,
+}
+
+/// Состояние, восстановленное из базы при старте
+#[derive(Debug, Default)]
+pub struct RecoveredState {
+    pub seen_pools: usize,
+    pub pending: Vec<PendingSubmission>,
+}
+
+/// Ограниченный кэш последних увиденных пулов: при переполнении вытесняются самые старые
+struct SeenCache {
+    capacity: usize,
+    set: HashSet<Pubkey>,
+    order: VecDeque<Pubkey>,
+}
+
+impl SeenCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            set: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn contains(&self, pool: &Pubkey) -> bool {
+        self.set.contains(pool)
+    }
+
+    fn insert(&mut self, pool: Pubkey) {
+        if self.capacity == 0 || !self.set.insert(pool) {
+            return;
+        }
+        self.order.push_back(pool);
+        while self.order.len() > self.capacity {
+            if let Some(old) = self.order.pop_front() {
+                self.set.remove(&old);
+            }
+        }
+    }
+
+    fn remove(&mut self, pool: &Pubkey) {
+        if self.set.remove(pool) {
+            self.order.retain(|p| p != pool);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+}
+
+/// Персистентное хранилище бота на SQLite: увиденные пулы, отправленные транзакции,
+/// подтверждения и исполненные покупки.
+///
+/// Переживает перезапуск, поэтому один и тот же пул не будет куплен повторно.
+/// Последние `cache_capacity` пулов держатся в памяти, остальные проверяются по базе.
+pub struct Store {
+    conn: Mutex<Connection>,
+    seen: Mutex<SeenCache>,
+}
+
+impl Store {
+    /// Открывает (или создаёт) базу по пути `path`
+    pub fn open<P: AsRef<Path>>(path: P, cache_capacity: usize) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?, cache_capacity)
+    }
+
+    /// Открывает существующую базу только на чтение, ничего в ней не создавая, — для проверки настроек
+    ///
+    /// # Возвращает
+    /// Количество увиденных пулов в базе
+    pub fn inspect<P: AsRef<Path>>(path: P) -> Result<u64> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        let pools = conn.query_row("SELECT COUNT(*) FROM seen_pools", [], |row| {
+            row.get::<_, i64>(0)
+        })?;
+        Ok(pools as u64)
+    }
+
+    /// Открывает базу в памяти — для тестов и replay
+    pub fn open_in_memory(cache_capacity: usize) -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?, cache_capacity)
+    }
+
+    fn with_connection(conn: Connection, cache_capacity: usize) -> Result<Self> {
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        conn.execute_batch(SCHEMA)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            seen: Mutex::new(SeenCache::new(cache_capacity)),
+        })
+    }
+
+    /// Прогревает кэш последними увиденными пулами и возвращает неподтверждённые отправки
+    pub fn recover(&self) -> Result<RecoveredState> {
+        let conn = self.lock_conn()?;
+        let capacity = self.lock_seen()?.capacity;
+
+        let mut stmt =
+            conn.prepare("SELECT pool FROM seen_pools ORDER BY seen_at DESC, rowid DESC LIMIT ?1")?;
+        let mut recent = stmt
+            .query_map(params![capacity as i64], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        recent.reverse();
+
+        let mut seen = self.lock_seen()?;
+        for pool in recent {
+            seen.insert(parse_pubkey(&pool)?);
+        }
+
+        Ok(RecoveredState {
+            seen_pools: seen.len(),
+            pending: query_pending(&conn)?,
+        })
+    }
+
+    /// Отправленные покупки без записанного подтверждения, от старых к новым
+    pub fn pending_submissions(&self) -> Result<Vec<PendingSubmission>> {
+        query_pending(&*self.lock_conn()?)
+    }
+
+    /// Атомарно помечает пул как увиденный
+    ///
+    /// # Возвращает
+    /// `true`, если пул встретился впервые, и `false`, если он уже был обработан ранее
+    pub fn mark_pool_seen(&self, pool: &Pubkey, target_mint: &Pubkey) -> Result<bool> {
+        if self.lock_seen()?.contains(pool) {
+            return Ok(false);
+        }
+
+        let inserted = self.lock_conn()?.execute(
+            "INSERT OR IGNORE INTO seen_pools (pool, target_mint, seen_at) VALUES (?1, ?2, ?3)",
+            params![pool.to_string(), target_mint.to_string(), now_unix()],
+        )?;
+
+        self.lock_seen()?.insert(*pool);
+        Ok(inserted == 1)
+    }
+
+    /// Снимает отметку пула, чтобы его можно было купить снова — когда покупка
+    /// не отправлена из-за временной ошибки или лимитов
+    pub fn unmark_pool_seen(&self, pool: &Pubkey) -> Result<()> {
+        self.lock_conn()?.execute(
+            "DELETE FROM seen_pools WHERE pool = ?1",
+            params![pool.to_string()],
+        )?;
+        self.lock_seen()?.remove(pool);
+        Ok(())
+    }
+
+    /// Проверяет, обрабатывался ли пул ранее
+    pub fn is_pool_seen(&self, pool: &Pubkey) -> Result<bool> {
+        if self.lock_seen()?.contains(pool) {
+            return Ok(true);
+        }
+
+        let found = self
+            .lock_conn()?
+            .query_row(
+                "SELECT 1 FROM seen_pools WHERE pool = ?1",
+                params![pool.to_string()],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// Токен, ради которого обрабатывался пул
+    pub fn seen_pool_mint(&self, pool: &Pubkey) -> Result<Option<Pubkey>> {
+        let mint = self
+            .lock_conn()?
+            .query_row(
+                "SELECT target_mint FROM seen_pools WHERE pool = ?1",
+                params![pool.to_string()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        mint.as_deref().map(parse_pubkey).transpose()
+    }
+
+    /// Записывает отправленную транзакцию покупки
+    pub fn record_submission(
+        &self,
+        signature: &Signature,
+        pool: &Pubkey,
+        amount_in: u64,
+    ) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR IGNORE INTO submissions (signature, pool, amount_in, submitted_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![
+                signature.to_string(),
+                pool.to_string(),
+                sql_amount(amount_in)?,
+                now_unix()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Записывает результат подтверждения транзакции
+    pub fn record_confirmation(&self, signature: &Signature, error: Option<&str>) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR REPLACE INTO confirmations (signature, success, error, confirmed_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![signature.to_string(), error.is_none(), error, now_unix()],
+        )?;
+        Ok(())
+    }
+
+    /// Записывает исполненную покупку
+    pub fn record_fill(
+        &self,
+        signature: &Signature,
+        pool: &Pubkey,
+        mint: &Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR REPLACE INTO fills (signature, pool, mint, amount_in, amount_out, filled_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                signature.to_string(),
+                pool.to_string(),
+                mint.to_string(),
+                sql_amount(amount_in)?,
+                sql_amount(amount_out)?,
+                now_unix()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Запоминает кошелёк, которым подписана покупка
+    pub fn record_buy_wallet(&self, signature: &Signature, wallet: &Pubkey) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR REPLACE INTO buy_wallets (signature, wallet) VALUES (?1, ?2)",
+            params![signature.to_string(), wallet.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Кошелёк, которым подписана покупка; `None` — для покупок, записанных без кошелька
+    pub fn buy_wallet(&self, signature: &Signature) -> Result<Option<Pubkey>> {
+        let wallet = self
+            .lock_conn()?
+            .query_row(
+                "SELECT wallet FROM buy_wallets WHERE signature = ?1",
+                params![signature.to_string()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        wallet.as_deref().map(parse_pubkey).transpose()
+    }
+
+    /// Кошелёк последней исполненной покупки в пуле — он владеет купленными токенами
+    pub fn pool_buy_wallet(&self, pool: &Pubkey) -> Result<Option<Pubkey>> {
+        let wallet = self
+            .lock_conn()?
+            .query_row(
+                "SELECT w.wallet FROM fills f JOIN buy_wallets w ON w.signature = f.signature
+                 WHERE f.pool = ?1 ORDER BY f.filled_at DESC, f.rowid DESC LIMIT 1",
+                params![pool.to_string()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()?;
+        wallet.as_deref().map(parse_pubkey).transpose()
+    }
+
+    /// Себестоимость непроданных токенов по кошелькам, в лампортах
+    ///
+    /// Продажи не привязаны к кошельку, поэтому остаток позиции делится между
+    /// кошельками пропорционально их покупкам токена. Покупки без записанного
+    /// кошелька не учитываются.
+    pub fn wallet_exposure(&self) -> Result<HashMap<Pubkey, u64>> {
+        let positions: HashMap<Pubkey, Position> =
+            self.positions()?.into_iter().map(|p| (p.mint, p)).collect();
+
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT w.wallet, f.mint, SUM(f.amount_in)
+             FROM fills f JOIN buy_wallets w ON w.signature = f.signature
+             GROUP BY w.wallet, f.mint",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut exposure = HashMap::new();
+        for (wallet, mint, spent) in rows {
+            let Some(position) = positions.get(&parse_pubkey(&mint)?) else {
+                continue;
+            };
+            if position.sol_spent == 0 {
+                continue;
+            }
+            let share = position.held_cost() as u128 * spent as u128 / position.sol_spent as u128;
+            *exposure.entry(parse_pubkey(&wallet)?).or_default() += share as u64;
+        }
+        Ok(exposure)
+    }
+
+    /// Возвращает все исполненные покупки, от старых к новым
+    pub fn fills(&self) -> Result<Vec<Fill>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT signature, pool, mint, amount_in, amount_out, filled_at FROM fills ORDER BY filled_at, rowid",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(sig, pool, mint, amount_in, amount_out, filled_at)| {
+                Ok(Fill {
+                    signature: parse_signature(&sig)?,
+                    pool: parse_pubkey(&pool)?,
+                    mint: parse_pubkey(&mint)?,
+                    amount_in: amount_in as u64,
+                    amount_out: amount_out as u64,
+                    filled_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Запоминает аккаунты и программу пула, в котором куплен токен, — они нужны для продажи
+    pub fn record_pool_accounts(&self, accounts: &SwapAccounts, target_mint: &Pubkey) -> Result<()> {
+        // Одной транзакцией: без программы аккаунты пула читались бы как AMM
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO pool_accounts (pool, target_mint, user_source, user_destination,
+                 pool_source, pool_destination, pool_authority, token_program)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                accounts.pool.to_string(),
+                target_mint.to_string(),
+                accounts.user_source.to_string(),
+                accounts.user_destination.to_string(),
+                accounts.pool_source.to_string(),
+                accounts.pool_destination.to_string(),
+                accounts.pool_authority.to_string(),
+                accounts.token_program.to_string()
+            ],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO pool_programs (pool, program) VALUES (?1, ?2)",
+            params![accounts.pool.to_string(), accounts.program.as_str()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Возвращает аккаунты пула, в котором покупался `mint`: пула последней исполненной
+    /// покупки (токен мог покупаться на кривой DBC и после миграции), а без исполненных —
+    /// последнего записанного
+    pub fn pool_accounts_for_mint(&self, mint: &Pubkey) -> Result<Option<SwapAccounts>> {
+        let row = self
+            .lock_conn()?
+            .query_row(
+                "SELECT a.pool, a.user_source, a.user_destination, a.pool_source,
+                        a.pool_destination, a.pool_authority, a.token_program,
+                        COALESCE(p.program, 'amm')
+                 FROM pool_accounts a LEFT JOIN pool_programs p ON p.pool = a.pool
+                 WHERE a.target_mint = ?1
+                 ORDER BY COALESCE((SELECT MAX(f.rowid) FROM fills f WHERE f.pool = a.pool), 0) DESC,
+                          a.rowid DESC
+                 LIMIT 1",
+                params![mint.to_string()],
+                |row| {
+                    (0..8)
+                        .map(|i| row.get::<_, String>(i))
+                        .collect::<rusqlite::Result<Vec<_>>>()
+                },
+            )
+            .optional()?;
+
+        let Some(mut row) = row else {
+            return Ok(None);
+        };
+        // Пулы, записанные до поддержки DLMM, — AMM
+        let program = row.pop().unwrap_or_default().parse()?;
+        let k = row
+            .iter()
+            .map(|s| parse_pubkey(s))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(SwapAccounts {
+            program,
+            pool: k[0],
+            user_source: k[1],
+            user_destination: k[2],
+            pool_source: k[3],
+            pool_destination: k[4],
+            pool_authority: k[5],
+            token_program: k[6],
+        }))
+    }
+
+    /// Записывает исполненную продажу: `amount_in` — проданные токены, `amount_out` — полученные лампорты
+    pub fn record_sell(
+        &self,
+        signature: &Signature,
+        pool: &Pubkey,
+        mint: &Pubkey,
+        amount_in: u64,
+        amount_out: u64,
+    ) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR REPLACE INTO sells (signature, pool, mint, amount_in, amount_out, sold_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                signature.to_string(),
+                pool.to_string(),
+                mint.to_string(),
+                sql_amount(amount_in)?,
+                sql_amount(amount_out)?,
+                now_unix()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Сводит покупки и продажи по каждому токену
+    ///
+    /// # Возвращает
+    /// Позиции в порядке первой покупки, включая полностью закрытые
+    pub fn positions(&self) -> Result<Vec<Position>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT f.mint, f.pool, f.sol_spent, f.tokens_bought,
+                    COALESCE(s.sol_received, 0), COALESCE(s.tokens_sold, 0)
+             FROM (SELECT g.mint,
+                          (SELECT l.pool FROM fills l WHERE l.mint = g.mint
+                           ORDER BY l.filled_at DESC, l.rowid DESC LIMIT 1) AS pool,
+                          SUM(g.amount_in) AS sol_spent, SUM(g.amount_out) AS tokens_bought,
+                          MIN(g.filled_at) AS first_fill
+                   FROM fills g GROUP BY g.mint) f
+             LEFT JOIN (SELECT mint, SUM(amount_out) AS sol_received, SUM(amount_in) AS tokens_sold
+                        FROM sells GROUP BY mint) s ON s.mint = f.mint
+             ORDER BY f.first_fill, f.mint",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        rows.into_iter()
+            .map(|(mint, pool, sol_spent, bought, sol_received, sold)| {
+                Ok(Position {
+                    mint: parse_pubkey(&mint)?,
+                    pool: parse_pubkey(&pool)?,
+                    sol_spent: sol_spent as u64,
+                    sol_received: sol_received as u64,
+                    tokens_bought: bought as u64,
+                    tokens_sold: sold as u64,
+                })
+            })
+            .collect()
+    }
+
+    /// Реализованная прибыль (отрицательная — убыток) по продажам начиная с `since`
+    ///
+    /// Себестоимость проданных токенов считается по средней цене всех покупок токена.
+    ///
+    /// # Аргументы
+    /// * `since` — unix-время в секундах
+    pub fn realized_pnl_since(&self, since: i64) -> Result<i64> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT s.amount_in, s.amount_out, f.sol_spent, f.tokens_bought
+             FROM sells s
+             JOIN (SELECT mint, SUM(amount_in) AS sol_spent, SUM(amount_out) AS tokens_bought
+                   FROM fills GROUP BY mint) f ON f.mint = s.mint
+             WHERE s.sold_at >= ?1",
+        )?;
+        let rows = stmt
+            .query_map([since], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(sold, received, sol_spent, bought)| {
+                let cost = if bought > 0 {
+                    (sold as i128 * sol_spent as i128 / bought as i128) as i64
+                } else {
+                    0
+                };
+                received - cost
+            })
+            .sum())
+    }
+
+    /// Сохраняет историю создателя пулов с текущим временем проверки
+    pub fn record_deployer(&self, history: &DeployerHistory) -> Result<()> {
+        self.lock_conn()?.execute(
+            "INSERT OR REPLACE INTO deployers
+                 (creator, launches, rugs, fastest_pull_secs, funder, checked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                history.creator.to_string(),
+                history.launches,
+                history.rugs,
+                history.fastest_pull_secs,
+                history.funder.map(|f| f.to_string()),
+                now_unix()
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// История создателя, если она проверялась не раньше `max_age_secs` секунд назад
+    pub fn deployer(&self, creator: &Pubkey, max_age_secs: i64) -> Result<Option<DeployerHistory>> {
+        let row = self
+            .lock_conn()?
+            .query_row(
+                "SELECT launches, rugs, fastest_pull_secs, funder FROM deployers
+                 WHERE creator = ?1 AND checked_at >= ?2",
+                params![creator.to_string(), now_unix() - max_age_secs],
+                |row| {
+                    Ok((
+                        row.get::<_, u32>(0)?,
+                        row.get::<_, u32>(1)?,
+                        row.get::<_, Option<i64>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        row.map(|(launches, rugs, fastest_pull_secs, funder)| {
+            Ok(DeployerHistory {
+                creator: *creator,
+                launches,
+                rugs,
+                fastest_pull_secs,
+                funder: funder.as_deref().map(parse_pubkey).transpose()?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Сумма rug-ов других создателей, пополненных тем же кошельком
+    pub fn funded_rugs(&self, funder: &Pubkey, except: &Pubkey) -> Result<u32> {
+        let rugs = self.lock_conn()?.query_row(
+            "SELECT COALESCE(SUM(rugs), 0) FROM deployers WHERE funder = ?1 AND creator != ?2",
+            params![funder.to_string(), except.to_string()],
+            |row| row.get::<_, u32>(0),
+        )?;
+        Ok(rugs)
+    }
+
+    fn lock_conn(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| anyhow!("Хранилище заблокировано после паники"))
+    }
+
+    fn lock_seen(&self) -> Result<std::sync::MutexGuard<'_, SeenCache>> {
+        self.seen
+            .lock()
+            .map_err(|_| anyhow!("Кэш пулов заблокирован после паники"))
+    }
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Сумма для INTEGER-столбца: SQLite хранит знаковые 64-битные числа
+fn sql_amount(amount: u64) -> Result<i64> {
+    i64::try_from(amount).map_err(|_| anyhow!("Сумма {} не помещается в хранилище", amount))
+}
+
+fn query_pending(conn: &Connection) -> Result<Vec<PendingSubmission>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.signature, s.pool, s.amount_in, s.submitted_at
+         FROM submissions s
+         LEFT JOIN confirmations c ON c.signature = s.signature
+         WHERE c.signature IS NULL
+         ORDER BY s.submitted_at",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    rows.into_iter()
+        .map(|(sig, pool, amount_in, submitted_at)| {
+            Ok(PendingSubmission {
+                signature: parse_signature(&sig)?,
+                pool: parse_pubkey(&pool)?,
+                amount_in: amount_in as u64,
+                submitted_at,
+            })
+        })
+        .collect()
+}
+
+fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    Pubkey::from_str(s).map_err(|e| anyhow!("Невалидный pubkey в хранилище {}: {}", s, e))
+}
+
+fn parse_signature(s: &str) -> Result<Signature> {
+    Signature::from_str(s).map_err(|e| anyhow!("Невалидная подпись в хранилище {}: {}", s, e))
+}
@@ -1,23 +1,35 @@
+use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use base64::{engine::general_purpose, Engine as _};
 use bincode::serialize;
 use solana_client::nonblocking::rpc_client::RpcClient;
 
+use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::{
+    commitment_config::CommitmentConfig,
     compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::Signature,
+    system_program,
     transaction::{Transaction, VersionedTransaction},
 };
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, UiTransactionEncoding, UiTransactionTokenBalance,
+};
 use tokio::join;
 
-use crate::bloxroute::send_to_bloxroute;
-use crate::nextblock::send_to_nextblock;
-use crate::config::METEORA_PROGRAM_ID;
+use crate::config::{ASSOCIATED_TOKEN_PROGRAM_ID, METEORA_PROGRAM_ID};
+use crate::damm_v2::DammV2Swap;
+use crate::dbc::DbcSwap;
+use crate::dlmm::DlmmSwap;
+use crate::relay::RelayRegistry;
+use crate::rpc::{RpcPool, RpcRole};
+use crate::signer::{sign_transaction, TxSigner};
 
 #[derive(Debug)]
 struct SwapInstructionData {
@@ -35,17 +47,336 @@ impl SwapInstructionData {
     }
 }
 
+/// Программа Meteora, в которой создан пул
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PoolProgram {
+    /// Пулы Meteora с однобайтовым кодом операции (`METEORA_PROGRAM_ID`)
+    #[default]
+    Amm,
+    /// Liquidity book Meteora DLMM (`DLMM_PROGRAM_ID`)
+    Dlmm,
+    /// Пулы Meteora DAMM v2 (`DAMM_V2_PROGRAM_ID`)
+    DammV2,
+    /// Кривые Meteora Dynamic Bonding Curve (`DBC_PROGRAM_ID`)
+    Dbc,
+}
+
+impl PoolProgram {
+    /// Имя для хранилища
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PoolProgram::Amm => "amm",
+            PoolProgram::Dlmm => "dlmm",
+            PoolProgram::DammV2 => "damm_v2",
+            PoolProgram::Dbc => "dbc",
+        }
+    }
+}
+
+impl FromStr for PoolProgram {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "amm" => Ok(PoolProgram::Amm),
+            "dlmm" => Ok(PoolProgram::Dlmm),
+            "damm_v2" => Ok(PoolProgram::DammV2),
+            "dbc" => Ok(PoolProgram::Dbc),
+            other => Err(anyhow!("Неизвестная программа пула {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PoolProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PoolProgram::Amm => write!(f, "AMM"),
+            PoolProgram::Dlmm => write!(f, "DLMM"),
+            PoolProgram::DammV2 => write!(f, "DAMM v2"),
+            PoolProgram::Dbc => write!(f, "DBC"),
+        }
+    }
+}
+
+/// Аккаунты, участвующие в swap-инструкции Meteora
+///
+/// Для DLMM `pool_source`/`pool_destination` — резервы входного и выходного токена,
+/// `pool_authority` — сам lb pair, а нулевой пользовательский аккаунт означает
+/// ATA подписанта (см. [`crate::dlmm`]). DAMM v2 устроен так же, но `pool_authority` —
+/// общий владелец хранилищ (см. [`crate::damm_v2`]); так же устроены кривые DBC
+/// (см. [`crate::dbc`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapAccounts {
+    pub program: PoolProgram,
+    pub pool: Pubkey,
+    pub user_source: Pubkey,
+    pub user_destination: Pubkey,
+    pub pool_source: Pubkey,
+    pub pool_destination: Pubkey,
+    pub pool_authority: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl SwapAccounts {
+    /// Аккаунты обратного свапа в том же пуле: входной и выходной токены меняются местами
+    pub fn reversed(&self) -> SwapAccounts {
+        SwapAccounts {
+            user_source: self.user_destination,
+            user_destination: self.user_source,
+            pool_source: self.pool_destination,
+            pool_destination: self.pool_source,
+            ..*self
+        }
+    }
+}
+
+/// Swap-инструкция пула Meteora AMM
+pub fn amm_swap_instruction(
+    accounts: &SwapAccounts,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Instruction> {
+    let ix_data = SwapInstructionData {
+        amount_in,
+        minimum_amount_out: min_out,
+    }
+    .serialize();
+
+    Ok(Instruction {
+        program_id: Pubkey::from_str(METEORA_PROGRAM_ID)?,
+        accounts: vec![
+            AccountMeta::new(accounts.user_source, false),
+            AccountMeta::new(accounts.user_destination, false),
+            AccountMeta::new(accounts.pool_source, false),
+            AccountMeta::new(accounts.pool_destination, false),
+            AccountMeta::new_readonly(accounts.pool_authority, false),
+            AccountMeta::new_readonly(accounts.token_program, false),
+        ],
+        data: ix_data,
+    })
+}
+
+/// Инструкции свапа в пуле программы `accounts.program`
+///
+/// Для AMM хватает самих аккаунтов; для DLMM, DAMM v2 и DBC состояние пула
+/// загружается через RPC, а выходной ATA создаётся, если он не задан.
+///
+/// # Аргументы
+/// * `rpc` — пул RPC-узлов для чтения состояния пула
+/// * `payer` — владелец пользовательских аккаунтов и подписант
+/// * `accounts` — аккаунты пула и пользователя
+/// * `amount_in` — количество входных токенов
+/// * `min_out` — минимальное количество выходных токенов
+pub async fn swap_instructions(
+    rpc: &RpcPool,
+    payer: &Pubkey,
+    accounts: &SwapAccounts,
+    amount_in: u64,
+    min_out: u64,
+) -> Result<Vec<Instruction>> {
+    match accounts.program {
+        PoolProgram::Amm => Ok(vec![amm_swap_instruction(accounts, amount_in, min_out)?]),
+        PoolProgram::Dlmm => {
+            let pool = DlmmSwap::fetch(rpc, accounts).await?;
+            pool.instructions(payer, accounts, amount_in, min_out)
+        }
+        PoolProgram::DammV2 => {
+            let pool = DammV2Swap::fetch(rpc, accounts).await?;
+            pool.instructions(payer, accounts, amount_in, min_out)
+        }
+        PoolProgram::Dbc => {
+            let curve = DbcSwap::fetch(rpc, accounts).await?;
+            curve.instructions(payer, accounts, amount_in, min_out)
+        }
+    }
+}
+
+/// Собирает и подписывает транзакцию из инструкций свапа с ценой compute unit `tip`
+pub async fn build_transaction(
+    payer: &dyn TxSigner,
+    instructions: Vec<Instruction>,
+    tip: u64,
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let mut ixs = vec![ComputeBudgetInstruction::set_compute_unit_price(tip)];
+    ixs.extend(instructions);
+
+    let tx = Transaction::new_with_payer(&ixs, Some(&payer.pubkey()));
+    let tx = sign_transaction(tx, blockhash, &[payer]).await?;
+
+    Ok(VersionedTransaction::from(tx))
+}
+
+/// Собирает и подписывает транзакцию swap через программу Meteora, не отправляя её
+///
+/// Не обращается к сети, поэтому поддерживает только AMM;
+/// свапы DLMM, DAMM v2 и DBC собираются через [`swap_instructions`] и [`build_transaction`].
+///
+/// # Аргументы
+/// * `payer` — подписант транзакции: ключ в процессе или сервис подписи
+/// * `accounts` — аккаунты пула и пользователя
+/// * `amount_in` — количество входных токенов
+/// * `min_out` — минимальное количество выходных токенов
+/// * `tip` — повышение приоритета (цена compute unit в микролампортах)
+/// * `blockhash` — недавний blockhash
+pub async fn build_swap_transaction(
+    payer: &dyn TxSigner,
+    accounts: &SwapAccounts,
+    amount_in: u64,
+    min_out: u64,
+    tip: u64,
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    if accounts.program != PoolProgram::Amm {
+        bail!(
+            "Свап {} в пуле {} собирается по состоянию пула из RPC",
+            accounts.program,
+            accounts.pool
+        );
+    }
+
+    // Основная инструкция swap через Meteora
+    let swap_ix = amm_swap_instruction(accounts, amount_in, min_out)?;
+    build_transaction(payer, vec![swap_ix], tip, blockhash).await
+}
+
+/// Адрес associated token account владельца для `mint`
+pub fn associated_token_address(
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Pubkey> {
+    let program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)?;
+    Ok(Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &program,
+    )
+    .0)
+}
+
+/// Пользовательские аккаунты свапа: нулевые `user_source`/`user_destination`
+/// заменяются ATA `payer`, а для выходного ATA добавляется инструкция создания
+///
+/// # Аргументы
+/// * `input`, `output` — mint и токен-программа входного и выходного токена
+///
+/// # Возвращает
+/// Входной и выходной аккаунты и инструкции, которые нужно выполнить до свапа
+pub fn resolve_user_token_accounts(
+    payer: &Pubkey,
+    accounts: &SwapAccounts,
+    input: (Pubkey, Pubkey),
+    output: (Pubkey, Pubkey),
+) -> Result<(Pubkey, Pubkey, Vec<Instruction>)> {
+    let (in_mint, in_program) = input;
+    let (out_mint, out_program) = output;
+    let mut ixs = vec![];
+
+    let user_token_in = match accounts.user_source {
+        key if key == Pubkey::default() => associated_token_address(payer, &in_mint, &in_program)?,
+        key => key,
+    };
+    let user_token_out = match accounts.user_destination {
+        key if key == Pubkey::default() => {
+            ixs.push(create_associated_token_account_idempotent(
+                payer,
+                payer,
+                &out_mint,
+                &out_program,
+            )?);
+            associated_token_address(payer, &out_mint, &out_program)?
+        }
+        key => key,
+    };
+
+    Ok((user_token_in, user_token_out, ixs))
+}
+
+/// Инструкция `CreateIdempotent`: создаёт ATA, если его ещё нет
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction> {
+    Ok(Instruction {
+        program_id: Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)?,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint, token_program)?, false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: vec![1],
+    })
+}
+
+/// Отправляет подписанную транзакцию одновременно через:
+/// 1. Все RPC-узлы пула с ролью отправки (без preflight)
+/// 2. Все ретрансляторы из `relays` (по умолчанию Bloxroute и NextBlock)
+///
+/// Подтверждения не ждёт — см. [`RpcPool::wait_for_confirmation`].
+///
+/// # Возвращает
+/// Подпись транзакции, если все каналы приняли её без ошибок
+pub async fn submit_swap(
+    rpc: &RpcPool,
+    relays: &RelayRegistry,
+    versioned_tx: &VersionedTransaction,
+    tip: u64,
+) -> Result<Signature> {
+    let tx_bytes = serialize(versioned_tx)?;
+    let tx_base64 = general_purpose::STANDARD.encode(&tx_bytes);
+
+    let (rpc_res, relay_results) = join!(
+        rpc.broadcast(versioned_tx),
+        relays.broadcast(&tx_base64, tip),
+    );
+
+    let mut errors = vec![];
+
+    for (url, res) in rpc_res {
+        match res {
+            Ok(sig) => println!("Покупка отправлена через RPC {}: {}", url, sig),
+            Err(e) => {
+                let e = rpc.scrub(&format!("{:?}", e));
+                eprintln!("Ошибка RPC {}: {}", url, e);
+                errors.push(anyhow!("Solana RPC error ({}): {}", url, e));
+            }
+        }
+    }
+
+    for (name, res) in relay_results {
+        match res {
+            Ok(ack) => println!(
+                "Покупка принята {}: {}",
+                name,
+                ack.signature.or(ack.uuid).unwrap_or_default()
+            ),
+            Err(e) => {
+                eprintln!("Ошибка отправки в {}: {}", name, e);
+                errors.push(anyhow!(e));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(anyhow!("Ошибка при выполнении свапа: {:?}", errors));
+    }
+
+    Ok(versioned_tx.signatures[0])
+}
+
 /// Выполняет swap через программу Meteora
 ///
-/// Отправляет транзакцию через:
-/// 1. Стандартный Solana RPC
-/// 2. Bloxroute MEV endpoint
-/// 3. NextBlock endpoint
+/// Собирает транзакцию через [`build_swap_transaction`] и отправляет её через [`submit_swap`].
 ///
 /// # Аргументы
 /// * `rpc` — RPC клиент
 /// * `payer` — аккаунт, подписывающий транзакцию
-/// * `_pool` — публичный ключ пула (не используется напрямую, но может быть полезен логически)
+/// * `pool` — публичный ключ пула
 /// * `user_source` — аккаунт, с которого списываются токены
 /// * `user_destination` — аккаунт, на который зачисляются токены
 /// * `pool_source` — аккаунт пула (откуда берётся токен)
@@ -55,10 +386,11 @@ impl SwapInstructionData {
 /// * `amount_in` — количество входных токенов
 /// * `min_out` — минимальное количество выходных токенов
 /// * `tip` — повышени приоритета
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_swap(
     rpc: Arc<RpcClient>,
-    payer: &Keypair,
-    _pool: Pubkey,
+    payer: &dyn TxSigner,
+    pool: Pubkey,
     user_source: Pubkey,
     user_destination: Pubkey,
     pool_source: Pubkey,
@@ -68,74 +400,64 @@ pub async fn execute_swap(
     amount_in: u64,
     min_out: u64,
     tip: u64,
-) -> Result<()> {
+) -> Result<Signature> {
     println!("Составляем swap через Meteora");
 
-    let ix_data = SwapInstructionData {
-        amount_in,
-        minimum_amount_out: min_out,
-    }
-    .serialize();
-
-    // Основная инструкция swap через Meteora
-    let swap_ix = Instruction {
-        program_id: Pubkey::from_str(METEORA_PROGRAM_ID)?,
-        accounts: vec![
-            AccountMeta::new(user_source, false),
-            AccountMeta::new(user_destination, false),
-            AccountMeta::new(pool_source, false),
-            AccountMeta::new(pool_destination, false),
-            AccountMeta::new_readonly(pool_authority, false),
-            AccountMeta::new_readonly(token_program, false),
-        ],
-        data: ix_data,
+    let accounts = SwapAccounts {
+        program: PoolProgram::Amm,
+        pool,
+        user_source,
+        user_destination,
+        pool_source,
+        pool_destination,
+        pool_authority,
+        token_program,
     };
 
-    let compute_budget_ix = ComputeBudgetInstruction::set_compute_unit_price(tip);
-
     let blockhash = rpc.get_latest_blockhash().await?;
+    let versioned_tx =
+        build_swap_transaction(payer, &accounts, amount_in, min_out, tip, blockhash).await?;
 
-    let tx = Transaction::new_signed_with_payer(
-        &[compute_budget_ix, swap_ix],
-        Some(&payer.pubkey()),
-        &[payer],
-        blockhash,
-    );
-
-    let versioned_tx = VersionedTransaction::from(tx);
-
-    let tx_bytes = serialize(&versioned_tx)?;
-    let tx_base64 = general_purpose::STANDARD.encode(&tx_bytes);
-
-    let (rpc_res, blox_res, next_res) = join!(
-        rpc.send_and_confirm_transaction(&versioned_tx),
-        send_to_bloxroute(&tx_base64, tip),
-        send_to_nextblock(&tx_base64, tip),
-    );
-
-    let mut errors = vec![];
-
-    match rpc_res {
-        Ok(sig) => println!("Покупка отправлена через Solana RPC: {}", sig),
-        Err(e) => {
-            eprintln!("Ошибка Solana RPC: {:?}", e);
-            errors.push(anyhow!("Solana RPC error: {:?}", e));
-        }
-    }
+    let pool = RpcPool::from_clients([(rpc, RpcRole::ReadSend)])?;
+    submit_swap(&pool, &RelayRegistry::from_config(), &versioned_tx, tip).await
+}
 
-    if let Err(e) = blox_res {
-        eprintln!("Ошибка отправки в Bloxroute: {:?}", e);
-        errors.push(anyhow!("Bloxroute error: {:?}", e));
-    }
+/// Определяет, сколько токенов `mint` получил `owner` в подтверждённой транзакции,
+/// по разнице pre/post token balances
+pub async fn fetch_fill_amount(
+    rpc: &RpcClient,
+    signature: &Signature,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<u64> {
+    let tx = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
 
-    if let Err(e) = next_res {
-        eprintln!("Ошибка отправки в NextBlock: {:?}", e);
-        errors.push(anyhow!("NextBlock error: {:?}", e));
-    }
+    let meta = tx
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow!("Транзакция {} без meta", signature))?;
 
-    if !errors.is_empty() {
-        return Err(anyhow!("Ошибка при выполнении свапа: {:?}", errors));
-    }
+    let owner = owner.to_string();
+    let mint = mint.to_string();
+    let sum = |balances: OptionSerializer<Vec<UiTransactionTokenBalance>>| -> u64 {
+        Option::<Vec<_>>::from(balances)
+            .unwrap_or_default()
+            .iter()
+            .filter(|b| b.mint == mint && matches!(&b.owner, OptionSerializer::Some(o) if *o == owner))
+            .filter_map(|b| b.ui_token_amount.amount.parse::<u64>().ok())
+            .sum()
+    };
 
-    Ok(())
-}
\ No newline at end of file
+    let pre = sum(meta.pre_token_balances);
+    let post = sum(meta.post_token_balances);
+    Ok(post.saturating_sub(pre))
+}
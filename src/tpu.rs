@@ -0,0 +1,213 @@
+use std::{
+    collections::HashMap,
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::future::{join_all, BoxFuture};
+use quinn::{
+    crypto::rustls::QuicClientConfig, ClientConfig, Connection, Endpoint, IdleTimeout,
+    TransportConfig,
+};
+use solana_sdk::{signature::Keypair, transaction::VersionedTransaction};
+use solana_tls_utils::{tls_client_config_builder, QuicClientCertificate};
+
+use crate::config::TPU_LEADER_FANOUT;
+use crate::relay::{Relay, RelayAck, RelayError};
+
+pub mod leaders;
+
+use leaders::LeaderTracker;
+
+const RELAY_NAME: &str = "TPU";
+
+/// ALPN, который TPU валидатора принимает для транзакций
+pub const ALPN_TPU_PROTOCOL_ID: &[u8] = b"solana-tpu";
+
+const QUIC_MAX_IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+const QUIC_KEEP_ALIVE: Duration = Duration::from_secs(1);
+
+/// Отправка транзакций напрямую в TPU ближайших лидеров по QUIC, минуя RPC и ретрансляторы.
+///
+/// Каждая транзакция уходит отдельным однонаправленным потоком всем `fanout` ближайшим лидерам
+/// из [`LeaderTracker`]. Соединения с валидаторами переиспользуются между отправками.
+pub struct TpuRelay {
+    leaders: Arc<LeaderTracker>,
+    endpoint: Endpoint,
+    connections: Mutex<HashMap<SocketAddr, Connection>>,
+    fanout: usize,
+}
+
+impl TpuRelay {
+    /// Создаёт QUIC-клиент; должен вызываться внутри tokio runtime
+    ///
+    /// # Аргументы
+    /// * `leaders` — расписание лидеров и адреса TPU
+    /// * `identity` — ключ, которым подписан TLS-сертификат клиента
+    /// * `fanout` — скольким ближайшим лидерам отправлять транзакцию
+    pub fn new(leaders: Arc<LeaderTracker>, identity: &Keypair, fanout: usize) -> Result<Self> {
+        let certificate = QuicClientCertificate::new(Some(identity));
+        let mut crypto = tls_client_config_builder()
+            .with_client_auth_cert(vec![certificate.certificate], certificate.key)
+            .context("Ошибка TLS-сертификата QUIC")?;
+        crypto.enable_early_data = true;
+        crypto.alpn_protocols = vec![ALPN_TPU_PROTOCOL_ID.to_vec()];
+
+        let mut transport = TransportConfig::default();
+        transport.max_idle_timeout(Some(IdleTimeout::try_from(QUIC_MAX_IDLE_TIMEOUT)?));
+        transport.keep_alive_interval(Some(QUIC_KEEP_ALIVE));
+
+        let mut config = ClientConfig::new(Arc::new(QuicClientConfig::try_from(crypto)?));
+        config.transport_config(Arc::new(transport));
+
+        let mut endpoint = Endpoint::client(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+            .context("Ошибка открытия UDP-сокета для QUIC")?;
+        endpoint.set_default_client_config(config);
+
+        Ok(Self {
+            leaders,
+            endpoint,
+            connections: Mutex::new(HashMap::new()),
+            fanout: fanout.max(1),
+        })
+    }
+
+    /// Количество лидеров из config.rs
+    pub fn from_config(leaders: Arc<LeaderTracker>, identity: &Keypair) -> Result<Self> {
+        Self::new(leaders, identity, TPU_LEADER_FANOUT)
+    }
+
+    /// Отправляет сериализованную транзакцию ближайшим лидерам
+    ///
+    /// # Возвращает
+    /// Адреса TPU, принявшие транзакцию, или ошибку, если не принял ни один
+    pub async fn send_to_leaders(&self, wire_tx: &[u8]) -> Result<Vec<SocketAddr>, RelayError> {
+        let targets = self.leaders.upcoming_tpu_addresses(self.fanout);
+        if targets.is_empty() {
+            return Err(RelayError::Transport {
+                relay: RELAY_NAME.to_string(),
+                message: "адреса TPU ближайших лидеров неизвестны".to_string(),
+            });
+        }
+
+        let results = join_all(targets.iter().map(|(leader, addr)| async move {
+            let res = self.send_to(*addr, wire_tx).await;
+            if let Err(e) = &res {
+                eprintln!("Ошибка отправки в TPU {} ({}): {:#}", leader, addr, e);
+            }
+            (*addr, res)
+        }))
+        .await;
+
+        let delivered: Vec<SocketAddr> = results
+            .iter()
+            .filter(|(_, res)| res.is_ok())
+            .map(|(addr, _)| *addr)
+            .collect();
+        if delivered.is_empty() {
+            let errors: Vec<String> = results
+                .into_iter()
+                .filter_map(|(addr, res)| Some(format!("{}: {:#}", addr, res.err()?)))
+                .collect();
+            return Err(RelayError::Transport {
+                relay: RELAY_NAME.to_string(),
+                message: errors.join("; "),
+            });
+        }
+        Ok(delivered)
+    }
+
+    /// Отправляет транзакцию одним потоком; при сбое закэшированного соединения
+    /// открывает новое и пробует ещё раз
+    async fn send_to(&self, addr: SocketAddr, wire_tx: &[u8]) -> Result<()> {
+        let connection = self.connection(addr).await?;
+        if Self::write_tx(&connection, wire_tx).await.is_ok() {
+            return Ok(());
+        }
+
+        self.lock_connections().remove(&addr);
+        let connection = self.connection(addr).await?;
+        Self::write_tx(&connection, wire_tx).await
+    }
+
+    async fn write_tx(connection: &Connection, wire_tx: &[u8]) -> Result<()> {
+        let mut stream = connection.open_uni().await?;
+        stream.write_all(wire_tx).await?;
+        stream.finish()?;
+        Ok(())
+    }
+
+    /// Живое соединение с TPU из кэша или новое
+    async fn connection(&self, addr: SocketAddr) -> Result<Connection> {
+        if let Some(connection) = self.lock_connections().get(&addr) {
+            if connection.close_reason().is_none() {
+                return Ok(connection.clone());
+            }
+        }
+
+        let connection = self
+            .endpoint
+            .connect(addr, "connect")?
+            .await
+            .map_err(|e| anyhow!("QUIC-соединение с {} не установлено: {}", addr, e))?;
+        self.lock_connections().insert(addr, connection.clone());
+        Ok(connection)
+    }
+
+    fn lock_connections(&self) -> std::sync::MutexGuard<'_, HashMap<SocketAddr, Connection>> {
+        self.connections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Relay for TpuRelay {
+    fn name(&self) -> &str {
+        RELAY_NAME
+    }
+
+    /// `tip` не используется: у TPU нет чаевых, приоритет задаётся комиссией в самой транзакции
+    fn send<'a>(
+        &'a self,
+        tx_base64: &'a str,
+        _tip: u64,
+    ) -> BoxFuture<'a, Result<RelayAck, RelayError>> {
+        Box::pin(async move {
+            let invalid = |message: String| RelayError::Rejected {
+                relay: RELAY_NAME.to_string(),
+                code: None,
+                message,
+            };
+            let wire_tx = general_purpose::STANDARD
+                .decode(tx_base64)
+                .map_err(|e| invalid(format!("невалидный base64: {}", e)))?;
+            let tx: VersionedTransaction = bincode::deserialize(&wire_tx)
+                .map_err(|e| invalid(format!("невалидная транзакция: {}", e)))?;
+
+            self.send_to_leaders(&wire_tx).await?;
+            Ok(RelayAck {
+                signature: tx.signatures.first().map(ToString::to_string),
+                uuid: None,
+            })
+        })
+    }
+
+    /// Открывает соединения с ближайшими лидерами заранее
+    fn warm_up(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let targets = self.leaders.upcoming_tpu_addresses(self.fanout);
+            let results = join_all(targets.iter().map(|(_, addr)| self.connection(*addr))).await;
+            if !results.is_empty() && results.iter().all(Result::is_err) {
+                return Err(anyhow!(
+                    "Ни один TPU не ответил: {:?}",
+                    results
+                        .into_iter()
+                        .filter_map(Result::err)
+                        .collect::<Vec<_>>()
+                ));
+            }
+            Ok(())
+        })
+    }
+}
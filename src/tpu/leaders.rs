@@ -0,0 +1,205 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use solana_sdk::{clock::DEFAULT_MS_PER_SLOT, commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tokio::task::JoinHandle;
+
+use crate::rpc::RpcPool;
+
+struct State {
+    slot: u64,
+    slot_observed_at: Instant,
+    /// Слот, с которого начинается `leaders`
+    schedule_start: u64,
+    leaders: Vec<Pubkey>,
+    /// QUIC-адреса TPU валидаторов из `getClusterNodes`
+    contacts: HashMap<Pubkey, SocketAddr>,
+    contacts_updated_at: Option<Instant>,
+}
+
+/// Расписание лидеров и адреса TPU валидаторов.
+///
+/// Текущий слот между обновлениями оценивается по времени: с последнего известного слота
+/// прибавляется по слоту каждые `DEFAULT_MS_PER_SLOT` миллисекунд.
+pub struct LeaderTracker {
+    state: RwLock<State>,
+}
+
+impl Default for LeaderTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LeaderTracker {
+    pub fn new() -> Self {
+        Self {
+            state: RwLock::new(State {
+                slot: 0,
+                slot_observed_at: Instant::now(),
+                schedule_start: 0,
+                leaders: vec![],
+                contacts: HashMap::new(),
+                contacts_updated_at: None,
+            }),
+        }
+    }
+
+    /// Запоминает слот, увиденный в сети; более старые слоты игнорируются
+    pub fn observe_slot(&self, slot: u64) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        if slot > state.slot {
+            state.slot = slot;
+            state.slot_observed_at = Instant::now();
+        }
+    }
+
+    /// Оценка текущего слота
+    pub fn current_slot(&self) -> u64 {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let elapsed = state.slot_observed_at.elapsed().as_millis() as u64;
+        state.slot + elapsed / DEFAULT_MS_PER_SLOT
+    }
+
+    /// Заменяет расписание лидеров
+    ///
+    /// # Аргументы
+    /// * `start_slot` — слот первого лидера в `leaders`
+    /// * `leaders` — лидер каждого слота подряд
+    pub fn set_leader_schedule(&self, start_slot: u64, leaders: Vec<Pubkey>) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.schedule_start = start_slot;
+        state.leaders = leaders;
+    }
+
+    /// Заменяет адреса TPU валидаторов
+    pub fn set_contacts(&self, contacts: impl IntoIterator<Item = (Pubkey, SocketAddr)>) {
+        let mut state = self.state.write().unwrap_or_else(|e| e.into_inner());
+        state.contacts = contacts.into_iter().collect();
+        state.contacts_updated_at = Some(Instant::now());
+    }
+
+    /// Лидер слота, если слот попадает в известное расписание
+    pub fn leader_at(&self, slot: u64) -> Option<Pubkey> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let index = slot.checked_sub(state.schedule_start)?;
+        state.leaders.get(index as usize).copied()
+    }
+
+    /// QUIC-адрес TPU валидатора
+    pub fn tpu_address(&self, validator: &Pubkey) -> Option<SocketAddr> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.contacts.get(validator).copied()
+    }
+
+    /// Различные лидеры, начиная с текущего слота, в порядке их очереди
+    ///
+    /// # Аргументы
+    /// * `count` — сколько лидеров вернуть
+    pub fn upcoming_leaders(&self, count: usize) -> Vec<Pubkey> {
+        let current = self.current_slot();
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        let skip = current.saturating_sub(state.schedule_start) as usize;
+
+        let mut leaders: Vec<Pubkey> = Vec::with_capacity(count);
+        for leader in state.leaders.iter().skip(skip) {
+            if leaders.len() == count {
+                break;
+            }
+            if !leaders.contains(leader) {
+                leaders.push(*leader);
+            }
+        }
+        leaders
+    }
+
+    /// Адреса TPU ближайших `count` лидеров; лидеры без известного адреса пропускаются
+    pub fn upcoming_tpu_addresses(&self, count: usize) -> Vec<(Pubkey, SocketAddr)> {
+        self.upcoming_leaders(count)
+            .into_iter()
+            .filter_map(|leader| Some((leader, self.tpu_address(&leader)?)))
+            .collect()
+    }
+
+    /// Загружает текущий слот и расписание на `lookahead_slots` слотов вперёд
+    pub async fn refresh_schedule(&self, rpc: &RpcPool, lookahead_slots: u64) -> Result<()> {
+        let slot = rpc
+            .read(|client| async move {
+                client
+                    .get_slot_with_commitment(CommitmentConfig::processed())
+                    .await
+            })
+            .await?;
+        let leaders = rpc
+            .read(|client| async move { client.get_slot_leaders(slot, lookahead_slots).await })
+            .await?;
+
+        self.observe_slot(slot);
+        self.set_leader_schedule(slot, leaders);
+        Ok(())
+    }
+
+    /// Загружает QUIC-адреса TPU всех валидаторов через `getClusterNodes`
+    ///
+    /// # Возвращает
+    /// Количество валидаторов с известным адресом
+    pub async fn refresh_contacts(&self, rpc: &RpcPool) -> Result<usize> {
+        let nodes = rpc
+            .read(|client| async move { client.get_cluster_nodes().await })
+            .await?;
+
+        let contacts: Vec<(Pubkey, SocketAddr)> = nodes
+            .into_iter()
+            .filter_map(|node| Some((Pubkey::from_str(&node.pubkey).ok()?, node.tpu_quic?)))
+            .collect();
+        let count = contacts.len();
+        self.set_contacts(contacts);
+        Ok(count)
+    }
+
+    fn contacts_age(&self) -> Option<Duration> {
+        let state = self.state.read().unwrap_or_else(|e| e.into_inner());
+        state.contacts_updated_at.map(|at| at.elapsed())
+    }
+}
+
+/// Обновляет расписание лидеров каждые `interval`, а адреса TPU — каждые `contacts_interval`
+///
+/// # Аргументы
+/// * `tracker` — расписание, которое нужно поддерживать актуальным
+/// * `rpc` — пул RPC-узлов
+/// * `lookahead_slots` — на сколько слотов вперёд запрашивать лидеров
+pub fn spawn_leader_refresher(
+    tracker: Arc<LeaderTracker>,
+    rpc: Arc<RpcPool>,
+    lookahead_slots: u64,
+    interval: Duration,
+    contacts_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            if tracker
+                .contacts_age()
+                .is_none_or(|age| age >= contacts_interval)
+            {
+                match tracker.refresh_contacts(&rpc).await {
+                    Ok(count) => println!("Адреса TPU обновлены: {} валидаторов", count),
+                    Err(e) => eprintln!("Ошибка загрузки адресов TPU: {:#}", e),
+                }
+            }
+
+            if let Err(e) = tracker.refresh_schedule(&rpc, lookahead_slots).await {
+                eprintln!("Ошибка загрузки расписания лидеров: {:#}", e);
+            }
+        }
+    })
+}
@@ -0,0 +1,348 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use anyhow::{bail, Context, Result};
+use solana_sdk::{instruction::Instruction, native_token::lamports_to_sol, pubkey::Pubkey};
+
+use crate::config::{PRIVATE_KEY_BASE58, REMOTE_SIGNER, WALLETS, WALLET_POLICY};
+use crate::keyloader;
+use crate::secrets::SecretSource;
+use crate::signer::{RemoteSigner, TxSigner};
+use crate::swap::{
+    associated_token_address, create_associated_token_account_idempotent, PoolProgram, SwapAccounts,
+};
+
+/// Как выбирается кошелёк для новой покупки
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WalletPolicy {
+    /// По очереди, пропуская кошельки без свободного бюджета
+    RoundRobin,
+    /// Кошелёк с наименьшей суммой в позициях и покупках в процессе
+    LeastExposed,
+    /// Только кошельки стратегии покупки, по очереди; кошельки без стратегии —
+    /// для стратегий, у которых своих кошельков нет
+    PerStrategy,
+}
+
+impl fmt::Display for WalletPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletPolicy::RoundRobin => write!(f, "по очереди"),
+            WalletPolicy::LeastExposed => write!(f, "наименее загруженный"),
+            WalletPolicy::PerStrategy => write!(f, "по стратегии"),
+        }
+    }
+}
+
+/// Торговый кошелёк со своим WSOL-аккаунтом и бюджетом
+pub struct Wallet {
+    pub signer: Arc<dyn TxSigner>,
+    /// WSOL-аккаунт, с которого списывается покупка; `None` — из аккаунтов пула
+    pub wsol_account: Option<Pubkey>,
+    /// Максимум лампортов в позициях и покупках в процессе
+    pub budget_lamports: u64,
+    /// Стратегия, за которой закреплён кошелёк
+    pub strategy: Option<String>,
+}
+
+impl Wallet {
+    /// Кошелёк без WSOL-аккаунта, ограничения бюджета и стратегии
+    pub fn new(signer: impl TxSigner + 'static) -> Self {
+        Self {
+            signer: Arc::new(signer),
+            wsol_account: None,
+            budget_lamports: u64::MAX,
+            strategy: None,
+        }
+    }
+
+    pub fn with_budget(mut self, lamports: u64) -> Self {
+        self.budget_lamports = lamports;
+        self
+    }
+
+    pub fn with_strategy(mut self, strategy: impl Into<String>) -> Self {
+        self.strategy = Some(strategy.into());
+        self
+    }
+
+    pub fn with_wsol_account(mut self, account: Pubkey) -> Self {
+        self.wsol_account = Some(account);
+        self
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    /// Аккаунты покупки `target_mint` кошельком: WSOL списывается с его WSOL-аккаунта,
+    /// а токен приходит на его ATA, а не на аккаунт создателя пула из init-транзакции
+    ///
+    /// AMM не знает mint'ов пула, поэтому для него ATA указывается явно
+    /// (создаётся через [`Wallet::prepare_instructions`]); для остальных программ —
+    /// нулевым ключом, который адаптер заменяет ATA подписанта.
+    pub fn swap_accounts(
+        &self,
+        accounts: &SwapAccounts,
+        target_mint: &Pubkey,
+    ) -> Result<SwapAccounts> {
+        let user_destination = match accounts.program {
+            PoolProgram::Amm => {
+                associated_token_address(&self.pubkey(), target_mint, &accounts.token_program)?
+            }
+            _ => Pubkey::default(),
+        };
+        Ok(SwapAccounts {
+            user_source: self.wsol_account.unwrap_or(accounts.user_source),
+            user_destination,
+            ..*accounts
+        })
+    }
+
+    /// Инструкции перед покупкой `target_mint`: для AMM — создание ATA кошелька
+    pub fn prepare_instructions(
+        &self,
+        accounts: &SwapAccounts,
+        target_mint: &Pubkey,
+    ) -> Result<Vec<Instruction>> {
+        if accounts.program != PoolProgram::Amm {
+            return Ok(vec![]);
+        }
+        Ok(vec![create_associated_token_account_idempotent(
+            &self.pubkey(),
+            &self.pubkey(),
+            target_mint,
+            &accounts.token_program,
+        )?])
+    }
+}
+
+/// Без секретного ключа — только адрес
+impl fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wallet")
+            .field("pubkey", &self.pubkey())
+            .field("wsol_account", &self.wsol_account)
+            .field("budget_lamports", &self.budget_lamports)
+            .field("strategy", &self.strategy)
+            .finish()
+    }
+}
+
+/// Причина, по которой покупке не нашёлся кошелёк
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletRejection {
+    /// У стратегии нет кошельков
+    NoWallet { strategy: String },
+    /// Ни у одного подходящего кошелька не хватает бюджета
+    BudgetExhausted { amount: u64 },
+}
+
+impl fmt::Display for WalletRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WalletRejection::NoWallet { strategy } => {
+                write!(f, "нет кошельков для стратегии {}", strategy)
+            }
+            WalletRejection::BudgetExhausted { amount } => write!(
+                f,
+                "ни у одного кошелька нет свободных {} SOL",
+                lamports_to_sol(*amount)
+            ),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    /// Счётчик выбора по очереди
+    next: usize,
+    /// Покупки в процессе: пул → (индекс кошелька, сумма)
+    in_flight: HashMap<Pubkey, (usize, u64)>,
+}
+
+/// Набор торговых кошельков: распределяет покупки по политике и следит за бюджетами.
+///
+/// Занятая сумма кошелька — себестоимость его открытых позиций из хранилища
+/// ([`crate::store::Store::wallet_exposure`]) плюс покупки, выданные [`WalletPool::assign`]
+/// и ещё не снятые через [`WalletPool::release`].
+pub struct WalletPool {
+    wallets: Vec<Arc<Wallet>>,
+    policy: WalletPolicy,
+    state: Mutex<State>,
+}
+
+impl WalletPool {
+    pub fn new(wallets: Vec<Wallet>, policy: WalletPolicy) -> Result<Self> {
+        if wallets.is_empty() {
+            bail!("Не задано ни одного кошелька");
+        }
+        Ok(Self {
+            wallets: wallets.into_iter().map(Arc::new).collect(),
+            policy,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Один кошелёк без бюджета
+    pub fn single(wallet: Wallet) -> Self {
+        Self {
+            wallets: vec![Arc::new(wallet)],
+            policy: WalletPolicy::RoundRobin,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Кошельки из `WALLETS`; если список пуст — один `PRIVATE_KEY_BASE58` без бюджета.
+    /// При заданном `REMOTE_SIGNER` в `WALLETS` — адреса, подписывает сервис
+    pub fn from_config() -> Result<Self> {
+        if WALLETS.is_empty() {
+            if REMOTE_SIGNER.is_some() {
+                bail!("С REMOTE_SIGNER адреса кошельков задаются в WALLETS");
+            }
+            let keypair = keyloader::load_keypair(&PRIVATE_KEY_BASE58)?;
+            return Ok(Self::single(Wallet::new(keypair)));
+        }
+
+        let wallets = WALLETS
+            .iter()
+            .enumerate()
+            .map(|(i, (key, budget, strategy))| {
+                let wallet = load_wallet(key)
+                    .with_context(|| format!("Ошибка загрузки кошелька #{}", i + 1))?
+                    .with_budget(*budget);
+                Ok(match strategy {
+                    Some(strategy) => wallet.with_strategy(*strategy),
+                    None => wallet,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(wallets, WALLET_POLICY)
+    }
+
+    pub fn wallets(&self) -> &[Arc<Wallet>] {
+        &self.wallets
+    }
+
+    /// Первый кошелёк: идентичность QUIC-клиента TPU и подкоманды с одним кошельком
+    pub fn primary(&self) -> &Arc<Wallet> {
+        &self.wallets[0]
+    }
+
+    pub fn policy(&self) -> WalletPolicy {
+        self.policy
+    }
+
+    /// Заменяет кошельки, сохраняя политику; нужен, чтобы дописать созданные WSOL-аккаунты
+    pub fn map_wallets(self, f: impl FnMut(Wallet) -> Wallet) -> Result<Self> {
+        let wallets = self
+            .wallets
+            .into_iter()
+            .map(|wallet| {
+                Arc::try_unwrap(wallet)
+                    .ok()
+                    .context("Кошелёк уже используется")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(wallets.into_iter().map(f).collect(), self.policy)
+    }
+
+    /// Выбирает кошелёк для покупки и резервирует под неё его бюджет
+    ///
+    /// # Аргументы
+    /// * `pool` — пул покупки; резерв снимается через [`WalletPool::release`]
+    /// * `amount` — сумма покупки в лампортах
+    /// * `strategy` — стратегия покупки, учитывается политикой [`WalletPolicy::PerStrategy`]
+    /// * `exposure` — себестоимость открытых позиций по кошелькам из хранилища
+    pub fn assign(
+        &self,
+        pool: Pubkey,
+        amount: u64,
+        strategy: &str,
+        exposure: &HashMap<Pubkey, u64>,
+    ) -> Result<Arc<Wallet>, WalletRejection> {
+        let mut state = self.lock_state();
+
+        let mut candidates: Vec<usize> = (0..self.wallets.len()).collect();
+        if self.policy == WalletPolicy::PerStrategy {
+            let with_strategy = |expected: Option<&str>| -> Vec<usize> {
+                (0..self.wallets.len())
+                    .filter(|&i| self.wallets[i].strategy.as_deref() == expected)
+                    .collect()
+            };
+            candidates = with_strategy(Some(strategy));
+            if candidates.is_empty() {
+                candidates = with_strategy(None);
+            }
+            if candidates.is_empty() {
+                return Err(WalletRejection::NoWallet {
+                    strategy: strategy.to_string(),
+                });
+            }
+        }
+
+        let used = |i: usize| -> u64 {
+            let stored = exposure
+                .get(&self.wallets[i].pubkey())
+                .copied()
+                .unwrap_or_default();
+            let in_flight: u64 = state
+                .in_flight
+                .values()
+                .filter(|(wallet, _)| *wallet == i)
+                .map(|(_, amount)| amount)
+                .sum();
+            stored.saturating_add(in_flight)
+        };
+        let fits = |i: usize| used(i).saturating_add(amount) <= self.wallets[i].budget_lamports;
+
+        let chosen = match self.policy {
+            WalletPolicy::LeastExposed => candidates
+                .iter()
+                .copied()
+                .filter(|&i| fits(i))
+                .min_by_key(|&i| (used(i), i)),
+            WalletPolicy::RoundRobin | WalletPolicy::PerStrategy => {
+                let start = state.next % candidates.len();
+                candidates
+                    .iter()
+                    .cycle()
+                    .skip(start)
+                    .take(candidates.len())
+                    .copied()
+                    .find(|&i| fits(i))
+            }
+        };
+        let Some(chosen) = chosen else {
+            return Err(WalletRejection::BudgetExhausted { amount });
+        };
+
+        state.next = state.next.wrapping_add(1);
+        state.in_flight.insert(pool, (chosen, amount));
+        Ok(self.wallets[chosen].clone())
+    }
+
+    /// Снимает резерв покупки после её завершения — успешного или нет
+    pub fn release(&self, pool: &Pubkey) {
+        self.lock_state().in_flight.remove(pool);
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Кошелёк с подписью через `REMOTE_SIGNER` по адресу или с ключом в процессе
+fn load_wallet(key: &SecretSource) -> Result<Wallet> {
+    if REMOTE_SIGNER.is_some() {
+        let address = key.load()?;
+        let pubkey = Pubkey::from_str(address.expose()).context("Невалидный адрес кошелька")?;
+        let signer = RemoteSigner::from_config(pubkey)?.context("REMOTE_SIGNER не задан")?;
+        return Ok(Wallet::new(signer));
+    }
+    Ok(Wallet::new(keyloader::load_keypair(key)?))
+}
@@ -4,30 +4,116 @@ use anyhow::Result;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_program::program_pack::Pack;
 use solana_sdk::{
-    native_token::LAMPORTS_PER_SOL,
+    native_token::lamports_to_sol,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::Keypair,
     system_instruction,
     transaction::Transaction,
 };
+use solana_client::rpc_request::TokenAccountsFilter;
 use spl_token::{
-    instruction::{initialize_account, sync_native},
+    instruction::{close_account, initialize_account, sync_native},
     state::Account,
 };
 
-use crate::config::WSOL_MINT;
+use crate::config::{WSOL_FEE_RESERVE_LAMPORTS, WSOL_MINT};
+use crate::signer::{sign_transaction, TxSigner};
+use crate::swap::{associated_token_address, create_associated_token_account_idempotent};
 
-/// Создаёт временный токен-аккаунт с обёрнутым SOL (WSOL), инициализированный под заданного владельца.
-/// # Аргументы:
-/// - `rpc`: асинхронный клиент RPC
-/// - `payer`: ключ плательщика (владелец и подписант)
+/// Готовит WSOL-аккаунт кошелька с балансом `lamports`
 ///
-/// # Возвращает:
-/// - `Ok(Pubkey)` — адрес созданного WSOL-аккаунта
-/// - `Err` — при ошибке создания/инициализации
+/// Переиспользует WSOL-аккаунт владельца — ATA, а если его нет, то аккаунт с наибольшим
+/// балансом — и доливает его до `lamports`; ATA создаётся, только если WSOL-аккаунтов нет.
+/// На кошельке остаётся `WSOL_FEE_RESERVE_LAMPORTS`: если SOL не хватает, долив меньше.
+///
+/// # Аргументы
+/// * `rpc` — асинхронный клиент RPC
+/// * `payer` — владелец аккаунта и подписант
+/// * `lamports` — нужный баланс WSOL
+///
+/// # Возвращает
+/// Адрес WSOL-аккаунта
 pub async fn ensure_wsol_account(
     rpc: &RpcClient,
-    payer: &Keypair,
+    payer: &dyn TxSigner,
+    lamports: u64,
+) -> Result<Pubkey> {
+    let owner = payer.pubkey();
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+    let ata = associated_token_address(&owner, &wsol_mint, &spl_token::id())?;
+
+    let accounts = wsol_accounts(rpc, &owner).await?;
+    let existing = accounts
+        .iter()
+        .find(|(account, _)| *account == ata)
+        .or_else(|| accounts.iter().max_by_key(|(_, balance)| *balance))
+        .copied();
+
+    let mut instructions = vec![];
+    let mut reserve = WSOL_FEE_RESERVE_LAMPORTS;
+    let (account, balance) = match existing {
+        Some(existing) => existing,
+        None => {
+            instructions.push(create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &wsol_mint,
+                &spl_token::id(),
+            )?);
+            reserve += rpc
+                .get_minimum_balance_for_rent_exemption(Account::LEN)
+                .await?;
+            (ata, 0)
+        }
+    };
+
+    let missing = lamports.saturating_sub(balance);
+    let available = rpc.get_balance(&owner).await?.saturating_sub(reserve);
+    let top_up = missing.min(available);
+    if top_up < missing {
+        println!(
+            "На кошельке {} не хватает SOL: WSOL долит на {} из {} SOL",
+            owner,
+            lamports_to_sol(top_up),
+            lamports_to_sol(missing)
+        );
+    }
+    if top_up > 0 {
+        instructions.push(system_instruction::transfer(&owner, &account, top_up));
+        instructions.push(sync_native(&spl_token::id(), &account)?);
+    }
+    if instructions.is_empty() {
+        return Ok(account);
+    }
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_with_payer(&instructions, Some(&owner));
+    let tx = sign_transaction(tx, recent_blockhash, &[payer]).await?;
+
+    rpc.send_and_confirm_transaction(&tx).await?;
+
+    println!(
+        "WSOL аккаунт {}: {} SOL",
+        account,
+        lamports_to_sol(balance + top_up)
+    );
+    Ok(account)
+}
+
+/// Оборачивает `lamports` в новый WSOL-аккаунт плательщика
+///
+/// # Аргументы
+/// * `rpc` — асинхронный клиент RPC
+/// * `payer` — плательщик (владелец и подписант)
+/// * `lamports` — сколько SOL обернуть, сверх ренты
+///
+/// # Возвращает
+/// Адрес созданного WSOL-аккаунта
+pub async fn create_wsol_account(
+    rpc: &RpcClient,
+    payer: &dyn TxSigner,
+    lamports: u64,
 ) -> Result<Pubkey> {
     let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
 
@@ -40,7 +126,7 @@ pub async fn ensure_wsol_account(
     let create_acc_ix = system_instruction::create_account(
         &payer.pubkey(),
         &token_account.pubkey(),
-        rent_exemption + LAMPORTS_PER_SOL / 10,
+        rent_exemption + lamports,
         Account::LEN as u64,
         &spl_token::id(),
     );
@@ -56,15 +142,66 @@ pub async fn ensure_wsol_account(
 
     let recent_blockhash = rpc.get_latest_blockhash().await?;
 
-    let tx = Transaction::new_signed_with_payer(
+    let tx = Transaction::new_with_payer(
         &[create_acc_ix, init_acc_ix, sync_ix],
         Some(&payer.pubkey()),
-        &[payer, &token_account],
-        recent_blockhash,
     );
+    let tx = sign_transaction(tx, recent_blockhash, &[payer, &token_account]).await?;
 
     rpc.send_and_confirm_transaction(&tx).await?;
 
     println!("WSOL аккаунт создан: {}", token_account.pubkey());
     Ok(token_account.pubkey())
 }
+
+/// Возвращает WSOL-аккаунты владельца и их балансы в лампортах
+pub async fn wsol_accounts(rpc: &RpcClient, owner: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+    let wsol_mint = Pubkey::from_str(WSOL_MINT)?;
+
+    let keyed = rpc
+        .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(wsol_mint))
+        .await?;
+
+    let mut accounts = Vec::with_capacity(keyed.len());
+    for acc in keyed {
+        let pubkey = Pubkey::from_str(&acc.pubkey)?;
+        let balance = rpc.get_token_account_balance(&pubkey).await?;
+        accounts.push((pubkey, balance.amount.parse::<u64>()?));
+    }
+    Ok(accounts)
+}
+
+/// Закрывает все WSOL-аккаунты плательщика, возвращая SOL (вместе с рентой) на его счёт
+///
+/// # Возвращает
+/// Количество развёрнутых лампортов (без учёта возвращённой ренты)
+pub async fn unwrap_wsol(rpc: &RpcClient, payer: &dyn TxSigner) -> Result<u64> {
+    let accounts = wsol_accounts(rpc, &payer.pubkey()).await?;
+    if accounts.is_empty() {
+        return Ok(0);
+    }
+
+    let instructions = accounts
+        .iter()
+        .map(|(account, _)| {
+            close_account(
+                &spl_token::id(),
+                account,
+                &payer.pubkey(),
+                &payer.pubkey(),
+                &[],
+            )
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let recent_blockhash = rpc.get_latest_blockhash().await?;
+
+    let tx = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    let tx = sign_transaction(tx, recent_blockhash, &[payer]).await?;
+
+    rpc.send_and_confirm_transaction(&tx).await?;
+
+    let total = accounts.iter().map(|(_, amount)| amount).sum();
+    println!("Закрыто WSOL аккаунтов: {}", accounts.len());
+    Ok(total)
+}
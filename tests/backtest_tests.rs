@@ -0,0 +1,118 @@
+mod common;
+
+use meteora_sniper_bot::backtest::{
+    constant_product_out, extract_pool_histories, run_backtest, ExitReason, PoolEventKind,
+    StrategyParams,
+};
+use meteora_sniper_bot::geyser::recorder::RecordedUpdate;
+
+const SOL: u64 = 1_000_000_000;
+
+fn params() -> StrategyParams {
+    StrategyParams {
+        buy_lamports: SOL / 10,
+        fee_bps: 25,
+        landing_delay_slots: 1,
+        take_profit: 0.5,
+        stop_loss: 0.3,
+        max_hold_slots: 100,
+        tx_cost_lamports: 5_000,
+    }
+}
+
+fn recorded(
+    updates: Vec<yellowstone_grpc_proto::prelude::SubscribeUpdate>,
+) -> Vec<anyhow::Result<RecordedUpdate>> {
+    updates
+        .into_iter()
+        .enumerate()
+        .map(|(i, update)| {
+            Ok(RecordedUpdate {
+                received_at_micros: i as u64,
+                update,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_constant_product_out_applies_fee() {
+    let no_fee = constant_product_out(SOL, 10 * SOL, 1_000_000, 0);
+    let with_fee = constant_product_out(SOL, 10 * SOL, 1_000_000, 100);
+
+    assert_eq!(no_fee, 90_909);
+    assert!(with_fee < no_fee);
+    assert_eq!(constant_product_out(SOL, 0, 1_000_000, 0), 0);
+}
+
+#[test]
+fn test_backtest_take_profit_and_stop_loss() {
+    let (init_a, pool_a) = common::wsol_pool_update(10);
+    let (init_b, pool_b) = common::wsol_pool_update(20);
+
+    let updates = vec![
+        common::with_pool_reserves(init_a, &pool_a, 10 * SOL, 1_000_000_000),
+        // Пул A растёт: покупатели приходят после нас
+        common::pool_activity_update(12, &pool_a, 15 * SOL, 667_000_000),
+        common::pool_activity_update(14, &pool_a, 25 * SOL, 400_000_000),
+        common::pool_activity_update(15, &pool_a, 26 * SOL, 390_000_000),
+        common::with_pool_reserves(init_b, &pool_b, 10 * SOL, 1_000_000_000),
+        // Пул B: ликвидность забирают
+        common::pool_activity_update(22, &pool_b, 2 * SOL, 200_000_000),
+        common::pool_activity_update(23, &pool_b, SOL / 10, 10_000_000),
+    ];
+
+    let histories = extract_pool_histories(recorded(updates)).unwrap();
+    assert_eq!(histories.len(), 2);
+    assert_eq!(histories[0].pool, pool_a.pool);
+    assert_eq!(histories[0].events[0].kind, PoolEventKind::Init);
+    assert_eq!(histories[0].events[1].kind, PoolEventKind::Swap);
+    assert_eq!(histories[1].events[1].kind, PoolEventKind::RemoveLiquidity);
+
+    let report = run_backtest(&histories, &params());
+    assert_eq!(report.trades.len(), 2);
+
+    let win = &report.trades[0];
+    assert_eq!(win.entry_slot, 11);
+    assert_eq!(win.exit_reason, ExitReason::TakeProfit);
+    assert_eq!(win.exit_slot, 13, "Выход исполняется с задержкой посадки");
+    assert!(win.pnl_lamports > 0);
+
+    let loss = &report.trades[1];
+    assert_eq!(loss.exit_reason, ExitReason::StopLoss);
+    assert!(loss.pnl_lamports < 0);
+
+    assert_eq!(report.wins, 1);
+    assert_eq!(report.losses, 1);
+    assert_eq!(
+        report.total_pnl_lamports,
+        win.pnl_lamports + loss.pnl_lamports
+    );
+    assert_eq!(report.max_drawdown_lamports, -loss.pnl_lamports);
+}
+
+#[test]
+fn test_own_price_impact_reduces_fill() {
+    let (init, pool) = common::wsol_pool_update(1);
+    let updates = vec![common::with_pool_reserves(init, &pool, SOL, 1_000_000)];
+    let histories = extract_pool_histories(recorded(updates)).unwrap();
+
+    let small = run_backtest(
+        &histories,
+        &StrategyParams {
+            buy_lamports: SOL / 100,
+            ..params()
+        },
+    );
+    let large = run_backtest(
+        &histories,
+        &StrategyParams {
+            buy_lamports: SOL / 2,
+            ..params()
+        },
+    );
+
+    let price = |t: &meteora_sniper_bot::backtest::TradeReport| t.sol_in as f64 / t.tokens as f64;
+    assert!(price(&large.trades[0]) > price(&small.trades[0]));
+    assert_eq!(large.trades[0].exit_reason, ExitReason::EndOfData);
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use solana_sdk::{clock::MAX_PROCESSING_AGE, hash::Hash};
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::blockhash::{spawn_rpc_refresher, BlockhashCache, BlockhashSource};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+
+#[test]
+fn test_geyser_block_meta_feeds_cache() {
+    let cache = BlockhashCache::new(Duration::from_secs(10));
+    assert!(
+        cache.get().is_err(),
+        "Пустой кэш не должен отдавать blockhash"
+    );
+
+    let hash = Hash::new_unique();
+    assert!(!cache.observe(&common::ping_update()));
+    assert!(cache.observe(&common::block_meta_update(100, hash, 90)));
+
+    assert_eq!(cache.get().unwrap(), hash);
+    let cached = cache.latest().unwrap();
+    assert_eq!(cached.source, BlockhashSource::Geyser);
+    assert_eq!(
+        cached.last_valid_block_height,
+        90 + MAX_PROCESSING_AGE as u64
+    );
+}
+
+#[test]
+fn test_older_blockhash_does_not_replace_newer() {
+    let cache = BlockhashCache::new(Duration::from_secs(10));
+    let newer = Hash::new_unique();
+
+    assert!(cache.update(newer, 500, BlockhashSource::Geyser));
+    assert!(!cache.update(Hash::new_unique(), 400, BlockhashSource::Rpc));
+    assert_eq!(cache.get().unwrap(), newer);
+}
+
+#[test]
+fn test_staleness_by_age_and_block_height() {
+    let cache = BlockhashCache::new(Duration::from_millis(50));
+    cache.update(Hash::new_unique(), 1_000, BlockhashSource::Rpc);
+    assert!(!cache.is_stale());
+
+    // Сеть ушла дальше last_valid_block_height
+    cache.observe_block_height(1_001);
+    assert!(cache.is_stale());
+
+    let cache = BlockhashCache::new(Duration::from_millis(50));
+    cache.update(Hash::new_unique(), 1_000, BlockhashSource::Rpc);
+    std::thread::sleep(Duration::from_millis(80));
+    assert!(cache.is_stale(), "Давно не обновлявшийся blockhash устарел");
+    assert!(cache.latest().is_some());
+}
+
+#[tokio::test]
+async fn test_rpc_refresher_fills_cache_when_geyser_is_silent() {
+    let rpc = MockRpc::start().await;
+    let cache = Arc::new(BlockhashCache::new(Duration::from_secs(10)));
+
+    let refresher = spawn_rpc_refresher(
+        cache.clone(),
+        Arc::new(RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap()),
+        Duration::from_millis(20),
+    );
+
+    let mut filled = false;
+    for _ in 0..50 {
+        if cache.get().is_ok() {
+            filled = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    refresher.abort();
+
+    assert!(filled, "RPC должен заполнить кэш");
+    let cached = cache.latest().unwrap();
+    assert_eq!(cached.blockhash, Hash::new_from_array([7; 32]));
+    assert_eq!(cached.source, BlockhashSource::Rpc);
+}
@@ -1,14 +1,133 @@
-use meteora_sniper_bot::bloxroute::send_to_bloxroute;
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use serde_json::json;
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::bloxroute::BloxrouteRelay;
+use meteora_sniper_bot::relay::{
+    guard::{GuardedRelay, RelayPolicy},
+    Relay, RelayError,
+};
+
+#[tokio::test]
+async fn test_send_to_bloxroute_request_shape() {
+    let server = MockHttpServer::start(vec![MockResponse::Json(
+        200,
+        json!({ "signature": "5sig" }),
+    )])
+    .await;
+    let relay = BloxrouteRelay::new(server.url("/solana/submit"), "test-key");
+
+    let ack = relay.send("dHg=", 100_000).await.unwrap();
+    assert_eq!(ack.signature.as_deref(), Some("5sig"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let req = &requests[0];
+    assert_eq!(req.method, "POST");
+    assert_eq!(req.path, "/solana/submit");
+    assert_eq!(req.header("authorization"), Some("Bearer test-key"));
+
+    let body = req.json();
+    assert_eq!(body["transaction"], "dHg=");
+    assert_eq!(body["channel"], "solana-mainnet");
+    assert_eq!(body["mev"], true);
+    assert_eq!(body["max_block_delay"], 2);
+    assert_eq!(body["tip"], "100000");
+}
 
 #[tokio::test]
 async fn test_send_to_bloxroute_invalid_tx() {
-    let tx_base64 = "invalid_base64";
-    let tip = 100_000;
+    let server = MockHttpServer::start(vec![MockResponse::Json(
+        200,
+        json!({ "error": { "code": -32602, "message": "invalid transaction" } }),
+    )])
+    .await;
+    let relay = BloxrouteRelay::new(server.url("/"), "test-key");
 
-    let result = send_to_bloxroute(tx_base64, tip).await;
+    let result = relay.send("invalid_base64", 100_000).await;
 
-    assert!(
-        result.is_err(),
+    assert_eq!(
+        result,
+        Err(RelayError::Rejected {
+            relay: "Bloxroute".into(),
+            code: Some(-32602),
+            message: "invalid transaction".into(),
+        }),
         "Bloxroute должен вернуть ошибку при невалидной транзакции"
     );
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_send_to_bloxroute_non_json_response() {
+    let server = MockHttpServer::start(vec![MockResponse::Raw(502, "Bad Gateway".into())]).await;
+    let relay = BloxrouteRelay::new(server.url("/"), "test-key");
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(matches!(err, RelayError::Transport { .. }), "{:?}", err);
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_send_to_bloxroute_classifies_http_errors() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Json(401, json!({ "code": 16, "message": "invalid auth header" })),
+        MockResponse::Json(429, json!({ "message": "too many requests" })),
+        MockResponse::Json(
+            200,
+            json!({ "code": 3, "message": "transaction already processed" }),
+        ),
+    ])
+    .await;
+    let relay = BloxrouteRelay::new(server.url("/"), "bad-key");
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert_eq!(
+        err,
+        RelayError::Auth {
+            relay: "Bloxroute".into(),
+            message: "invalid auth header".into(),
+        }
+    );
+    assert!(!err.is_retryable());
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(matches!(err, RelayError::RateLimited { .. }), "{:?}", err);
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(
+        matches!(err, RelayError::Rejected { code: Some(3), .. }),
+        "{:?}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_send_to_bloxroute_slow_response() {
+    let server = MockHttpServer::start(vec![MockResponse::Delay(
+        Duration::from_secs(5),
+        Box::new(MockResponse::Json(200, json!({}))),
+    )])
+    .await;
+    let relay = GuardedRelay::new(
+        Arc::new(BloxrouteRelay::new(server.url("/"), "test-key")),
+        RelayPolicy {
+            timeout: Duration::from_millis(200),
+            max_retries: 0,
+            ..RelayPolicy::from_config(Duration::ZERO)
+        },
+    );
+
+    // Без ответа отправка обрывается по таймауту, а не висит до ответа сервера
+    let result = tokio::time::timeout(Duration::from_secs(2), relay.send("dHg=", 1))
+        .await
+        .expect("Таймаут ретранслятора должен сработать раньше");
+    assert!(
+        matches!(result, Err(RelayError::Timeout { .. })),
+        "{:?}",
+        result
+    );
+    assert_eq!(server.requests().len(), 1);
+}
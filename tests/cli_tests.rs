@@ -0,0 +1,96 @@
+use clap::Parser;
+use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+use meteora_sniper_bot::cli::{check_record_dir, check_store_path, Cli, Command};
+use meteora_sniper_bot::store::Store;
+
+#[test]
+fn test_no_subcommand_means_run() {
+    let cli = Cli::try_parse_from(["bot"]).unwrap();
+    assert_eq!(cli.command, None);
+}
+
+#[test]
+fn test_subcommands_parse_arguments() {
+    let cli = Cli::try_parse_from(["bot", "wrap", "0.25"]).unwrap();
+    assert_eq!(
+        cli.command,
+        Some(Command::Wrap {
+            amount: LAMPORTS_PER_SOL / 4,
+            wallet: None
+        })
+    );
+
+    let wallet = Pubkey::new_unique();
+    let cli = Cli::try_parse_from(["bot", "unwrap", "--wallet", &wallet.to_string()]).unwrap();
+    assert_eq!(
+        cli.command,
+        Some(Command::Unwrap {
+            wallet: Some(wallet)
+        })
+    );
+    let cli = Cli::try_parse_from(["bot", "balance"]).unwrap();
+    assert_eq!(cli.command, Some(Command::Balance { wallet: None }));
+
+    let mint = Pubkey::new_unique();
+    let cli = Cli::try_parse_from(["bot", "sell", &mint.to_string(), "--min-out", "5"]).unwrap();
+    assert_eq!(
+        cli.command,
+        Some(Command::Sell {
+            mint,
+            amount: None,
+            min_out: 5
+        })
+    );
+
+    let cli = Cli::try_parse_from(["bot", "replay", "rec", "--realtime"]).unwrap();
+    assert_eq!(
+        cli.command,
+        Some(Command::Replay {
+            path: "rec".into(),
+            realtime: true
+        })
+    );
+
+    let cli = Cli::try_parse_from(["bot", "check-config", "--online"]).unwrap();
+    assert_eq!(cli.command, Some(Command::CheckConfig { online: true }));
+}
+
+#[test]
+fn test_invalid_arguments_are_rejected() {
+    assert!(Cli::try_parse_from(["bot", "wrap", "-1"]).is_err());
+    assert!(Cli::try_parse_from(["bot", "wrap", "abc"]).is_err());
+    assert!(Cli::try_parse_from(["bot", "sell", "not-a-pubkey"]).is_err());
+    assert!(Cli::try_parse_from(["bot", "inspect", "bad"]).is_err());
+}
+
+#[test]
+fn test_storage_checks_do_not_create_anything() {
+    let dir = std::env::temp_dir().join(format!("check_config_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Новая база и каталог записи только проверяются
+    let db = dir.join("bot.db");
+    assert!(check_store_path(&db).unwrap().contains("будет создана"));
+    assert!(!db.exists());
+    let records = dir.join("records").join("geyser");
+    assert!(check_record_dir(&records).unwrap().contains("будет создан"));
+    assert!(!dir.join("records").exists());
+    assert!(check_store_path(&dir.join("missing").join("bot.db")).is_err());
+
+    // Существующая база открывается только на чтение: схема и данные не меняются
+    // (служебные -wal/-shm режима WAL SQLite создаёт и при чтении)
+    drop(Store::open(&db, 0).unwrap());
+    let before = std::fs::read(&db).unwrap();
+    let pools = check_store_path(&db).unwrap();
+    assert!(pools.contains("увиденных пулов: 0"));
+    assert_eq!(std::fs::read(&db).unwrap(), before);
+
+    // Не база SQLite — ошибка
+    let garbage = dir.join("garbage.db");
+    std::fs::write(&garbage, b"not a database").unwrap();
+    assert!(check_store_path(&garbage).is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,183 @@
+//! Встроенный Yellowstone gRPC сервер для сквозных тестов
+//!
+//! Каждая подписка получает один и тот же сценарий: обновления, ошибки, паузы
+//! и обрыв соединения.
+
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use yellowstone_grpc_proto::{
+    prelude::{
+        geyser_server::{Geyser, GeyserServer},
+        GetBlockHeightRequest, GetBlockHeightResponse, GetLatestBlockhashRequest,
+        GetLatestBlockhashResponse, GetSlotRequest, GetSlotResponse, GetVersionRequest,
+        GetVersionResponse, IsBlockhashValidRequest, IsBlockhashValidResponse, PingRequest,
+        PongResponse, SubscribeReplayInfoRequest, SubscribeReplayInfoResponse, SubscribeRequest,
+        SubscribeUpdate,
+    },
+    tonic::{self, transport::Server, Request, Response, Status, Streaming},
+};
+
+/// Шаг сценария потока
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum ScriptStep {
+    Update(SubscribeUpdate),
+    Error(Status),
+    Sleep(Duration),
+    /// Сервер закрывает поток; оставшиеся шаги не отправляются
+    Disconnect,
+}
+
+#[derive(Clone)]
+struct MockGeyser {
+    script: Arc<Vec<ScriptStep>>,
+    requests: Arc<Mutex<Vec<SubscribeRequest>>>,
+}
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Geyser for MockGeyser {
+    type SubscribeStream = UpdateStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let mut incoming = request.into_inner();
+        let (tx, rx) = mpsc::channel(16);
+        let script = self.script.clone();
+        let requests = self.requests.clone();
+
+        tokio::spawn(async move {
+            // Поток начинается после первого SubscribeRequest, как у настоящего сервера
+            match incoming.next().await {
+                Some(Ok(req)) => requests.lock().unwrap().push(req),
+                _ => return,
+            }
+
+            for step in script.iter() {
+                let sent = match step {
+                    ScriptStep::Update(update) => tx.send(Ok(update.clone())).await,
+                    ScriptStep::Error(status) => tx.send(Err(status.clone())).await,
+                    ScriptStep::Sleep(delay) => {
+                        tokio::time::sleep(*delay).await;
+                        Ok(())
+                    }
+                    ScriptStep::Disconnect => return,
+                };
+                if sent.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn subscribe_replay_info(
+        &self,
+        _request: Request<SubscribeReplayInfoRequest>,
+    ) -> Result<Response<SubscribeReplayInfoResponse>, Status> {
+        Ok(Response::new(SubscribeReplayInfoResponse::default()))
+    }
+
+    async fn ping(&self, request: Request<PingRequest>) -> Result<Response<PongResponse>, Status> {
+        Ok(Response::new(PongResponse {
+            count: request.into_inner().count,
+        }))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: Request<GetLatestBlockhashRequest>,
+    ) -> Result<Response<GetLatestBlockhashResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: Request<GetBlockHeightRequest>,
+    ) -> Result<Response<GetBlockHeightResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: Request<GetSlotRequest>,
+    ) -> Result<Response<GetSlotResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: Request<IsBlockhashValidRequest>,
+    ) -> Result<Response<IsBlockhashValidResponse>, Status> {
+        Err(Status::unimplemented("mock"))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> Result<Response<GetVersionResponse>, Status> {
+        Ok(Response::new(GetVersionResponse {
+            version: "mock".to_string(),
+        }))
+    }
+}
+
+/// Локальный Geyser gRPC сервер; останавливается при drop
+pub struct MockGeyserServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<SubscribeRequest>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockGeyserServer {
+    pub async fn start(script: Vec<ScriptStep>) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(vec![]));
+
+        let service = MockGeyser {
+            script: Arc::new(script),
+            requests: requests.clone(),
+        };
+        let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener);
+        let task = tokio::spawn(async move {
+            let _ = Server::builder()
+                .add_service(GeyserServer::new(service))
+                .serve_with_incoming(incoming)
+                .await;
+        });
+
+        Self {
+            addr,
+            requests,
+            task,
+        }
+    }
+
+    /// Адрес для `monitor_liquidity_additions` (без TLS)
+    pub fn endpoint(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Запросы подписки, полученные сервером
+    pub fn subscribe_requests(&self) -> Vec<SubscribeRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockGeyserServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
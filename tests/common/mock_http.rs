@@ -0,0 +1,231 @@
+//! Встроенный HTTP-сервер для офлайн-тестов ретрансляторов
+//!
+//! Записывает каждый запрос и отвечает по сценарию: очередной `MockResponse`
+//! из очереди, а когда она пуста — последним ответом. Вместо очереди можно
+//! передать обработчик, вычисляющий ответ по запросу.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+/// Сценарный ответ сервера
+#[derive(Debug, Clone)]
+pub enum MockResponse {
+    /// Ответ со статусом и JSON-телом
+    Json(u16, Value),
+    /// Ответ со статусом и произвольным телом
+    Raw(u16, String),
+    /// Ответ приходит только через заданное время — для проверки таймаутов
+    Delay(Duration, Box<MockResponse>),
+}
+
+/// Запрос, полученный сервером
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    /// Заголовки с именами в нижнем регистре
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).expect("Тело запроса должно быть JSON")
+    }
+}
+
+type Handler = Arc<dyn Fn(&RecordedRequest) -> MockResponse + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    requests: Vec<RecordedRequest>,
+    connections: usize,
+    responses: VecDeque<MockResponse>,
+    last: Option<MockResponse>,
+    handler: Option<Handler>,
+}
+
+/// Локальный HTTP/1.1 сервер на случайном порту; останавливается при drop
+pub struct MockHttpServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockHttpServer {
+    /// Запускает сервер со сценарием ответов
+    pub async fn start(responses: Vec<MockResponse>) -> Self {
+        Self::start_with_state(State {
+            responses: responses.into(),
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Запускает сервер, отвечающий через `handler`
+    pub async fn start_with_handler<F>(handler: F) -> Self
+    where
+        F: Fn(&RecordedRequest) -> MockResponse + Send + Sync + 'static,
+    {
+        Self::start_with_state(State {
+            handler: Some(Arc::new(handler)),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn start_with_state(state: State) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(state));
+
+        let accept_state = state.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                accept_state.lock().unwrap().connections += 1;
+                tokio::spawn(serve_connection(socket, accept_state.clone()));
+            }
+        });
+
+        Self { addr, state, task }
+    }
+
+    /// Полный URL для пути `path`
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Все полученные запросы в порядке поступления
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Сколько TCP-соединений принял сервер
+    pub fn connections(&self) -> usize {
+        self.state.lock().unwrap().connections
+    }
+}
+
+impl Drop for MockHttpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve_connection(socket: TcpStream, state: Arc<Mutex<State>>) {
+    let (reader, mut writer) = socket.into_split();
+    let mut reader = BufReader::new(reader);
+
+    // Соединение может переиспользоваться клиентом (keep-alive)
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).await.unwrap_or(0) == 0 {
+            return;
+        }
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default().to_string();
+        let path = parts.next().unwrap_or_default().to_string();
+
+        let mut headers = HashMap::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+            }
+        }
+
+        let len = headers
+            .get("content-length")
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(0);
+        let mut body = vec![0u8; len];
+        if reader.read_exact(&mut body).await.is_err() {
+            return;
+        }
+
+        let head_only = method == "HEAD";
+        let response = {
+            let mut state = state.lock().unwrap();
+            let request = RecordedRequest {
+                method,
+                path,
+                headers,
+                body,
+            };
+            let from_handler = state.handler.as_ref().map(|h| h(&request));
+            state.requests.push(request);
+
+            match from_handler {
+                Some(response) => response,
+                None => {
+                    let next = state.responses.pop_front().or_else(|| state.last.clone());
+                    state.last = next.clone();
+                    next.unwrap_or(MockResponse::Raw(404, String::new()))
+                }
+            }
+        };
+
+        if write_response(&mut writer, response, head_only)
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+async fn write_response(
+    writer: &mut tokio::net::tcp::OwnedWriteHalf,
+    mut response: MockResponse,
+    head_only: bool,
+) -> std::io::Result<()> {
+    while let MockResponse::Delay(delay, inner) = response {
+        tokio::time::sleep(delay).await;
+        response = *inner;
+    }
+
+    let (status, body) = match response {
+        MockResponse::Json(status, value) => (status, value.to_string()),
+        MockResponse::Raw(status, body) => (status, body),
+        MockResponse::Delay(..) => unreachable!(),
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: keep-alive\r\n\r\n",
+        status,
+        body.len()
+    );
+    writer.write_all(head.as_bytes()).await?;
+    // На HEAD отвечаем только заголовками, иначе тело собьёт следующий ответ в соединении
+    if !head_only {
+        writer.write_all(body.as_bytes()).await?;
+    }
+    writer.flush().await
+}
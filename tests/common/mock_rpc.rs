@@ -0,0 +1,279 @@
+//! Минимальный Solana JSON-RPC поверх `MockHttpServer`
+//!
+//! Подтверждает любую отправленную транзакцию и запоминает её; `getTransaction`
+//! по умолчанию отдаёт успешную транзакцию без изменений токен-балансов.
+//! Аккаунты, заданные через `set_account`, отдаются в `getAccountInfo`/`getMultipleAccounts`.
+//! Статусы подписей и токен-балансы транзакций можно переопределить, а токен-аккаунты
+//! владельцев для `getTokenAccountsByOwner`/`getTokenAccountBalance` — задать.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{
+    account::Account, hash::Hash, pubkey::Pubkey, signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use super::mock_http::{MockHttpServer, MockResponse, RecordedRequest};
+
+pub struct MockRpc {
+    pub server: MockHttpServer,
+    sent: Arc<Mutex<Vec<VersionedTransaction>>>,
+    accounts: Arc<Mutex<HashMap<Pubkey, Account>>>,
+    overrides: Arc<Mutex<Overrides>>,
+}
+
+#[derive(Default)]
+struct Overrides {
+    /// Статус подписи; `Value::Null` — транзакция не найдена
+    statuses: HashMap<String, Value>,
+    /// Ответ `getTransaction`
+    transactions: HashMap<String, Value>,
+    /// Токен-аккаунты: (владелец, аккаунт, mint, баланс)
+    token_accounts: Vec<(Pubkey, Pubkey, Pubkey, u64)>,
+}
+
+impl MockRpc {
+    pub async fn start() -> Self {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let accounts = Arc::new(Mutex::new(HashMap::new()));
+        let overrides = Arc::new(Mutex::new(Overrides::default()));
+        let handler_sent = sent.clone();
+        let handler_accounts = accounts.clone();
+        let handler_overrides = overrides.clone();
+        let server = MockHttpServer::start_with_handler(move |req| {
+            let body = req.json();
+            let result = handle(
+                &body,
+                req,
+                &handler_sent,
+                &handler_accounts,
+                &handler_overrides,
+            );
+            MockResponse::Json(
+                200,
+                json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }),
+            )
+        })
+        .await;
+
+        Self {
+            server,
+            sent,
+            accounts,
+            overrides,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url("/")
+    }
+
+    /// Транзакции, пришедшие через `sendTransaction`
+    pub fn sent_transactions(&self) -> Vec<VersionedTransaction> {
+        self.sent.lock().unwrap().clone()
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: Account) {
+        self.accounts.lock().unwrap().insert(pubkey, account);
+    }
+
+    /// Статус подписи: `None` — транзакция не найдена, `Some(err)` — ошибка
+    /// исполнения в формате `TransactionError` (`Value::Null` — успех)
+    pub fn set_signature_status(&self, signature: Signature, status: Option<Value>) {
+        let status = match status {
+            Some(err) => json!({
+                "slot": 100,
+                "confirmations": null,
+                "err": err,
+                "status": if err.is_null() { json!({ "Ok": null }) } else { json!({ "Err": err }) },
+                "confirmationStatus": "finalized"
+            }),
+            None => Value::Null,
+        };
+        self.overrides
+            .lock()
+            .unwrap()
+            .statuses
+            .insert(signature.to_string(), status);
+    }
+
+    /// Токен-аккаунт `account` владельца `owner` с балансом `amount` токена `mint`
+    pub fn set_token_account(&self, owner: Pubkey, account: Pubkey, mint: Pubkey, amount: u64) {
+        self.overrides
+            .lock()
+            .unwrap()
+            .token_accounts
+            .push((owner, account, mint, amount));
+    }
+
+    /// Успешная транзакция, в которой баланс `mint` у `owner` вырос с `pre` до `post`
+    pub fn set_token_balance_change(
+        &self,
+        signature: Signature,
+        owner: Pubkey,
+        mint: Pubkey,
+        pre: u64,
+        post: u64,
+    ) {
+        let balance = |amount: u64| {
+            json!([{
+                "accountIndex": 1,
+                "mint": mint.to_string(),
+                "owner": owner.to_string(),
+                "programId": spl_token::id().to_string(),
+                "uiTokenAmount": {
+                    "uiAmount": null,
+                    "decimals": 0,
+                    "amount": amount.to_string(),
+                    "uiAmountString": amount.to_string()
+                }
+            }])
+        };
+        let transaction = confirmed_transaction(balance(pre), balance(post));
+        self.overrides
+            .lock()
+            .unwrap()
+            .transactions
+            .insert(signature.to_string(), transaction);
+    }
+}
+
+/// Ответ `getTransaction`: успешная транзакция с заданными токен-балансами
+fn confirmed_transaction(pre_token_balances: Value, post_token_balances: Value) -> Value {
+    json!({
+        "slot": 100,
+        "blockTime": null,
+        "transaction": ["", "base64"],
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5_000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": pre_token_balances,
+            "postTokenBalances": post_token_balances
+        }
+    })
+}
+
+fn ui_account(account: Option<&Account>) -> Value {
+    match account {
+        Some(account) => json!({
+            "lamports": account.lamports,
+            "data": [general_purpose::STANDARD.encode(&account.data), "base64"],
+            "owner": account.owner.to_string(),
+            "executable": account.executable,
+            "rentEpoch": 0,
+            "space": account.data.len()
+        }),
+        None => Value::Null,
+    }
+}
+
+fn handle(
+    body: &Value,
+    _req: &RecordedRequest,
+    sent: &Mutex<Vec<VersionedTransaction>>,
+    accounts: &Mutex<HashMap<Pubkey, Account>>,
+    overrides: &Mutex<Overrides>,
+) -> Value {
+    let overrides = overrides.lock().unwrap();
+    let context = json!({ "slot": 100 });
+    let accounts = accounts.lock().unwrap();
+    let account = |key: &Value| {
+        let key = key.as_str().and_then(|k| k.parse::<Pubkey>().ok());
+        ui_account(key.and_then(|k| accounts.get(&k)))
+    };
+    match body["method"].as_str().unwrap_or_default() {
+        "getLatestBlockhash" => json!({
+            "context": context,
+            "value": {
+                "blockhash": Hash::new_from_array([7; 32]).to_string(),
+                "lastValidBlockHeight": 1_000
+            }
+        }),
+        "isBlockhashValid" => json!({ "context": context, "value": true }),
+        "sendTransaction" => {
+            let encoded = body["params"][0].as_str().unwrap_or_default();
+            let bytes = general_purpose::STANDARD.decode(encoded).unwrap();
+            let tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+            let signature = tx.signatures[0].to_string();
+            sent.lock().unwrap().push(tx);
+            json!(signature)
+        }
+        "getSignatureStatuses" => {
+            let signatures = body["params"][0].as_array().cloned().unwrap_or_default();
+            let statuses: Vec<Value> = signatures
+                .iter()
+                .map(|signature| {
+                    let overridden = signature
+                        .as_str()
+                        .and_then(|s| overrides.statuses.get(s).cloned());
+                    overridden.unwrap_or_else(|| {
+                        json!({
+                            "slot": 100,
+                            "confirmations": null,
+                            "err": null,
+                            "status": { "Ok": null },
+                            "confirmationStatus": "finalized"
+                        })
+                    })
+                })
+                .collect();
+            json!({ "context": context, "value": statuses })
+        }
+        "getTransaction" => body["params"][0]
+            .as_str()
+            .and_then(|s| overrides.transactions.get(s).cloned())
+            .unwrap_or_else(|| confirmed_transaction(json!([]), json!([]))),
+        "getTokenAccountsByOwner" => {
+            let owner = body["params"][0].as_str().unwrap_or_default();
+            let mint = body["params"][1]["mint"].as_str().unwrap_or_default();
+            let keyed: Vec<Value> = overrides
+                .token_accounts
+                .iter()
+                .filter(|(o, _, m, _)| o.to_string() == owner && m.to_string() == mint)
+                .map(|(_, account, _, _)| {
+                    json!({
+                        "pubkey": account.to_string(),
+                        "account": ui_account(Some(&Account::new(0, 165, &spl_token::id())))
+                    })
+                })
+                .collect();
+            json!({ "context": context, "value": keyed })
+        }
+        "getTokenAccountBalance" => {
+            let account = body["params"][0].as_str().unwrap_or_default();
+            match overrides
+                .token_accounts
+                .iter()
+                .find(|(_, a, _, _)| a.to_string() == account)
+            {
+                Some((_, _, _, amount)) => json!({
+                    "context": context,
+                    "value": {
+                        "uiAmount": null,
+                        "decimals": 9,
+                        "amount": amount.to_string(),
+                        "uiAmountString": amount.to_string()
+                    }
+                }),
+                None => Value::Null,
+            }
+        }
+        "getMinimumBalanceForRentExemption" => json!(2_039_280),
+        "getBalance" => json!({ "context": context, "value": 5_000_000_000u64 }),
+        "getAccountInfo" => json!({ "context": context, "value": account(&body["params"][0]) }),
+        "getMultipleAccounts" => {
+            let keys = body["params"][0].as_array().cloned().unwrap_or_default();
+            let values: Vec<Value> = keys.iter().map(account).collect();
+            json!({ "context": context, "value": values })
+        }
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,99 @@
+//! Локальный QUIC-сервер, принимающий транзакции как TPU валидатора
+//!
+//! Каждый однонаправленный поток считается одной транзакцией.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use quinn::{crypto::rustls::QuicServerConfig, Endpoint, ServerConfig};
+use solana_sdk::signature::Keypair;
+use solana_tls_utils::{new_dummy_x509_certificate, tls_server_config_builder};
+
+use meteora_sniper_bot::tpu::ALPN_TPU_PROTOCOL_ID;
+
+const MAX_PACKET_BYTES: usize = 64 * 1024;
+
+#[derive(Default)]
+struct State {
+    packets: Vec<Vec<u8>>,
+    connections: usize,
+}
+
+pub struct MockTpuServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+    endpoint: Endpoint,
+}
+
+impl MockTpuServer {
+    pub async fn start() -> Self {
+        let (certificate, key) = new_dummy_x509_certificate(&Keypair::new());
+        let mut crypto = tls_server_config_builder()
+            .with_single_cert(vec![certificate], key)
+            .unwrap();
+        crypto.alpn_protocols = vec![ALPN_TPU_PROTOCOL_ID.to_vec()];
+        let config =
+            ServerConfig::with_crypto(Arc::new(QuicServerConfig::try_from(crypto).unwrap()));
+
+        let endpoint = Endpoint::server(config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let addr = endpoint.local_addr().unwrap();
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let accept_endpoint = endpoint.clone();
+        let accept_state = state.clone();
+        tokio::spawn(async move {
+            while let Some(incoming) = accept_endpoint.accept().await {
+                let state = accept_state.clone();
+                tokio::spawn(async move {
+                    let Ok(connection) = incoming.await else {
+                        return;
+                    };
+                    state.lock().unwrap().connections += 1;
+                    while let Ok(mut stream) = connection.accept_uni().await {
+                        if let Ok(packet) = stream.read_to_end(MAX_PACKET_BYTES).await {
+                            state.lock().unwrap().packets.push(packet);
+                        }
+                    }
+                });
+            }
+        });
+
+        Self {
+            addr,
+            state,
+            endpoint,
+        }
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Принятые транзакции в порядке получения
+    pub fn packets(&self) -> Vec<Vec<u8>> {
+        self.state.lock().unwrap().packets.clone()
+    }
+
+    /// Количество установленных QUIC-соединений
+    pub fn connections(&self) -> usize {
+        self.state.lock().unwrap().connections
+    }
+
+    /// Ждёт, пока сервер примет `count` транзакций
+    pub async fn wait_for_packets(&self, count: usize, timeout: Duration) -> Vec<Vec<u8>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.packets().len() < count && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        self.packets()
+    }
+}
+
+impl Drop for MockTpuServer {
+    fn drop(&mut self) {
+        self.endpoint.close(0u32.into(), b"shutdown");
+    }
+}
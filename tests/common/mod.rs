@@ -0,0 +1,601 @@
+//! Общие фикстуры интеграционных тестов
+#![allow(dead_code)]
+
+pub mod mock_geyser;
+pub mod mock_http;
+pub mod mock_rpc;
+pub mod mock_tpu;
+
+use std::str::FromStr;
+
+use solana_sdk::{
+    hash::{hash, Hash},
+    pubkey::Pubkey,
+    signature::Signature,
+};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, BlockHeight, CompiledInstruction, Message, MessageHeader,
+    SubscribeUpdate, SubscribeUpdateBlockMeta, SubscribeUpdatePing, SubscribeUpdateTransaction,
+    SubscribeUpdateTransactionInfo, TokenBalance, Transaction, TransactionStatusMeta,
+    UiTokenAmount,
+};
+
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::damm_v2::{self, InitializeKind, PoolInitDammV2};
+use meteora_sniper_bot::dbc::{self, CurveConfigInit, CurveInit, MigrationInit};
+use meteora_sniper_bot::dlmm::{self, LbPairInit};
+
+/// Дискриминатор Anchor по имени вида `global:swap` (как он считается — см. `dlmm`)
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    hash(name.as_bytes()).to_bytes()[..8].try_into().unwrap()
+}
+
+/// Сверяет захардкоженные дискриминаторы программы с именами инструкций и аккаунтов
+pub fn assert_anchor_discriminators(expected: &[([u8; 8], &str)]) {
+    for (discriminator, name) in expected {
+        assert_eq!(*discriminator, anchor_discriminator(name), "{}", name);
+    }
+}
+
+/// Пул из фикстурной init-транзакции
+#[derive(Debug, Clone, Copy)]
+pub struct FixturePool {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub target_mint: Pubkey,
+    pub signature: Signature,
+}
+
+/// Строит обновление Geyser с инициализацией пула Meteora для пары `token_a`/`token_b`
+///
+/// Аккаунты инструкции лежат в ключах 1..=10, программа — в ключе 11.
+pub fn init_pool_update_with(
+    slot: u64,
+    token_a: Pubkey,
+    token_b: Pubkey,
+) -> (SubscribeUpdate, Pubkey, Pubkey) {
+    init_pool_update_by(slot, Pubkey::new_unique(), token_a, token_b)
+}
+
+/// То же, что `init_pool_update_with`, но с заданным плательщиком — он же единственный подписант
+pub fn init_pool_update_by(
+    slot: u64,
+    fee_payer: Pubkey,
+    token_a: Pubkey,
+    token_b: Pubkey,
+) -> (SubscribeUpdate, Pubkey, Pubkey) {
+    let pool = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let mut keys = vec![fee_payer];
+    keys.extend([
+        Pubkey::new_unique(), // user_source
+        Pubkey::new_unique(), // user_destination
+        pool,                 // pool / pool_source
+        Pubkey::new_unique(), // pool_destination
+        authority,            // pool_authority
+        spl_token::id(),      // token_program
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        token_a,
+        token_b,
+    ]);
+    keys.push(Pubkey::from_str(METEORA_PROGRAM_ID).unwrap());
+
+    let update = transaction_update(
+        slot,
+        &keys,
+        CompiledInstruction {
+            program_id_index: 11,
+            accounts: (1..=10).collect(),
+            data: vec![2, 0, 0, 0],
+        },
+    );
+
+    (update, pool, authority)
+}
+
+/// Обновление Geyser с транзакцией из одной инструкции; подписант — первый ключ,
+/// последний ключ (программа) — только для чтения
+fn transaction_update(
+    slot: u64,
+    keys: &[Pubkey],
+    instruction: CompiledInstruction,
+) -> SubscribeUpdate {
+    transaction_update_signed_by(slot, keys, 1, instruction)
+}
+
+/// То же, что `transaction_update`, но подписантов — первые `signers` ключей
+fn transaction_update_signed_by(
+    slot: u64,
+    keys: &[Pubkey],
+    signers: u32,
+    instruction: CompiledInstruction,
+) -> SubscribeUpdate {
+    let signature = Signature::new_unique();
+    let tx = Transaction {
+        signatures: vec![signature.as_ref().to_vec()],
+        message: Some(Message {
+            header: Some(MessageHeader {
+                num_required_signatures: signers,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            }),
+            account_keys: keys.iter().map(|k| k.to_bytes().to_vec()).collect(),
+            recent_blockhash: vec![0; 32],
+            instructions: vec![instruction],
+            versioned: false,
+            address_table_lookups: vec![],
+        }),
+    };
+
+    SubscribeUpdate {
+        filters: vec![],
+        update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                is_vote: false,
+                transaction: Some(tx),
+                meta: None,
+                index: 0,
+            }),
+            slot,
+        })),
+        created_at: None,
+    }
+}
+
+/// Строит обновление Geyser с `initialize_lb_pair` DLMM для пары `token_x`/`token_y`
+///
+/// Плательщик — он же funder; аккаунты инструкции — в порядке IDL DLMM.
+pub fn init_lb_pair_update(
+    slot: u64,
+    token_x: Pubkey,
+    token_y: Pubkey,
+    active_id: i32,
+) -> (SubscribeUpdate, LbPairInit) {
+    let funder = Pubkey::new_unique();
+    let keys = vec![
+        funder,
+        Pubkey::new_unique(), // lb_pair
+        Pubkey::new_unique(), // bin_array_bitmap_extension
+        token_x,
+        token_y,
+        Pubkey::new_unique(), // reserve_x
+        Pubkey::new_unique(), // reserve_y
+        Pubkey::new_unique(), // oracle
+        Pubkey::new_unique(), // preset_parameter
+        spl_token::id(),
+        dlmm::program_id().unwrap(),
+    ];
+
+    let mut data = dlmm::INITIALIZE_LB_PAIR_DISCRIMINATOR.to_vec();
+    data.extend(active_id.to_le_bytes());
+    data.extend(25u16.to_le_bytes());
+
+    let update = transaction_update(
+        slot,
+        &keys,
+        CompiledInstruction {
+            program_id_index: 10,
+            accounts: vec![1, 2, 3, 4, 5, 6, 7, 8, 0, 9],
+            data,
+        },
+    );
+
+    let init = LbPairInit {
+        lb_pair: keys[1],
+        token_x_mint: token_x,
+        token_y_mint: token_y,
+        reserve_x: keys[5],
+        reserve_y: keys[6],
+        oracle: keys[7],
+        funder,
+        token_program: spl_token::id(),
+        active_id,
+        bin_step: 25,
+    };
+    (update, init)
+}
+
+/// Строит обновление Geyser с `initialize_pool` DAMM v2 для пары `token_a`/`token_b`
+///
+/// Подписывают плательщик (ключ 0) и mint NFT позиции (ключ 1), который
+/// в инструкции стоит раньше плательщика; аккаунты — в порядке IDL cp-amm.
+pub fn init_damm_v2_pool_update(
+    slot: u64,
+    token_a: Pubkey,
+    token_b: Pubkey,
+) -> (SubscribeUpdate, PoolInitDammV2) {
+    let program = damm_v2::program_id().unwrap();
+    let payer = Pubkey::new_unique();
+    let position_nft_mint = Pubkey::new_unique();
+    let keys = vec![
+        payer,
+        position_nft_mint,
+        Pubkey::new_unique(), // creator
+        Pubkey::new_unique(), // position_nft_account
+        Pubkey::new_unique(), // config
+        damm_v2::pool_authority_address(&program),
+        Pubkey::new_unique(), // pool
+        Pubkey::new_unique(), // position
+        token_a,
+        token_b,
+        Pubkey::new_unique(), // token_a_vault
+        Pubkey::new_unique(), // token_b_vault
+        Pubkey::new_unique(), // payer_token_a
+        Pubkey::new_unique(), // payer_token_b
+        spl_token::id(),
+        Pubkey::new_unique(), // token_2022_program
+        Pubkey::new_unique(), // system_program
+        damm_v2::event_authority_address(&program),
+        program,
+    ];
+
+    // creator, position_nft_mint, position_nft_account, payer, config, pool_authority, pool, ...
+    let mut accounts = vec![2, 1, 3, 0];
+    accounts.extend(4..=13);
+    accounts.extend([14, 14, 15, 16, 17, 18]);
+
+    let mut data = damm_v2::INITIALIZE_POOL_DISCRIMINATOR.to_vec();
+    data.extend([0; 33]); // liquidity, sqrt_price, activation_point: None
+
+    let update = transaction_update_signed_by(
+        slot,
+        &keys,
+        2,
+        CompiledInstruction {
+            program_id_index: 18,
+            accounts,
+            data,
+        },
+    );
+
+    let init = PoolInitDammV2 {
+        kind: InitializeKind::Config,
+        pool: keys[6],
+        token_a_mint: token_a,
+        token_b_mint: token_b,
+        token_a_vault: keys[10],
+        token_b_vault: keys[11],
+        token_a_program: spl_token::id(),
+        token_b_program: spl_token::id(),
+        pool_authority: keys[5],
+        payer,
+    };
+    (update, init)
+}
+
+/// Строит обновление Geyser с созданием конфига кривой DBC с quote-токеном `quote_mint`
+pub fn create_curve_config_update(
+    slot: u64,
+    quote_mint: Pubkey,
+) -> (SubscribeUpdate, CurveConfigInit) {
+    let program = dbc::program_id().unwrap();
+    let keys = vec![
+        Pubkey::new_unique(), // payer
+        Pubkey::new_unique(), // config
+        Pubkey::new_unique(), // fee_claimer
+        Pubkey::new_unique(), // leftover_receiver
+        quote_mint,
+        Pubkey::new_unique(), // system_program
+        dbc::event_authority_address(&program),
+        program,
+    ];
+
+    let mut data = dbc::CREATE_CONFIG_DISCRIMINATOR.to_vec();
+    data.extend([0; 64]);
+
+    let update = transaction_update_signed_by(
+        slot,
+        &keys,
+        2,
+        CompiledInstruction {
+            program_id_index: 7,
+            // config, fee_claimer, leftover_receiver, quote_mint, payer, system_program, ...
+            accounts: vec![1, 2, 3, 4, 0, 5, 6, 7],
+            data,
+        },
+    );
+
+    let init = CurveConfigInit {
+        config: keys[1],
+        fee_claimer: keys[2],
+        quote_mint,
+        payer: keys[0],
+    };
+    (update, init)
+}
+
+/// Строит обновление Geyser с созданием кривой DBC (SPL-вариант) для `base_mint`/`quote_mint`
+///
+/// Подписывают плательщик, создатель и mint base-токена.
+pub fn init_curve_update(
+    slot: u64,
+    config: Pubkey,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+) -> (SubscribeUpdate, CurveInit) {
+    let program = dbc::program_id().unwrap();
+    let keys = vec![
+        Pubkey::new_unique(), // payer
+        Pubkey::new_unique(), // creator
+        base_mint,
+        config,
+        dbc::pool_authority_address(&program),
+        quote_mint,
+        Pubkey::new_unique(), // pool
+        Pubkey::new_unique(), // base_vault
+        Pubkey::new_unique(), // quote_vault
+        Pubkey::new_unique(), // mint_metadata
+        Pubkey::new_unique(), // metadata_program
+        spl_token::id(),
+        Pubkey::new_unique(), // system_program
+        dbc::event_authority_address(&program),
+        program,
+    ];
+
+    let mut data = dbc::INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR.to_vec();
+    data.extend([0; 12]); // name, symbol, uri: пустые строки
+
+    // config, pool_authority, creator, base_mint, quote_mint, pool, vaults, metadata, payer, ...
+    let accounts = vec![3, 4, 1, 2, 5, 6, 7, 8, 9, 10, 0, 11, 11, 12, 13, 14];
+    let update = transaction_update_signed_by(
+        slot,
+        &keys,
+        3,
+        CompiledInstruction {
+            program_id_index: 14,
+            accounts,
+            data,
+        },
+    );
+
+    let init = CurveInit {
+        pool: keys[6],
+        config,
+        creator: keys[1],
+        base_mint,
+        quote_mint,
+        base_vault: keys[7],
+        quote_vault: keys[8],
+        pool_authority: keys[4],
+        base_token_program: spl_token::id(),
+        quote_token_program: spl_token::id(),
+    };
+    (update, init)
+}
+
+/// Строит обновление Geyser с миграцией кривой DBC в новый пул DAMM v2
+///
+/// Подписывают плательщик и mint-ы NFT обеих позиций.
+pub fn migration_damm_v2_update(
+    slot: u64,
+    base_mint: Pubkey,
+    quote_mint: Pubkey,
+) -> (SubscribeUpdate, MigrationInit) {
+    let program = dbc::program_id().unwrap();
+    let damm_program = damm_v2::program_id().unwrap();
+    let mut keys = vec![
+        Pubkey::new_unique(), // payer
+        Pubkey::new_unique(), // first_position_nft_mint
+        Pubkey::new_unique(), // second_position_nft_mint
+    ];
+    keys.extend([
+        Pubkey::new_unique(), // virtual_pool
+        Pubkey::new_unique(), // migration_metadata
+        Pubkey::new_unique(), // config
+        dbc::pool_authority_address(&program),
+        Pubkey::new_unique(), // pool
+        Pubkey::new_unique(), // first_position_nft_account
+        Pubkey::new_unique(), // first_position
+        Pubkey::new_unique(), // second_position_nft_account
+        Pubkey::new_unique(), // second_position
+        damm_v2::pool_authority_address(&damm_program),
+        damm_program,
+        base_mint,
+        quote_mint,
+        Pubkey::new_unique(), // token_a_vault
+        Pubkey::new_unique(), // token_b_vault
+        Pubkey::new_unique(), // base_vault
+        Pubkey::new_unique(), // quote_vault
+        spl_token::id(),
+        Pubkey::new_unique(), // token_2022_program
+        damm_v2::event_authority_address(&damm_program),
+        Pubkey::new_unique(), // system_program
+        program,
+    ]);
+
+    // virtual_pool, migration_metadata, config, pool_authority, pool, first position, ...
+    let accounts = vec![
+        3, 4, 5, 6, 7, 1, 8, 9, 2, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 0, 20, 20, 21, 22, 23,
+    ];
+    let update = transaction_update_signed_by(
+        slot,
+        &keys,
+        3,
+        CompiledInstruction {
+            program_id_index: 24,
+            accounts,
+            data: dbc::MIGRATION_DAMM_V2_DISCRIMINATOR.to_vec(),
+        },
+    );
+
+    let init = MigrationInit {
+        curve: keys[3],
+        config: keys[5],
+        pool: keys[7],
+        pool_authority: keys[12],
+        base_mint,
+        quote_mint,
+        token_a_vault: keys[16],
+        token_b_vault: keys[17],
+        payer: keys[0],
+        base_token_program: spl_token::id(),
+        quote_token_program: spl_token::id(),
+    };
+    (update, init)
+}
+
+/// Init-транзакция пула WSOL/новый токен
+pub fn wsol_pool_update(slot: u64) -> (SubscribeUpdate, FixturePool) {
+    let target_mint = Pubkey::new_unique();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, pool, authority) = init_pool_update_with(slot, wsol, target_mint);
+
+    let signature = match &update.update_oneof {
+        Some(UpdateOneof::Transaction(tx)) => {
+            Signature::try_from(tx.transaction.as_ref().unwrap().signature.as_slice()).unwrap()
+        }
+        _ => unreachable!(),
+    };
+
+    (
+        update,
+        FixturePool {
+            pool,
+            authority,
+            target_mint,
+            signature,
+        },
+    )
+}
+
+/// Init-транзакция пула без WSOL
+pub fn non_wsol_pool_update(slot: u64) -> (SubscribeUpdate, Pubkey) {
+    let (update, pool, _) = init_pool_update_with(slot, Pubkey::new_unique(), Pubkey::new_unique());
+    (update, pool)
+}
+
+/// Добавляет в транзакцию meta с post token balances хранилищ пула
+pub fn with_pool_reserves(
+    mut update: SubscribeUpdate,
+    fixture: &FixturePool,
+    reserve_sol: u64,
+    reserve_token: u64,
+) -> SubscribeUpdate {
+    let balance = |index: u32, mint: String, amount: u64| TokenBalance {
+        account_index: index,
+        mint,
+        ui_token_amount: Some(UiTokenAmount {
+            amount: amount.to_string(),
+            ..Default::default()
+        }),
+        owner: fixture.authority.to_string(),
+        program_id: spl_token::id().to_string(),
+    };
+
+    if let Some(UpdateOneof::Transaction(tx)) = &mut update.update_oneof {
+        if let Some(info) = &mut tx.transaction {
+            info.meta = Some(TransactionStatusMeta {
+                post_token_balances: vec![
+                    balance(3, WSOL_MINT.to_string(), reserve_sol),
+                    balance(4, fixture.target_mint.to_string(), reserve_token),
+                ],
+                ..Default::default()
+            });
+        }
+    }
+    update
+}
+
+/// Данные init-инструкции пула байт в байт: код операции `2`,
+/// token_a_amount = 5 SOL и token_b_amount = 1 000 000 (u64 little-endian)
+pub const INIT_DATA_5_SOL_1M_TOKENS: [u8; 17] = [
+    0x02, // initialize
+    0x00, 0xf2, 0x05, 0x2a, 0x01, 0x00, 0x00, 0x00, // 5 000 000 000
+    0x40, 0x42, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, // 1 000 000
+];
+
+/// Записывает в аргументы init-инструкции внесённые количества token_a и token_b
+pub fn with_init_amounts(update: SubscribeUpdate, token_a: u64, token_b: u64) -> SubscribeUpdate {
+    let mut data = vec![2];
+    data.extend(token_a.to_le_bytes());
+    data.extend(token_b.to_le_bytes());
+    with_init_data(update, data)
+}
+
+/// Заменяет данные init-инструкции
+pub fn with_init_data(mut update: SubscribeUpdate, data: Vec<u8>) -> SubscribeUpdate {
+    if let Some(UpdateOneof::Transaction(tx)) = &mut update.update_oneof {
+        let message = tx
+            .transaction
+            .as_mut()
+            .and_then(|info| info.transaction.as_mut())
+            .and_then(|tx| tx.message.as_mut());
+        if let Some(message) = message {
+            message.instructions[0].data = data;
+        }
+    }
+    update
+}
+
+/// Добавляет ещё один аккаунт хранилища пула с балансом `amount` токена `mint`
+pub fn with_extra_pool_balance(
+    mut update: SubscribeUpdate,
+    fixture: &FixturePool,
+    mint: Pubkey,
+    amount: u64,
+) -> SubscribeUpdate {
+    if let Some(UpdateOneof::Transaction(tx)) = &mut update.update_oneof {
+        if let Some(meta) = tx.transaction.as_mut().and_then(|info| info.meta.as_mut()) {
+            meta.post_token_balances.push(TokenBalance {
+                account_index: 7,
+                mint: mint.to_string(),
+                ui_token_amount: Some(UiTokenAmount {
+                    amount: amount.to_string(),
+                    ..Default::default()
+                }),
+                owner: fixture.authority.to_string(),
+                program_id: spl_token::id().to_string(),
+            });
+        }
+    }
+    update
+}
+
+/// Произвольная транзакция (например, swap), после которой у пула такие резервы
+pub fn pool_activity_update(
+    slot: u64,
+    fixture: &FixturePool,
+    reserve_sol: u64,
+    reserve_token: u64,
+) -> SubscribeUpdate {
+    let signature = Signature::new_unique();
+    let update = SubscribeUpdate {
+        filters: vec![],
+        update_oneof: Some(UpdateOneof::Transaction(SubscribeUpdateTransaction {
+            transaction: Some(SubscribeUpdateTransactionInfo {
+                signature: signature.as_ref().to_vec(),
+                is_vote: false,
+                transaction: None,
+                meta: None,
+                index: 0,
+            }),
+            slot,
+        })),
+        created_at: None,
+    };
+    with_pool_reserves(update, fixture, reserve_sol, reserve_token)
+}
+
+pub fn ping_update() -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec![],
+        update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+        created_at: None,
+    }
+}
+
+/// Block-meta обновление с заданным blockhash и высотой блока
+pub fn block_meta_update(slot: u64, blockhash: Hash, block_height: u64) -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec!["blockhash".to_string()],
+        update_oneof: Some(UpdateOneof::BlockMeta(SubscribeUpdateBlockMeta {
+            slot,
+            blockhash: blockhash.to_string(),
+            block_height: Some(BlockHeight { block_height }),
+            ..Default::default()
+        })),
+        created_at: None,
+    }
+}
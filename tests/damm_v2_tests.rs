@@ -0,0 +1,343 @@
+mod common;
+
+use std::str::FromStr;
+
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    sysvar,
+};
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::damm_v2::{
+    self, ActivationType, BaseFee, CollectFeeMode, DynamicFee, FeeSchedulerMode, Pool,
+    PoolInitDammV2,
+};
+use meteora_sniper_bot::filters::FilterLists;
+use meteora_sniper_bot::geyser::{decide, detect_pool_inits, Decision, PoolLaunch};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::sizing::BuySizing;
+use meteora_sniper_bot::store::Store;
+use meteora_sniper_bot::swap::{
+    associated_token_address, build_swap_transaction, swap_instructions, PoolProgram, SwapAccounts,
+};
+
+/// Корень цены 1.0 в Q64.64
+const SQRT_PRICE_ONE: u128 = 1 << 64;
+
+fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Пул по цене 1.0 с ликвидностью 1e6, комиссией 1% без расписания и активацией по слоту
+fn pool_state(init: &PoolInitDammV2, activation_point: u64) -> Pool {
+    Pool {
+        base_fee: BaseFee {
+            cliff_fee_numerator: 10_000_000,
+            fee_scheduler_mode: FeeSchedulerMode::Linear,
+            number_of_period: 0,
+            period_frequency: 0,
+            reduction_factor: 0,
+        },
+        dynamic_fee: DynamicFee {
+            initialized: false,
+            max_volatility_accumulator: 0,
+            variable_fee_control: 0,
+            bin_step: 0,
+            volatility_accumulator: 0,
+        },
+        token_a_mint: init.token_a_mint,
+        token_b_mint: init.token_b_mint,
+        token_a_vault: init.token_a_vault,
+        token_b_vault: init.token_b_vault,
+        liquidity: 1_000_000 << 64,
+        sqrt_min_price: 1 << 60,
+        sqrt_max_price: 1 << 68,
+        sqrt_price: SQRT_PRICE_ONE,
+        activation_point,
+        activation_type: ActivationType::Slot,
+        pool_status: 0,
+        token_a_flag: 0,
+        token_b_flag: 0,
+        collect_fee_mode: CollectFeeMode::BothToken,
+        creator: init.payer,
+    }
+}
+
+/// Пул и `Clock` на слоте `slot` в mock RPC
+fn mock_pool(rpc: &MockRpc, init: &PoolInitDammV2, state: &Pool, slot: u64) {
+    let program = damm_v2::program_id().unwrap();
+    rpc.set_account(init.pool, account(program, state.pack()));
+    let clock = Clock {
+        slot,
+        ..Clock::default()
+    };
+    rpc.set_account(
+        sysvar::clock::id(),
+        account(sysvar::id(), bincode::serialize(&clock).unwrap()),
+    );
+}
+
+/// Обнаруживает пул TOKEN/WSOL и принимает по нему решение о покупке
+fn detect_wsol_pool() -> (PoolInitDammV2, PoolLaunch) {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, init) = common::init_damm_v2_pool_update(3, Pubkey::new_unique(), wsol);
+
+    let mut inits = detect_pool_inits(&update, &meteora);
+    assert_eq!(inits.len(), 1);
+    let detected = inits.remove(0);
+    assert_eq!(detected.accounts, init.swap_accounts());
+
+    let store = Store::open_in_memory(16).unwrap();
+    let decision = decide(
+        &detected,
+        &wsol,
+        &FilterLists::default(),
+        &BuySizing::fixed(1_000),
+        &store,
+    )
+    .unwrap();
+    let Decision::Buy(launch) = decision else {
+        panic!("Пул DAMM v2 с WSOL должен покупаться: {:?}", decision);
+    };
+    (init, *launch)
+}
+
+#[test]
+fn test_discriminators_match_anchor_names() {
+    common::assert_anchor_discriminators(&[
+        (
+            damm_v2::INITIALIZE_POOL_DISCRIMINATOR,
+            "global:initialize_pool",
+        ),
+        (
+            damm_v2::INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_DISCRIMINATOR,
+            "global:initialize_pool_with_dynamic_config",
+        ),
+        (
+            damm_v2::INITIALIZE_CUSTOMIZABLE_POOL_DISCRIMINATOR,
+            "global:initialize_customizable_pool",
+        ),
+        (damm_v2::SWAP_DISCRIMINATOR, "global:swap"),
+        (damm_v2::POOL_DISCRIMINATOR, "account:Pool"),
+    ]);
+}
+
+#[test]
+fn test_pool_layout_roundtrip() {
+    let (_, init) = common::init_damm_v2_pool_update(1, Pubkey::new_unique(), Pubkey::new_unique());
+    let mut state = pool_state(&init, 42);
+    state.base_fee.fee_scheduler_mode = FeeSchedulerMode::Exponential;
+    state.base_fee.number_of_period = 12;
+    state.dynamic_fee.initialized = true;
+    state.dynamic_fee.volatility_accumulator = u128::MAX / 3;
+    state.activation_type = ActivationType::Timestamp;
+    state.token_b_flag = 1;
+    state.collect_fee_mode = CollectFeeMode::OnlyB;
+    let data = state.pack();
+
+    assert_eq!(data.len(), damm_v2::POOL_LEN);
+    assert_eq!(Pool::unpack(&data).unwrap(), state);
+    assert!(Pool::unpack(&data[..500]).is_err());
+    assert!(Pool::unpack(&[0; damm_v2::POOL_LEN]).is_err());
+}
+
+#[test]
+fn test_fee_scheduler_and_dynamic_fee() {
+    let mut fee = BaseFee {
+        cliff_fee_numerator: 10_000_000,
+        fee_scheduler_mode: FeeSchedulerMode::Linear,
+        number_of_period: 5,
+        period_frequency: 10,
+        reduction_factor: 1_000_000,
+    };
+    assert_eq!(fee.fee_numerator(100, 100), 10_000_000);
+    assert_eq!(fee.fee_numerator(125, 100), 8_000_000);
+    // После number_of_period комиссия больше не снижается
+    assert_eq!(fee.fee_numerator(1_000, 100), 5_000_000);
+
+    // Экспоненциально: −10% за период
+    fee.fee_scheduler_mode = FeeSchedulerMode::Exponential;
+    fee.reduction_factor = 1_000;
+    assert_eq!(fee.fee_numerator(130, 100), 7_290_000);
+
+    let dynamic = DynamicFee {
+        initialized: true,
+        max_volatility_accumulator: 14_460_000,
+        variable_fee_control: 1_000,
+        bin_step: 100,
+        volatility_accumulator: 10_000,
+    };
+    assert_eq!(dynamic.fee_numerator(), 10_000);
+    assert_eq!(
+        DynamicFee {
+            initialized: false,
+            ..dynamic
+        }
+        .fee_numerator(),
+        0
+    );
+}
+
+#[test]
+fn test_quote_applies_fee_by_collect_mode() {
+    let (_, init) = common::init_damm_v2_pool_update(1, Pubkey::new_unique(), Pubkey::new_unique());
+    let mut state = pool_state(&init, 0);
+
+    // A → B: комиссия 1% удерживается с выхода
+    let quote = state.quote(1_000, true, 0).unwrap();
+    assert_eq!(quote.fee_numerator, 10_000_000);
+    assert_eq!((quote.amount_out, quote.fee), (989, 10));
+
+    // OnlyB при B → A: комиссия со входа
+    state.collect_fee_mode = CollectFeeMode::OnlyB;
+    let quote = state.quote(1_000, false, 0).unwrap();
+    assert_eq!((quote.amount_out, quote.fee), (989, 10));
+
+    // Суммарная комиссия ограничена 50%
+    state.base_fee.cliff_fee_numerator = 900_000_000;
+    assert_eq!(state.fee_numerator(0), damm_v2::MAX_FEE_NUMERATOR);
+
+    // Выход за диапазон цен — нехватка ликвидности
+    assert!(state.quote(u64::MAX / 2, true, 0).is_err());
+}
+
+#[test]
+fn test_quote_rejects_inactive_pool() {
+    let (_, init) = common::init_damm_v2_pool_update(1, Pubkey::new_unique(), Pubkey::new_unique());
+    let mut state = pool_state(&init, 500);
+
+    let err = state.quote(1_000, true, 499).unwrap_err();
+    assert!(err.to_string().contains("активируется в 500"), "{:#}", err);
+    assert!(state.quote(1_000, true, 500).is_ok());
+
+    state.pool_status = 1;
+    assert!(state.quote(1_000, true, 500).is_err());
+}
+
+#[test]
+fn test_detected_pool_buys_from_wsol_vault() {
+    let (init, launch) = detect_wsol_pool();
+
+    assert_eq!(launch.target_mint, init.token_a_mint);
+    // Создатель — плательщик, а не первый подписант инструкции (mint NFT позиции)
+    assert_eq!(launch.creator, init.payer);
+    assert_eq!(launch.accounts.program, PoolProgram::DammV2);
+    assert_eq!(launch.accounts.pool, init.pool);
+    assert_eq!(
+        launch.accounts.pool_authority,
+        damm_v2::pool_authority_address(&damm_v2::program_id().unwrap())
+    );
+    // WSOL — токен B, поэтому покупка идёт из хранилища B в хранилище A
+    assert_eq!(launch.accounts.pool_source, init.token_b_vault);
+    assert_eq!(launch.accounts.pool_destination, init.token_a_vault);
+}
+
+#[tokio::test]
+async fn test_swap_instruction_account_layout() {
+    let (init, launch) = detect_wsol_pool();
+
+    let rpc = MockRpc::start().await;
+    mock_pool(&rpc, &init, &pool_state(&init, 10), 10);
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let payer = Keypair::new();
+    let wsol_account = Pubkey::new_unique();
+    let accounts = SwapAccounts {
+        user_source: wsol_account,
+        ..launch.accounts
+    };
+    let ixs = swap_instructions(&pool, &payer.pubkey(), &accounts, 1_000, 7)
+        .await
+        .unwrap();
+    assert_eq!(ixs.len(), 2, "Сначала создаётся ATA покупаемого токена");
+
+    let target_ata =
+        associated_token_address(&payer.pubkey(), &init.token_a_mint, &spl_token::id()).unwrap();
+    assert_eq!(ixs[0].accounts[1].pubkey, target_ata);
+
+    let program = damm_v2::program_id().unwrap();
+    let swap = &ixs[1];
+    assert_eq!(swap.program_id, program);
+
+    let mut data = damm_v2::SWAP_DISCRIMINATOR.to_vec();
+    data.extend(1_000u64.to_le_bytes());
+    data.extend(7u64.to_le_bytes());
+    assert_eq!(swap.data, data);
+
+    let keys: Vec<Pubkey> = swap.accounts.iter().map(|a| a.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            init.pool_authority,
+            init.pool,
+            wsol_account,
+            target_ata,
+            init.token_a_vault,
+            init.token_b_vault,
+            init.token_a_mint,
+            init.token_b_mint,
+            payer.pubkey(),
+            spl_token::id(),
+            spl_token::id(),
+            program, // referral_token_account
+            damm_v2::event_authority_address(&program),
+            program,
+        ]
+    );
+    assert!(swap.accounts[8].is_signer);
+    assert!(swap.accounts[1..6].iter().all(|a| a.is_writable));
+}
+
+#[tokio::test]
+async fn test_swap_fails_before_activation() {
+    let (init, launch) = detect_wsol_pool();
+
+    let rpc = MockRpc::start().await;
+    mock_pool(&rpc, &init, &pool_state(&init, 20), 10);
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let err = swap_instructions(&pool, &Pubkey::new_unique(), &launch.accounts, 1_000, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("активируется"), "{:#}", err);
+
+    // Без RPC свап DAMM v2 не собрать
+    let offline = build_swap_transaction(
+        &Keypair::new(),
+        &launch.accounts,
+        1_000,
+        1,
+        0,
+        Hash::default(),
+    )
+    .await;
+    assert!(offline.is_err());
+}
+
+#[test]
+fn test_store_keeps_damm_v2_program() {
+    let (init, launch) = detect_wsol_pool();
+    let store = Store::open_in_memory(16).unwrap();
+
+    store
+        .record_pool_accounts(&launch.accounts, &init.token_a_mint)
+        .unwrap();
+    let stored = store
+        .pool_accounts_for_mint(&init.token_a_mint)
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, launch.accounts);
+    assert_eq!(stored.reversed().program, PoolProgram::DammV2);
+}
@@ -0,0 +1,311 @@
+mod common;
+
+use std::str::FromStr;
+
+use solana_sdk::{
+    account::Account,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::damm_v2;
+use meteora_sniper_bot::dbc::{self, CurveInit, VirtualPool};
+use meteora_sniper_bot::filters::FilterLists;
+use meteora_sniper_bot::geyser::{
+    decide, detect_curve_configs, detect_pool_inits, Decision, LaunchPhase, PoolLaunch,
+};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::sizing::{BuySizing, PhaseRejection, PhaseSizing};
+use meteora_sniper_bot::store::Store;
+use meteora_sniper_bot::swap::{associated_token_address, swap_instructions, PoolProgram};
+
+fn account(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Свежая кривая: весь base в хранилище, quote ещё нет
+fn curve_state(init: &CurveInit) -> VirtualPool {
+    VirtualPool {
+        config: init.config,
+        creator: init.creator,
+        base_mint: init.base_mint,
+        base_vault: init.base_vault,
+        quote_vault: init.quote_vault,
+        base_reserve: 1_000_000_000,
+        quote_reserve: 0,
+        sqrt_price: 1 << 60,
+        activation_point: 0,
+        pool_type: 0,
+        is_migrated: false,
+    }
+}
+
+/// Кривая и её хранилища в mock RPC: у токен-аккаунта mint — первые 32 байта
+fn mock_curve(rpc: &MockRpc, init: &CurveInit, state: &VirtualPool) {
+    rpc.set_account(init.pool, account(dbc::program_id().unwrap(), state.pack()));
+    for (vault, mint) in [
+        (init.base_vault, init.base_mint),
+        (init.quote_vault, init.quote_mint),
+    ] {
+        let mut data = mint.to_bytes().to_vec();
+        data.resize(spl_token::state::Account::LEN, 0);
+        rpc.set_account(vault, account(spl_token::id(), data));
+    }
+}
+
+/// Первое найденное в обновлении событие и решение по нему
+fn detect_and_decide(update: &SubscribeUpdate) -> Decision {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let mut inits = detect_pool_inits(update, &meteora);
+    assert_eq!(inits.len(), 1);
+
+    let store = Store::open_in_memory(16).unwrap();
+    decide(
+        &inits.remove(0),
+        &wsol,
+        &FilterLists::default(),
+        &BuySizing::fixed(1_000),
+        &store,
+    )
+    .unwrap()
+}
+
+/// Обнаруживает кривую TOKEN/WSOL и принимает по ней решение о покупке
+fn detect_wsol_curve() -> (CurveInit, PoolLaunch) {
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, init) =
+        common::init_curve_update(5, Pubkey::new_unique(), Pubkey::new_unique(), wsol);
+    let decision = detect_and_decide(&update);
+    let Decision::Buy(launch) = decision else {
+        panic!("Кривая DBC с WSOL должна покупаться: {:?}", decision);
+    };
+    (init, *launch)
+}
+
+#[test]
+fn test_discriminators_match_anchor_names() {
+    common::assert_anchor_discriminators(&[
+        (dbc::CREATE_CONFIG_DISCRIMINATOR, "global:create_config"),
+        (
+            dbc::INITIALIZE_VIRTUAL_POOL_WITH_SPL_TOKEN_DISCRIMINATOR,
+            "global:initialize_virtual_pool_with_spl_token",
+        ),
+        (
+            dbc::INITIALIZE_VIRTUAL_POOL_WITH_TOKEN2022_DISCRIMINATOR,
+            "global:initialize_virtual_pool_with_token2022",
+        ),
+        (
+            dbc::MIGRATION_DAMM_V2_DISCRIMINATOR,
+            "global:migration_damm_v2",
+        ),
+        (dbc::SWAP_DISCRIMINATOR, "global:swap"),
+        (dbc::VIRTUAL_POOL_DISCRIMINATOR, "account:VirtualPool"),
+    ]);
+}
+
+#[test]
+fn test_virtual_pool_layout_roundtrip() {
+    let (_, init) = common::init_curve_update(
+        1,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let mut state = curve_state(&init);
+    state.quote_reserve = 42;
+    state.sqrt_price = u128::MAX / 3;
+    state.pool_type = 1;
+    state.is_migrated = true;
+    let data = state.pack();
+
+    assert_eq!(data.len(), dbc::VIRTUAL_POOL_LEN);
+    assert_eq!(VirtualPool::unpack(&data).unwrap(), state);
+    assert!(VirtualPool::unpack(&data[..300]).is_err());
+    assert!(VirtualPool::unpack(&[0; dbc::VIRTUAL_POOL_LEN]).is_err());
+}
+
+#[test]
+fn test_detected_curve_buys_from_quote_vault() {
+    let (init, launch) = detect_wsol_curve();
+
+    assert_eq!(launch.target_mint, init.base_mint);
+    assert_eq!(launch.creator, init.creator);
+    assert_eq!(
+        launch.phase,
+        LaunchPhase::Curve {
+            config: init.config
+        }
+    );
+    assert_eq!(launch.phase.strategy(), "curve");
+    assert_eq!(launch.accounts.program, PoolProgram::Dbc);
+    assert_eq!(launch.accounts.pool, init.pool);
+    // WSOL — quote, поэтому покупка идёт из хранилища quote в хранилище base
+    assert_eq!(launch.accounts.pool_source, init.quote_vault);
+    assert_eq!(launch.accounts.pool_destination, init.base_vault);
+}
+
+#[test]
+fn test_detected_migration_buys_on_damm_v2() {
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, init) = common::migration_damm_v2_update(9, Pubkey::new_unique(), wsol);
+    let decision = detect_and_decide(&update);
+    let Decision::Buy(launch) = decision else {
+        panic!("Миграция в пул с WSOL должна покупаться: {:?}", decision);
+    };
+
+    assert_eq!(launch.target_mint, init.base_mint);
+    assert_eq!(launch.creator, init.payer);
+    assert_eq!(launch.phase, LaunchPhase::Migration { curve: init.curve });
+    assert_eq!(launch.phase.strategy(), "migration");
+    assert_eq!(launch.accounts.program, PoolProgram::DammV2);
+    assert_eq!(launch.accounts.pool, init.pool);
+    assert_eq!(
+        launch.accounts.pool_authority,
+        damm_v2::pool_authority_address(&damm_v2::program_id().unwrap())
+    );
+    assert_eq!(launch.accounts.pool_source, init.token_b_vault);
+    assert_eq!(launch.accounts.pool_destination, init.token_a_vault);
+}
+
+#[test]
+fn test_detect_curve_configs() {
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, init) = common::create_curve_config_update(2, wsol);
+    assert_eq!(detect_curve_configs(&update), vec![init]);
+    // Создание конфига — не запуск, покупать нечего
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    assert!(detect_pool_inits(&update, &meteora).is_empty());
+
+    let (curve_update, _) = common::init_curve_update(3, init.config, Pubkey::new_unique(), wsol);
+    assert!(detect_curve_configs(&curve_update).is_empty());
+}
+
+#[test]
+fn test_phase_sizing_rejections() {
+    let config = Pubkey::new_unique();
+    let curve = LaunchPhase::Curve { config };
+    let migration = LaunchPhase::Migration {
+        curve: Pubkey::new_unique(),
+    };
+
+    let mut phases = PhaseSizing::uniform(BuySizing::fixed(1_000));
+    assert_eq!(phases.for_phase(&curve), Ok(BuySizing::fixed(1_000)));
+    assert_eq!(phases.for_phase(&migration), Ok(BuySizing::fixed(1_000)));
+
+    phases.curve_configs = vec![Pubkey::new_unique()];
+    assert_eq!(
+        phases.for_phase(&curve),
+        Err(PhaseRejection::CurveConfig(config))
+    );
+    phases.curve_configs.push(config);
+    assert!(phases.for_phase(&curve).is_ok());
+
+    phases.curve = None;
+    phases.migration = None;
+    assert_eq!(phases.for_phase(&curve), Err(PhaseRejection::CurveDisabled));
+    assert_eq!(
+        phases.for_phase(&migration),
+        Err(PhaseRejection::MigrationDisabled)
+    );
+    // Обычные пулы от фаз DBC не зависят
+    assert!(phases.for_phase(&LaunchPhase::Pool).is_ok());
+}
+
+#[tokio::test]
+async fn test_swap_instruction_account_layout() {
+    let (init, launch) = detect_wsol_curve();
+
+    let rpc = MockRpc::start().await;
+    mock_curve(&rpc, &init, &curve_state(&init));
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let payer = Keypair::new();
+    let ixs = swap_instructions(&pool, &payer.pubkey(), &launch.accounts, 1_000, 7)
+        .await
+        .unwrap();
+    assert_eq!(ixs.len(), 2, "Сначала создаётся ATA покупаемого токена");
+
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let wsol_ata = associated_token_address(&payer.pubkey(), &wsol, &spl_token::id()).unwrap();
+    let target_ata =
+        associated_token_address(&payer.pubkey(), &init.base_mint, &spl_token::id()).unwrap();
+    assert_eq!(ixs[0].accounts[1].pubkey, target_ata);
+
+    let program = dbc::program_id().unwrap();
+    let swap = &ixs[1];
+    assert_eq!(swap.program_id, program);
+
+    let mut data = dbc::SWAP_DISCRIMINATOR.to_vec();
+    data.extend(1_000u64.to_le_bytes());
+    data.extend(7u64.to_le_bytes());
+    assert_eq!(swap.data, data);
+
+    let keys: Vec<Pubkey> = swap.accounts.iter().map(|a| a.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            init.pool_authority,
+            init.config,
+            init.pool,
+            wsol_ata,
+            target_ata,
+            init.base_vault,
+            init.quote_vault,
+            init.base_mint,
+            wsol,
+            payer.pubkey(),
+            spl_token::id(),
+            spl_token::id(),
+            program, // referral_token_account
+            dbc::event_authority_address(&program),
+            program,
+        ]
+    );
+    assert!(swap.accounts[9].is_signer);
+    assert!(swap.accounts[2..7].iter().all(|a| a.is_writable));
+}
+
+#[tokio::test]
+async fn test_swap_fails_on_migrated_curve() {
+    let (init, launch) = detect_wsol_curve();
+
+    let rpc = MockRpc::start().await;
+    let state = VirtualPool {
+        is_migrated: true,
+        ..curve_state(&init)
+    };
+    mock_curve(&rpc, &init, &state);
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let err = swap_instructions(&pool, &Pubkey::new_unique(), &launch.accounts, 1_000, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("мигрировала"), "{:#}", err);
+}
+
+#[test]
+fn test_store_keeps_dbc_program() {
+    let (init, launch) = detect_wsol_curve();
+    let store = Store::open_in_memory(16).unwrap();
+
+    store
+        .record_pool_accounts(&launch.accounts, &init.base_mint)
+        .unwrap();
+    let stored = store
+        .pool_accounts_for_mint(&init.base_mint)
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, launch.accounts);
+    assert_eq!(stored.reversed().program, PoolProgram::Dbc);
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use std::str::FromStr;
+
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::dlmm::{
+    self, bin_array_address, bin_array_index, swap_bin_array_indexes, LbPair, LbPairInit,
+};
+use meteora_sniper_bot::filters::FilterLists;
+use meteora_sniper_bot::geyser::{decide, detect_pool_inits, Decision};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::sizing::BuySizing;
+use meteora_sniper_bot::store::Store;
+use meteora_sniper_bot::swap::{
+    associated_token_address, build_swap_transaction, swap_instructions, PoolProgram, SwapAccounts,
+};
+
+fn owned_by(owner: Pubkey, data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Lb pair в mock RPC: сам пул, mint'ы и bin array с заданными индексами
+fn mock_lb_pair(rpc: &MockRpc, init: &LbPairInit, active_id: i32, bin_arrays: &[i64]) {
+    let program = dlmm::program_id().unwrap();
+    let state = LbPair {
+        active_id,
+        bin_step: init.bin_step,
+        token_x_mint: init.token_x_mint,
+        token_y_mint: init.token_y_mint,
+        reserve_x: init.reserve_x,
+        reserve_y: init.reserve_y,
+        oracle: init.oracle,
+    };
+    rpc.set_account(init.lb_pair, owned_by(program, state.pack()));
+    rpc.set_account(init.token_x_mint, owned_by(spl_token::id(), vec![0; 82]));
+    rpc.set_account(init.token_y_mint, owned_by(spl_token::id(), vec![0; 82]));
+    for &index in bin_arrays {
+        let address = bin_array_address(&init.lb_pair, index, &program);
+        rpc.set_account(address, owned_by(program, vec![0; 64]));
+    }
+}
+
+/// Обнаруживает lb pair TOKEN/WSOL и принимает по нему решение о покупке
+fn detect_wsol_lb_pair(active_id: i32) -> (LbPairInit, Decision) {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (update, init) = common::init_lb_pair_update(3, Pubkey::new_unique(), wsol, active_id);
+
+    let mut inits = detect_pool_inits(&update, &meteora);
+    assert_eq!(inits.len(), 1);
+    let detected = inits.remove(0);
+    assert_eq!(detected.accounts, init.swap_accounts());
+
+    let store = Store::open_in_memory(16).unwrap();
+    let decision = decide(
+        &detected,
+        &wsol,
+        &FilterLists::default(),
+        &BuySizing::fixed(1_000),
+        &store,
+    )
+    .unwrap();
+    (init, decision)
+}
+
+#[test]
+fn test_discriminators_match_anchor_names() {
+    common::assert_anchor_discriminators(&[
+        (
+            dlmm::INITIALIZE_LB_PAIR_DISCRIMINATOR,
+            "global:initialize_lb_pair",
+        ),
+        (dlmm::SWAP_DISCRIMINATOR, "global:swap"),
+        (dlmm::LB_PAIR_DISCRIMINATOR, "account:LbPair"),
+    ]);
+}
+
+#[test]
+fn test_bin_array_index_rounds_down() {
+    assert_eq!(bin_array_index(0), 0);
+    assert_eq!(bin_array_index(69), 0);
+    assert_eq!(bin_array_index(70), 1);
+    assert_eq!(bin_array_index(-1), -1);
+    assert_eq!(bin_array_index(-70), -1);
+    assert_eq!(bin_array_index(-71), -2);
+
+    // X → Y двигает цену вниз, Y → X — вверх
+    assert_eq!(swap_bin_array_indexes(-1, true, 3), vec![-1, -2, -3]);
+    assert_eq!(swap_bin_array_indexes(69, false, 3), vec![0, 1, 2]);
+}
+
+#[test]
+fn test_lb_pair_layout_roundtrip() {
+    let state = LbPair {
+        active_id: -1234,
+        bin_step: 80,
+        token_x_mint: Pubkey::new_unique(),
+        token_y_mint: Pubkey::new_unique(),
+        reserve_x: Pubkey::new_unique(),
+        reserve_y: Pubkey::new_unique(),
+        oracle: Pubkey::new_unique(),
+    };
+    let data = state.pack();
+
+    assert_eq!(data.len(), dlmm::LB_PAIR_LEN);
+    assert_eq!(LbPair::unpack(&data).unwrap(), state);
+    assert!(LbPair::unpack(&data[..100]).is_err());
+    assert!(LbPair::unpack(&[0; dlmm::LB_PAIR_LEN]).is_err());
+}
+
+#[test]
+fn test_detected_lb_pair_buys_from_wsol_reserve() {
+    let (init, decision) = detect_wsol_lb_pair(10);
+
+    let Decision::Buy(launch) = decision else {
+        panic!("Lb pair с WSOL должен покупаться: {:?}", decision);
+    };
+    assert_eq!(launch.target_mint, init.token_x_mint);
+    assert_eq!(launch.creator, init.funder);
+    assert_eq!(launch.accounts.program, PoolProgram::Dlmm);
+    assert_eq!(launch.accounts.pool, init.lb_pair);
+    // WSOL — токен Y, поэтому покупка идёт из резерва Y в резерв X
+    assert_eq!(launch.accounts.pool_source, init.reserve_y);
+    assert_eq!(launch.accounts.pool_destination, init.reserve_x);
+}
+
+#[tokio::test]
+async fn test_swap_uses_initialized_bin_arrays_in_swap_direction() {
+    let (init, decision) = detect_wsol_lb_pair(10);
+    let Decision::Buy(launch) = decision else {
+        panic!("Lb pair с WSOL должен покупаться");
+    };
+
+    // Активный бин сдвинулся в массив -1; массив 1 не создан, поэтому 2 не используется
+    let rpc = MockRpc::start().await;
+    mock_lb_pair(&rpc, &init, -5, &[-1, 0, 2]);
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let payer = Keypair::new();
+    let wsol_account = Pubkey::new_unique();
+    let accounts = SwapAccounts {
+        user_source: wsol_account,
+        ..launch.accounts
+    };
+    let ixs = swap_instructions(&pool, &payer.pubkey(), &accounts, 1_000, 7)
+        .await
+        .unwrap();
+    assert_eq!(ixs.len(), 2, "Сначала создаётся ATA покупаемого токена");
+
+    let target_ata =
+        associated_token_address(&payer.pubkey(), &init.token_x_mint, &spl_token::id()).unwrap();
+    assert_eq!(ixs[0].accounts[1].pubkey, target_ata);
+
+    let program = dlmm::program_id().unwrap();
+    let swap = &ixs[1];
+    assert_eq!(swap.program_id, program);
+
+    let mut data = dlmm::SWAP_DISCRIMINATOR.to_vec();
+    data.extend(1_000u64.to_le_bytes());
+    data.extend(7u64.to_le_bytes());
+    assert_eq!(swap.data, data);
+
+    let keys: Vec<Pubkey> = swap.accounts.iter().map(|a| a.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            init.lb_pair,
+            program, // bitmap extension не создан
+            init.reserve_x,
+            init.reserve_y,
+            wsol_account,
+            target_ata,
+            init.token_x_mint,
+            init.token_y_mint,
+            init.oracle,
+            program, // host_fee_in
+            payer.pubkey(),
+            spl_token::id(),
+            spl_token::id(),
+            dlmm::event_authority_address(&program),
+            program,
+            bin_array_address(&init.lb_pair, -1, &program),
+            bin_array_address(&init.lb_pair, 0, &program),
+        ]
+    );
+    assert!(swap.accounts[10].is_signer);
+    assert!(swap.accounts[15..].iter().all(|a| a.is_writable));
+}
+
+#[tokio::test]
+async fn test_swap_fails_without_active_bin_array() {
+    let (init, decision) = detect_wsol_lb_pair(10);
+    let Decision::Buy(launch) = decision else {
+        panic!("Lb pair с WSOL должен покупаться");
+    };
+
+    let rpc = MockRpc::start().await;
+    mock_lb_pair(&rpc, &init, 10, &[1]);
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+
+    let err = swap_instructions(&pool, &Pubkey::new_unique(), &launch.accounts, 1_000, 1)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("нет bin array"), "{:#}", err);
+
+    // Без RPC свап DLMM не собрать
+    let offline = build_swap_transaction(
+        &Keypair::new(),
+        &launch.accounts,
+        1_000,
+        1,
+        0,
+        Hash::default(),
+    )
+    .await;
+    assert!(offline.is_err());
+}
+
+#[test]
+fn test_store_keeps_pool_program_for_sells() {
+    let (init, _) = detect_wsol_lb_pair(0);
+    let store = Store::open_in_memory(16).unwrap();
+
+    store
+        .record_pool_accounts(&init.swap_accounts(), &init.token_x_mint)
+        .unwrap();
+    let stored = store
+        .pool_accounts_for_mint(&init.token_x_mint)
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored, init.swap_accounts());
+    assert_eq!(stored.reversed().program, PoolProgram::Dlmm);
+}
@@ -0,0 +1,161 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use meteora_sniper_bot::executor::Executor;
+use meteora_sniper_bot::geyser::{LaunchPhase, PoolLaunch};
+use meteora_sniper_bot::swap::{PoolProgram, SwapAccounts};
+
+fn launch(pool: Pubkey) -> PoolLaunch {
+    PoolLaunch {
+        slot: 1,
+        signature: Signature::new_unique(),
+        target_mint: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        wsol_liquidity: None,
+        amount_in: 1_000,
+        accounts: SwapAccounts {
+            program: PoolProgram::Amm,
+            pool,
+            user_source: Pubkey::new_unique(),
+            user_destination: Pubkey::new_unique(),
+            pool_source: pool,
+            pool_destination: Pubkey::new_unique(),
+            pool_authority: Pubkey::new_unique(),
+            token_program: Pubkey::new_unique(),
+        },
+        phase: LaunchPhase::Pool,
+    }
+}
+
+#[tokio::test]
+async fn test_slow_buys_do_not_block_submission() {
+    let bought = Arc::new(Mutex::new(vec![]));
+    let running = Arc::new(AtomicUsize::new(0));
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let executor = {
+        let (bought, running, max_running) = (bought.clone(), running.clone(), max_running.clone());
+        Executor::spawn(2, 8, move |launch| {
+            let (bought, running, max_running) =
+                (bought.clone(), running.clone(), max_running.clone());
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                bought.lock().unwrap().push(launch.accounts.pool);
+            }
+        })
+    };
+
+    let pools: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+    let started = Instant::now();
+    for pool in &pools {
+        assert!(executor.submit(launch(*pool)).await.unwrap());
+    }
+    assert!(
+        started.elapsed() < Duration::from_millis(100),
+        "Постановка в очередь не должна ждать покупок"
+    );
+
+    executor.shutdown().await;
+
+    let mut bought = bought.lock().unwrap().clone();
+    bought.sort();
+    let mut expected = pools.clone();
+    expected.sort();
+    assert_eq!(bought, expected);
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+    assert!(started.elapsed() < Duration::from_millis(6 * 200));
+}
+
+#[tokio::test]
+async fn test_pool_in_flight_is_deduplicated() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let executor = {
+        let calls = calls.clone();
+        Executor::spawn(4, 4, move |_| {
+            let calls = calls.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+    };
+
+    let pool = Pubkey::new_unique();
+    assert!(executor.submit(launch(pool)).await.unwrap());
+    assert!(!executor.submit(launch(pool)).await.unwrap());
+    assert_eq!(executor.in_flight(), 1);
+
+    // После завершения покупки пул снова принимается (повторы отсекает хранилище)
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(executor.in_flight(), 0);
+    assert!(executor.submit(launch(pool)).await.unwrap());
+
+    executor.shutdown().await;
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_full_queue_applies_backpressure() {
+    let executor = Executor::spawn(1, 1, |_| async {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+    });
+
+    // Первый запуск уходит исполнителю, второй занимает очередь
+    assert!(executor.submit(launch(Pubkey::new_unique())).await.unwrap());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(executor.submit(launch(Pubkey::new_unique())).await.unwrap());
+
+    // Третий ждёт, пока освободится место
+    let blocked = tokio::time::timeout(
+        Duration::from_millis(100),
+        executor.submit(launch(Pubkey::new_unique())),
+    )
+    .await;
+    assert!(blocked.is_err(), "При полной очереди детектор должен ждать");
+
+    executor.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_cancelled_submit_and_panicked_buy_release_pool() {
+    let executor = Executor::spawn(1, 1, |launch| async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_ne!(launch.amount_in, 0, "Покупка без суммы");
+    });
+
+    assert!(executor.submit(launch(Pubkey::new_unique())).await.unwrap());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(executor.submit(launch(Pubkey::new_unique())).await.unwrap());
+
+    // Ожидание места в очереди отменено — пул не должен остаться принятым
+    let cancelled = Pubkey::new_unique();
+    let blocked = tokio::time::timeout(
+        Duration::from_millis(50),
+        executor.submit(launch(cancelled)),
+    )
+    .await;
+    assert!(blocked.is_err());
+    assert_eq!(executor.in_flight(), 2);
+
+    // Паника в покупке тоже снимает пул с учёта
+    let panicking = Pubkey::new_unique();
+    let mut broken = launch(panicking);
+    broken.amount_in = 0;
+    assert!(executor.submit(broken).await.unwrap());
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    assert_eq!(executor.in_flight(), 0);
+
+    assert!(executor.submit(launch(cancelled)).await.unwrap());
+    assert!(executor.submit(launch(panicking)).await.unwrap());
+    executor.shutdown().await;
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use solana_sdk::pubkey::Pubkey;
+
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::filters::{FilterKind, FilterLists, FilterRejection, LaunchFilter};
+use meteora_sniper_bot::geyser::{decide, detect_pool_inits, Decision, SkipReason};
+use meteora_sniper_bot::sizing::BuySizing;
+use meteora_sniper_bot::store::Store;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("filters_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_deny_wins_and_empty_allowlist_permits_all() {
+    let (good, bad, other) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    let deny_only = FilterLists::default().deny(FilterKind::Creator, [bad]);
+    assert!(deny_only.check(FilterKind::Creator, &good).is_ok());
+    assert_eq!(
+        deny_only.check(FilterKind::Creator, &bad),
+        Err(FilterRejection::Denied {
+            kind: FilterKind::Creator,
+            key: bad
+        })
+    );
+    // Списки одного вида не влияют на другой
+    assert!(deny_only.check(FilterKind::Mint, &bad).is_ok());
+
+    let lists = FilterLists::default()
+        .allow(FilterKind::Mint, [good, bad])
+        .deny(FilterKind::Mint, [bad]);
+    assert!(lists.check(FilterKind::Mint, &good).is_ok());
+    assert!(matches!(
+        lists.check(FilterKind::Mint, &bad),
+        Err(FilterRejection::Denied { .. })
+    ));
+    assert_eq!(
+        lists.check(FilterKind::Mint, &other),
+        Err(FilterRejection::NotAllowed {
+            kind: FilterKind::Mint,
+            key: Some(other)
+        })
+    );
+    assert!(lists.check_unknown(FilterKind::Mint).is_err());
+    assert!(lists.check_unknown(FilterKind::UpdateAuthority).is_ok());
+}
+
+#[test]
+fn test_lists_are_loaded_from_files() {
+    let dir = temp_dir("load");
+    let (mint, payer) = (Pubkey::new_unique(), Pubkey::new_unique());
+    fs::write(
+        dir.join("mint_allow.txt"),
+        format!("# токены запуска\n\n{}  # основной\n", mint),
+    )
+    .unwrap();
+    fs::write(dir.join("fee_payer_deny.txt"), format!("{}\n", payer)).unwrap();
+
+    let lists = FilterLists::load_dir(&dir).unwrap();
+
+    assert!(lists.check(FilterKind::Mint, &mint).is_ok());
+    assert!(lists.check(FilterKind::Mint, &payer).is_err());
+    assert!(lists.check(FilterKind::FeePayer, &payer).is_err());
+    assert!(!lists.is_active(FilterKind::Creator));
+    assert_eq!(
+        lists.to_string(),
+        "mint: 1/0, creator: 0/0, fee_payer: 0/1, update_authority: 0/0"
+    );
+
+    fs::write(dir.join("creator_deny.txt"), "not-a-pubkey\n").unwrap();
+    let err = FilterLists::load_dir(&dir).unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("creator_deny.txt:1"),
+        "{:#}",
+        err
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_changed_files_are_reloaded_and_bad_files_keep_old_lists() {
+    let dir = temp_dir("reload");
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let filter = LaunchFilter::from_dir(&dir).unwrap();
+
+    assert!(!filter.reload_if_changed().unwrap());
+    assert!(filter.lists().check(FilterKind::Mint, &first).is_ok());
+
+    fs::write(dir.join("mint_deny.txt"), format!("{}\n", first)).unwrap();
+    assert!(filter.reload_if_changed().unwrap());
+    assert!(filter.lists().check(FilterKind::Mint, &first).is_err());
+
+    // Ошибка в файле: остаются прежние списки, перезагрузка повторится
+    fs::write(
+        dir.join("mint_deny.txt"),
+        format!("{}\n{}\nnot-a-pubkey\n", first, second),
+    )
+    .unwrap();
+    assert!(filter.reload_if_changed().is_err());
+    assert!(filter.lists().check(FilterKind::Mint, &first).is_err());
+
+    fs::write(dir.join("mint_deny.txt"), format!("{}\n", second)).unwrap();
+    assert!(filter.reload_if_changed().unwrap());
+    assert!(filter.lists().check(FilterKind::Mint, &first).is_ok());
+    assert!(filter.lists().check(FilterKind::Mint, &second).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_filtered_launch_is_skipped_without_marking_pool() {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let store = Store::open_in_memory(16).unwrap();
+    let (update, pool) = common::wsol_pool_update(5);
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+
+    // В фикстуре подписант только плательщик комиссии, он же создатель
+    assert_eq!(init.creator, init.fee_payer);
+
+    let lists = FilterLists::default().deny(FilterKind::FeePayer, [init.fee_payer]);
+    let decision = decide(&init, &wsol, &lists, &BuySizing::fixed(1_000), &store).unwrap();
+    assert_eq!(
+        decision,
+        Decision::Skip {
+            pool: pool.pool,
+            reason: SkipReason::Filtered(FilterRejection::Denied {
+                kind: FilterKind::FeePayer,
+                key: init.fee_payer
+            })
+        }
+    );
+    assert!(!store.is_pool_seen(&pool.pool).unwrap());
+
+    let lists = FilterLists::default().allow(FilterKind::Mint, [pool.target_mint]);
+    assert!(matches!(
+        decide(&init, &wsol, &lists, &BuySizing::fixed(1_000), &store).unwrap(),
+        Decision::Buy(_)
+    ));
+}
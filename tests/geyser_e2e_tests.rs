@@ -0,0 +1,384 @@
+mod common;
+
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::{stream, StreamExt};
+use serde_json::json;
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+use yellowstone_grpc_proto::{prelude::CommitmentLevel, tonic::Status};
+
+use common::mock_geyser::{MockGeyserServer, ScriptStep};
+use common::mock_http::{MockHttpServer, MockResponse};
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::blockhash::BlockhashCache;
+use meteora_sniper_bot::bloxroute::BloxrouteRelay;
+use meteora_sniper_bot::config::METEORA_PROGRAM_ID;
+use meteora_sniper_bot::context::BotContext;
+use meteora_sniper_bot::filters::{FilterKind, FilterLists, LaunchFilter};
+use meteora_sniper_bot::geyser::{
+    monitor_liquidity_additions, reconcile_pending, run_pipeline, Decision, DecisionHandler,
+    Reconciliation,
+};
+use meteora_sniper_bot::nextblock::NextBlockRelay;
+use meteora_sniper_bot::relay::RelayRegistry;
+use meteora_sniper_bot::risk::{RiskLimits, RiskManager};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::store::Store;
+use meteora_sniper_bot::wallets::{Wallet, WalletPool};
+
+struct Harness {
+    rpc: MockRpc,
+    bloxroute: MockHttpServer,
+    nextblock: MockHttpServer,
+    ctx: BotContext,
+}
+
+async fn harness() -> Harness {
+    let rpc = MockRpc::start().await;
+    let bloxroute =
+        MockHttpServer::start(vec![MockResponse::Json(200, json!({ "signature": "s" }))]).await;
+    let nextblock = MockHttpServer::start(vec![MockResponse::Json(200, json!({}))]).await;
+
+    let ctx = BotContext {
+        rpc: Arc::new(RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap()),
+        relays: Arc::new(
+            RelayRegistry::new()
+                .with(Arc::new(BloxrouteRelay::new(bloxroute.url("/"), "b")))
+                .with(Arc::new(NextBlockRelay::new(nextblock.url("/"), "n"))),
+        ),
+        wallets: Arc::new(WalletPool::single(Wallet::new(Keypair::new()))),
+        store: Arc::new(Store::open_in_memory(16).unwrap()),
+        blockhash: Arc::new(BlockhashCache::new(Duration::from_secs(10))),
+        risk: Arc::new(RiskManager::new(RiskLimits::from_config())),
+        filter: Arc::new(LaunchFilter::allow_all()),
+        reputation: None,
+    };
+
+    Harness {
+        rpc,
+        bloxroute,
+        nextblock,
+        ctx,
+    }
+}
+
+async fn run(endpoint: String, ctx: BotContext) {
+    tokio::time::timeout(
+        Duration::from_secs(30),
+        monitor_liquidity_additions(&endpoint, ctx),
+    )
+    .await
+    .expect("Монитор должен завершиться, когда сервер закроет поток")
+    .expect("Монитор не должен падать на ошибках потока");
+}
+
+#[tokio::test]
+async fn test_detects_wsol_pool_and_sends_swap_everywhere() {
+    let h = harness().await;
+
+    let (wsol_init, pool) = common::wsol_pool_update(10);
+    let (other_init, other_pool) = common::non_wsol_pool_update(11);
+    let (late_init, late_pool) = common::wsol_pool_update(12);
+
+    let geyser = MockGeyserServer::start(vec![
+        ScriptStep::Update(common::ping_update()),
+        ScriptStep::Update(wsol_init.clone()),
+        ScriptStep::Update(other_init),
+        ScriptStep::Update(wsol_init),
+        ScriptStep::Sleep(Duration::from_millis(50)),
+        ScriptStep::Error(Status::unavailable("node restarting")),
+        ScriptStep::Update(late_init),
+    ])
+    .await;
+
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    let requests = geyser.subscribe_requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].commitment,
+        Some(CommitmentLevel::Processed as i32)
+    );
+    assert!(requests[0].blocks_meta.contains_key("blockhash"));
+
+    // Обнаружен только пул с WSOL, до ошибки потока
+    assert!(h.ctx.store.is_pool_seen(&pool.pool).unwrap());
+    assert!(!h.ctx.store.is_pool_seen(&other_pool).unwrap());
+    assert!(!h.ctx.store.is_pool_seen(&late_pool.pool).unwrap());
+
+    // Ровно один swap, одинаковый во всех каналах
+    let sent = h.rpc.sent_transactions();
+    assert_eq!(
+        sent.len(),
+        1,
+        "Повтор пула не должен вызывать вторую покупку"
+    );
+    let swap = &sent[0];
+
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let keys = swap.message.static_account_keys();
+    let swap_ix = swap
+        .message
+        .instructions()
+        .iter()
+        .find(|ix| keys[ix.program_id_index as usize] == meteora)
+        .expect("В транзакции должна быть swap-инструкция Meteora");
+    assert!(swap_ix
+        .accounts
+        .iter()
+        .any(|&i| keys[i as usize] == pool.pool));
+
+    let expected = general_purpose::STANDARD.encode(bincode::serialize(swap).unwrap());
+    assert_eq!(h.bloxroute.requests().len(), 1);
+    assert_eq!(h.bloxroute.requests()[0].json()["transaction"], expected);
+    assert_eq!(h.nextblock.requests().len(), 1);
+    assert_eq!(h.nextblock.requests()[0].json()["tx"], expected);
+
+    // Отправка и подтверждение записаны в хранилище
+    assert!(h.ctx.store.recover().unwrap().pending.is_empty());
+}
+
+#[tokio::test]
+async fn test_disconnect_ends_monitoring() {
+    let h = harness().await;
+    let (after_disconnect, pool) = common::wsol_pool_update(20);
+
+    let geyser = MockGeyserServer::start(vec![
+        ScriptStep::Update(common::ping_update()),
+        ScriptStep::Disconnect,
+        ScriptStep::Update(after_disconnect),
+    ])
+    .await;
+
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert!(!h.ctx.store.is_pool_seen(&pool.pool).unwrap());
+    assert!(h.rpc.sent_transactions().is_empty());
+    assert!(h.bloxroute.requests().is_empty());
+}
+
+#[tokio::test]
+async fn test_swap_uses_blockhash_from_block_meta() {
+    let h = harness().await;
+    let geyser_hash = Hash::new_unique();
+    let (wsol_init, _) = common::wsol_pool_update(31);
+
+    let geyser = MockGeyserServer::start(vec![
+        ScriptStep::Update(common::block_meta_update(30, geyser_hash, 25)),
+        ScriptStep::Update(wsol_init),
+    ])
+    .await;
+
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    let sent = h.rpc.sent_transactions();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        *sent[0].message.recent_blockhash(),
+        geyser_hash,
+        "Blockhash должен браться из кэша, а не из RPC"
+    );
+}
+
+#[tokio::test]
+async fn test_denied_mint_is_not_bought() {
+    let mut h = harness().await;
+    let (denied_init, denied) = common::wsol_pool_update(40);
+    let (allowed_init, allowed) = common::wsol_pool_update(41);
+    h.ctx.filter = Arc::new(LaunchFilter::new(
+        FilterLists::default().deny(FilterKind::Mint, [denied.target_mint]),
+    ));
+
+    let geyser = MockGeyserServer::start(vec![
+        ScriptStep::Update(denied_init),
+        ScriptStep::Update(allowed_init),
+    ])
+    .await;
+
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert!(!h.ctx.store.is_pool_seen(&denied.pool).unwrap());
+    assert!(h.ctx.store.is_pool_seen(&allowed.pool).unwrap());
+    assert_eq!(h.rpc.sent_transactions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_pool_rejected_by_limits_can_be_bought_later() {
+    let mut h = harness().await;
+    let (init, pool) = common::wsol_pool_update(50);
+
+    h.ctx.risk = Arc::new(RiskManager::new(RiskLimits {
+        max_trade_lamports: 0,
+        ..RiskLimits::from_config()
+    }));
+    let geyser = MockGeyserServer::start(vec![ScriptStep::Update(init.clone())]).await;
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert!(h.rpc.sent_transactions().is_empty());
+    assert!(
+        !h.ctx.store.is_pool_seen(&pool.pool).unwrap(),
+        "Пул без отправленной покупки не должен оставаться отмеченным"
+    );
+
+    // Лимиты освободились — тот же пул покупается
+    h.ctx.risk = Arc::new(RiskManager::new(RiskLimits::from_config()));
+    let geyser = MockGeyserServer::start(vec![ScriptStep::Update(init)]).await;
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert_eq!(h.rpc.sent_transactions().len(), 1);
+    assert!(h.ctx.store.is_pool_seen(&pool.pool).unwrap());
+}
+
+#[tokio::test]
+async fn test_unsent_buy_returns_its_risk_reservation() {
+    let mut h = harness().await;
+    let (init, pool) = common::wsol_pool_update(50);
+
+    // Риск-менеджер один на оба запуска: отказ кошелька не должен занять единственную покупку окна
+    h.ctx.risk = Arc::new(RiskManager::new(RiskLimits {
+        max_buys_per_window: 1,
+        buy_window: Duration::from_secs(600),
+        ..RiskLimits::from_config()
+    }));
+    h.ctx.wallets = Arc::new(WalletPool::single(
+        Wallet::new(Keypair::new()).with_budget(0),
+    ));
+    let geyser = MockGeyserServer::start(vec![ScriptStep::Update(init.clone())]).await;
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert!(h.rpc.sent_transactions().is_empty());
+    assert_eq!(h.ctx.risk.in_flight(), 0);
+    assert!(!h.ctx.store.is_pool_seen(&pool.pool).unwrap());
+
+    h.ctx.wallets = Arc::new(WalletPool::single(Wallet::new(Keypair::new())));
+    let geyser = MockGeyserServer::start(vec![ScriptStep::Update(init)]).await;
+    run(geyser.endpoint(), h.ctx.clone()).await;
+
+    assert_eq!(h.rpc.sent_transactions().len(), 1);
+    assert_eq!(h.ctx.risk.in_flight(), 0);
+}
+
+#[tokio::test]
+async fn test_pending_buys_are_reconciled_on_startup() {
+    let rpc = MockRpc::start().await;
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+    let store = Store::open_in_memory(16).unwrap();
+    let (wallet, default_wallet) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    // Исполнена кошельком из пула
+    let (landed, landed_pool, landed_mint) = (
+        Signature::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    store.mark_pool_seen(&landed_pool, &landed_mint).unwrap();
+    store
+        .record_submission(&landed, &landed_pool, 1_000)
+        .unwrap();
+    store.record_buy_wallet(&landed, &wallet).unwrap();
+    rpc.set_token_balance_change(landed, wallet, landed_mint, 0, 500);
+
+    // Записана без кошелька — токены получил кошелёк по умолчанию
+    let (legacy, legacy_pool, legacy_mint) = (
+        Signature::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    store.mark_pool_seen(&legacy_pool, &legacy_mint).unwrap();
+    store
+        .record_submission(&legacy, &legacy_pool, 2_000)
+        .unwrap();
+    rpc.set_token_balance_change(legacy, default_wallet, legacy_mint, 10, 30);
+
+    let failed = Signature::new_unique();
+    store
+        .record_submission(&failed, &Pubkey::new_unique(), 3_000)
+        .unwrap();
+    rpc.set_signature_status(failed, Some(json!("AccountInUse")));
+
+    // Не найдена, но ещё может попасть в блок
+    let unknown = Signature::new_unique();
+    store
+        .record_submission(&unknown, &Pubkey::new_unique(), 4_000)
+        .unwrap();
+    rpc.set_signature_status(unknown, None);
+
+    let pending = store.recover().unwrap().pending;
+    assert_eq!(pending.len(), 4);
+    let reconciled = reconcile_pending(&pool, &store, &pending, &default_wallet)
+        .await
+        .unwrap();
+    assert_eq!(
+        reconciled,
+        Reconciliation {
+            filled: 2,
+            failed: 1,
+            unresolved: 1,
+        }
+    );
+
+    let pending = store.recover().unwrap().pending;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].signature, unknown);
+
+    let positions = store.positions().unwrap();
+    let held = |mint: Pubkey| positions.iter().find(|p| p.mint == mint).unwrap();
+    assert_eq!(held(landed_mint).tokens_bought, 500);
+    assert_eq!(held(legacy_mint).tokens_bought, 20);
+    assert_eq!(store.wallet_exposure().unwrap().get(&wallet), Some(&1_000));
+}
+
+/// Сборщик решений конвейера
+#[derive(Default)]
+struct Collected(Vec<Decision>);
+
+impl DecisionHandler for Collected {
+    async fn handle(&mut self, decision: Decision) {
+        self.0.push(decision);
+    }
+}
+
+#[tokio::test]
+async fn test_store_error_skips_pool_without_stopping_pipeline() {
+    let dir = std::env::temp_dir().join(format!("pipeline_store_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("bot.db");
+
+    let store = Store::open(&path, 16).unwrap();
+    // Таблицы увиденных пулов нет — первая отметка падает с ошибкой SQLite
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute_batch("DROP TABLE seen_pools")
+        .unwrap();
+
+    let (first, first_pool) = common::wsol_pool_update(1);
+    let (second, second_pool) = common::wsol_pool_update(2);
+    let repaired = path.clone();
+    let updates = stream::iter([(first, false), (second, true)]).map(move |(update, repair)| {
+        if repair {
+            // Повторное открытие создаёт недостающие таблицы
+            Store::open(&repaired, 0).unwrap();
+        }
+        Ok::<_, String>(update)
+    });
+
+    let mut collected = Collected::default();
+    run_pipeline(updates, &store, &LaunchFilter::allow_all(), &mut collected)
+        .await
+        .unwrap();
+
+    assert_eq!(collected.0.len(), 1);
+    let Decision::Buy(launch) = &collected.0[0] else {
+        panic!("Второй пул должен покупаться");
+    };
+    assert_eq!(launch.accounts.pool, second_pool.pool);
+    assert!(!store.is_pool_seen(&first_pool.pool).unwrap());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -1,14 +1,114 @@
-use meteora_sniper_bot::nextblock::send_to_nextblock;
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use serde_json::json;
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::nextblock::NextBlockRelay;
+use meteora_sniper_bot::relay::{
+    guard::{GuardedRelay, RelayPolicy},
+    Relay, RelayError,
+};
+
+#[tokio::test]
+async fn test_send_to_nextblock_request_shape() {
+    let server = MockHttpServer::start(vec![MockResponse::Json(
+        200,
+        json!({ "signature": "5sig" }),
+    )])
+    .await;
+    let relay = NextBlockRelay::new(server.url("/solana/tx"), "next-key");
+
+    let ack = relay.send("dHg=", 42).await.unwrap();
+    assert_eq!(ack.signature.as_deref(), Some("5sig"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    let req = &requests[0];
+    assert_eq!(req.method, "POST");
+    assert_eq!(req.path, "/solana/tx");
+    assert_eq!(req.header("authorization"), Some("Bearer next-key"));
+
+    let body = req.json();
+    assert_eq!(body["tx"], "dHg=");
+    assert_eq!(body["meta"]["tip"], "42");
+}
 
 #[tokio::test]
 async fn test_send_to_nextblock_invalid_tx() {
-    let tx_base64 = "invalid_base64_string";
-    let tip = 42;
+    // Сервер недоступен: порт закрыт сразу после запуска
+    let server = MockHttpServer::start(vec![]).await;
+    let url = server.url("/");
+    drop(server);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let relay = NextBlockRelay::new(url, "next-key");
+    let result = relay.send("invalid_base64_string", 42).await;
 
-    let result = send_to_nextblock(tx_base64, tip).await;
+    assert!(
+        matches!(result, Err(RelayError::Transport { .. })),
+        "NextBlock должен вернуть ошибку при недоступном сервере"
+    );
+}
+
+#[tokio::test]
+async fn test_send_to_nextblock_checks_http_status() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Raw(500, "internal error".into()),
+        MockResponse::Json(403, json!({ "error": "forbidden" })),
+        MockResponse::Json(400, json!({ "error": "failed to deserialize transaction" })),
+        MockResponse::Json(200, json!({ "uuid": "f1e2" })),
+    ])
+    .await;
+    let relay = NextBlockRelay::new(server.url("/"), "next-key");
+
+    // Раньше любой статус считался успехом
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(matches!(err, RelayError::Transport { .. }), "{:?}", err);
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(matches!(err, RelayError::Auth { .. }), "{:?}", err);
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert_eq!(
+        err,
+        RelayError::Rejected {
+            relay: "NextBlock".into(),
+            code: Some(400),
+            message: "failed to deserialize transaction".into(),
+        }
+    );
+
+    let ack = relay.send("dHg=", 1).await.unwrap();
+    assert_eq!(ack.uuid.as_deref(), Some("f1e2"));
+    assert_eq!(ack.signature, None);
+}
+
+#[tokio::test]
+async fn test_send_to_nextblock_slow_response() {
+    let server = MockHttpServer::start(vec![MockResponse::Delay(
+        Duration::from_secs(5),
+        Box::new(MockResponse::Json(200, json!({}))),
+    )])
+    .await;
+    let relay = GuardedRelay::new(
+        Arc::new(NextBlockRelay::new(server.url("/"), "next-key")),
+        RelayPolicy {
+            timeout: Duration::from_millis(200),
+            max_retries: 0,
+            ..RelayPolicy::from_config(Duration::ZERO)
+        },
+    );
 
+    // Без ответа отправка обрывается по таймауту, а не висит до ответа сервера
+    let result = tokio::time::timeout(Duration::from_secs(2), relay.send("dHg=", 1))
+        .await
+        .expect("Таймаут ретранслятора должен сработать раньше");
     assert!(
-        result.is_err(),
-        "NextBlock должен вернуть ошибку при невалидной транзакции"
+        matches!(result, Err(RelayError::Timeout { .. })),
+        "{:?}",
+        result
     );
-}
\ No newline at end of file
+    assert_eq!(server.requests().len(), 1);
+}
@@ -0,0 +1,137 @@
+use std::{io::Write, time::Duration};
+
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, SubscribeUpdate, SubscribeUpdatePing, SubscribeUpdateSlot,
+};
+
+use meteora_sniper_bot::geyser::recorder::{
+    read_recording, recording_files, BackgroundRecorder, GeyserRecorder,
+};
+
+fn slot_update(slot: u64) -> SubscribeUpdate {
+    SubscribeUpdate {
+        filters: vec!["meteora".to_string()],
+        update_oneof: Some(UpdateOneof::Slot(SubscribeUpdateSlot {
+            slot,
+            ..Default::default()
+        })),
+        created_at: None,
+    }
+}
+
+#[test]
+fn test_recording_roundtrip_with_rotation() {
+    let dir = std::env::temp_dir().join(format!("geyser_rec_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut updates: Vec<SubscribeUpdate> = (0..50).map(slot_update).collect();
+    updates.push(SubscribeUpdate {
+        filters: vec![],
+        update_oneof: Some(UpdateOneof::Ping(SubscribeUpdatePing {})),
+        created_at: None,
+    });
+
+    {
+        // Маленький лимит, чтобы запись разошлась по нескольким файлам
+        let mut recorder = GeyserRecorder::new(&dir, "test", 256).unwrap();
+        for (i, update) in updates.iter().enumerate() {
+            recorder.record_at(update, 1_000 + i as u64).unwrap();
+        }
+    }
+
+    assert!(
+        recording_files(&dir).unwrap().len() > 1,
+        "Ожидалась ротация файлов"
+    );
+
+    let recorded = read_recording(&dir)
+        .unwrap()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(recorded.len(), updates.len());
+    for (i, (rec, original)) in recorded.iter().zip(&updates).enumerate() {
+        assert_eq!(rec.received_at_micros, 1_000 + i as u64);
+        assert_eq!(&rec.update, original);
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_reader_rejects_unknown_format() {
+    let path = std::env::temp_dir().join(format!("geyser_rec_bad_{}.rec", std::process::id()));
+    std::fs::write(&path, b"not a recording").unwrap();
+
+    assert!(read_recording(&path).is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_recording_survives_crash_with_truncated_frame() {
+    let dir = std::env::temp_dir().join(format!("geyser_rec_crash_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut recorder = GeyserRecorder::new(&dir, "crash", 1 << 20)
+        .unwrap()
+        .with_flush_interval(Duration::ZERO);
+    for slot in 0..5 {
+        recorder.record_at(&slot_update(slot), slot).unwrap();
+    }
+    // Падение: деструктор с финальным сбросом буфера не вызывается
+    std::mem::forget(recorder);
+
+    // Последний кадр записан не до конца
+    let files = recording_files(&dir).unwrap();
+    assert_eq!(files.len(), 1);
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&files[0])
+        .unwrap();
+    file.write_all(&100u32.to_le_bytes()).unwrap();
+    file.write_all(&[0; 10]).unwrap();
+
+    let recorded = read_recording(&dir)
+        .unwrap()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(recorded.len(), 5);
+    assert_eq!(recorded[4].update, slot_update(4));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_background_recorder_writes_queue_and_drops_overflow() {
+    let dir = std::env::temp_dir().join(format!("geyser_rec_bg_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let updates: Vec<SubscribeUpdate> = (0..500).map(slot_update).collect();
+
+    // Очередь на одно обновление: часть обновлений не успевает в очередь и пропускается
+    let recorder = GeyserRecorder::new(&dir, "test", u64::MAX).unwrap();
+    let background = BackgroundRecorder::spawn(recorder, 1);
+    let queued = updates.iter().filter(|u| background.record(u)).count();
+    let dropped = background.dropped();
+    background.shutdown().await.unwrap();
+
+    assert_eq!(queued as u64 + dropped, updates.len() as u64);
+
+    // Всё, что попало в очередь, записано по порядку
+    let recorded = read_recording(&dir)
+        .unwrap()
+        .collect::<anyhow::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(recorded.len(), queued);
+    let slots: Vec<_> = recorded
+        .iter()
+        .map(|rec| match &rec.update.update_oneof {
+            Some(UpdateOneof::Slot(slot)) => slot.slot,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert!(slots.windows(2).all(|w| w[0] < w[1]));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::bloxroute::BloxrouteRelay;
+use meteora_sniper_bot::relay::{
+    guard::{CircuitState, GuardedRelay, RelayPolicy},
+    Relay, RelayError,
+};
+
+fn policy(timeout_ms: u64, max_retries: u32, failure_threshold: u32) -> RelayPolicy {
+    RelayPolicy {
+        timeout: Duration::from_millis(timeout_ms),
+        max_retries,
+        retry_backoff: Duration::from_millis(10),
+        failure_threshold,
+        cooldown: Duration::from_millis(200),
+    }
+}
+
+fn guarded(server: &MockHttpServer, policy: RelayPolicy) -> GuardedRelay {
+    GuardedRelay::new(
+        Arc::new(BloxrouteRelay::new(server.url("/"), "key")),
+        policy,
+    )
+}
+
+#[tokio::test]
+async fn test_slow_relay_times_out() {
+    let server = MockHttpServer::start(vec![MockResponse::Delay(
+        Duration::from_secs(5),
+        Box::new(MockResponse::Json(200, json!({ "signature": "s" }))),
+    )])
+    .await;
+    let relay = guarded(&server, policy(100, 1, 5));
+
+    let started = Instant::now();
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+
+    assert!(matches!(err, RelayError::Timeout { .. }), "{:?}", err);
+    assert!(started.elapsed() < Duration::from_secs(1));
+    let stats = relay.snapshot();
+    assert_eq!(stats.timeouts, 2);
+    assert_eq!(stats.retries, 1);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_transient_error_is_retried() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Raw(502, "Bad Gateway".into()),
+        MockResponse::Json(200, json!({ "signature": "5sig" })),
+    ])
+    .await;
+    let relay = guarded(&server, policy(1_000, 2, 5));
+
+    let ack = relay.send("dHg=", 1).await.unwrap();
+
+    assert_eq!(ack.signature.as_deref(), Some("5sig"));
+    assert_eq!(server.requests().len(), 2);
+    let stats = relay.snapshot();
+    assert_eq!((stats.retries, stats.succeeded, stats.failed), (1, 1, 0));
+}
+
+#[tokio::test]
+async fn test_rejected_transaction_is_not_retried_or_counted_as_outage() {
+    let server = MockHttpServer::start(vec![MockResponse::Json(
+        400,
+        json!({ "error": "invalid transaction" }),
+    )])
+    .await;
+    let relay = guarded(&server, policy(1_000, 2, 1));
+
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+
+    assert!(matches!(err, RelayError::Rejected { .. }), "{:?}", err);
+    assert_eq!(server.requests().len(), 1);
+    assert_eq!(relay.snapshot().state, CircuitState::Closed);
+    assert_eq!(relay.snapshot().consecutive_failures, 0);
+}
+
+#[tokio::test]
+async fn test_circuit_opens_after_failures_and_recovers() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Raw(500, "down".into()),
+        MockResponse::Raw(500, "down".into()),
+        MockResponse::Json(200, json!({ "signature": "s" })),
+    ])
+    .await;
+    let relay = guarded(&server, policy(1_000, 0, 2));
+
+    assert!(relay.send("dHg=", 1).await.is_err());
+    assert!(relay.send("dHg=", 1).await.is_err());
+    assert!(matches!(relay.snapshot().state, CircuitState::Open { .. }));
+
+    // Пока автомат разомкнут, запросы не уходят
+    let err = relay.send("dHg=", 1).await.unwrap_err();
+    assert!(matches!(err, RelayError::CircuitOpen { .. }), "{:?}", err);
+    assert!(!err.is_retryable());
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(relay.snapshot().short_circuited, 1);
+
+    // После паузы пробная отправка проходит и замыкает автомат
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    relay.send("dHg=", 1).await.unwrap();
+    assert_eq!(relay.snapshot().state, CircuitState::Closed);
+    assert_eq!(server.requests().len(), 3);
+    assert_eq!(relay.stats(), Some(relay.snapshot()));
+}
+
+#[tokio::test]
+async fn test_cancelled_trial_does_not_block_relay() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Raw(500, "down".into()),
+        MockResponse::Delay(
+            Duration::from_secs(5),
+            Box::new(MockResponse::Json(200, json!({ "signature": "late" }))),
+        ),
+        MockResponse::Json(200, json!({ "signature": "s" })),
+    ])
+    .await;
+    let relay = guarded(&server, policy(1_000, 0, 1));
+
+    assert!(relay.send("dHg=", 1).await.is_err());
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    // Пробная отправка отменена вызывающим до ответа реле
+    let cancelled = tokio::time::timeout(Duration::from_millis(50), relay.send("dHg=", 1)).await;
+    assert!(cancelled.is_err());
+    assert_eq!(relay.snapshot().state, CircuitState::HalfOpen);
+
+    // Следующая отправка становится новой пробой, а не отбрасывается автоматом
+    let ack = relay.send("dHg=", 1).await.unwrap();
+    assert_eq!(ack.signature.as_deref(), Some("s"));
+    assert_eq!(relay.snapshot().state, CircuitState::Closed);
+    assert_eq!(relay.snapshot().short_circuited, 0);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::bloxroute::BloxrouteRelay;
+use meteora_sniper_bot::nextblock::NextBlockRelay;
+use meteora_sniper_bot::relay::{routing::RelayRouter, RelayRegistry};
+use meteora_sniper_bot::tpu::leaders::LeaderTracker;
+
+/// Каждый лидер ведёт 4 слота подряд, начиная со слота 100
+fn tracker_with_leaders(leaders: &[Pubkey]) -> Arc<LeaderTracker> {
+    let tracker = Arc::new(LeaderTracker::new());
+    tracker.observe_slot(100);
+    tracker.set_leader_schedule(100, leaders.iter().flat_map(|l| [*l; 4]).collect());
+    tracker
+}
+
+#[test]
+fn test_plan_falls_back_to_default_route_without_schedule() {
+    let router = RelayRouter::new(
+        Arc::new(LeaderTracker::new()),
+        [("Bloxroute", 10), ("NextBlock", 20)],
+        2,
+    );
+
+    let plan = router.plan();
+
+    assert!(plan.leaders.is_empty());
+    assert_eq!(plan.tip_for("Bloxroute"), Some(10));
+    assert_eq!(plan.tip_for("NextBlock"), Some(20));
+    assert_eq!(plan.tip_for("Jito"), None);
+}
+
+#[test]
+fn test_plan_uses_routes_of_upcoming_leaders() {
+    let (jito_leader, plain_leader) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let tracker = tracker_with_leaders(&[jito_leader, plain_leader]);
+    let router = |lookahead| {
+        RelayRouter::new(
+            tracker.clone(),
+            [("Bloxroute", 10), ("NextBlock", 10)],
+            lookahead,
+        )
+        .with_validator(jito_leader, [("Jito", 50_000), ("Bloxroute", 30)])
+    };
+
+    // Ближайший лидер работает с Jito: NextBlock ему не нужен
+    let plan = router(1).plan();
+    assert_eq!(plan.leaders, vec![jito_leader]);
+    assert_eq!(plan.tip_for("Jito"), Some(50_000));
+    assert_eq!(plan.tip_for("Bloxroute"), Some(30));
+    assert_eq!(plan.tip_for("NextBlock"), None);
+
+    // С учётом следующего лидера маршруты объединяются, чаевые берутся наибольшие
+    let plan = router(2).plan();
+    assert_eq!(plan.leaders, vec![jito_leader, plain_leader]);
+    assert_eq!(plan.tip_for("Jito"), Some(50_000));
+    assert_eq!(plan.tip_for("Bloxroute"), Some(30));
+    assert_eq!(plan.tip_for("NextBlock"), Some(10));
+}
+
+#[test]
+fn test_jito_is_skipped_when_upcoming_leaders_do_not_run_it() {
+    let (jito_leader, plain_leader) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let tracker = tracker_with_leaders(&[plain_leader, jito_leader]);
+    let router = RelayRouter::new(tracker, [("Bloxroute", 10)], 1)
+        .with_validator(jito_leader, [("Jito", 50_000)]);
+
+    let plan = router.plan();
+
+    assert_eq!(plan.tip_for("Jito"), None);
+    assert_eq!(plan.tip_for("Bloxroute"), Some(10));
+}
+
+#[tokio::test]
+async fn test_registry_sends_only_to_planned_relays_with_planned_tips() {
+    let blox =
+        MockHttpServer::start(vec![MockResponse::Json(200, json!({ "signature": "s" }))]).await;
+    let next = MockHttpServer::start(vec![MockResponse::Json(200, json!({}))]).await;
+    let leader = Pubkey::new_unique();
+    let router = RelayRouter::new(tracker_with_leaders(&[leader]), [("NextBlock", 1)], 1)
+        .with_validator(leader, [("Bloxroute", 777)]);
+
+    let registry = RelayRegistry::new()
+        .with(Arc::new(BloxrouteRelay::new(blox.url("/"), "b")))
+        .with(Arc::new(NextBlockRelay::new(next.url("/"), "n")))
+        .with_router(router);
+
+    let results = registry.broadcast("dHg=", 5).await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].0, "Bloxroute");
+    assert!(results[0].1.is_ok());
+    assert_eq!(blox.requests()[0].json()["tip"], "777");
+    assert!(next.requests().is_empty());
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use std::sync::Arc;
+
+use serde_json::json;
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::bloxroute::BloxrouteRelay;
+use meteora_sniper_bot::nextblock::NextBlockRelay;
+use meteora_sniper_bot::relay::{build_http_client, RelayRegistry};
+
+#[tokio::test]
+async fn test_registry_broadcasts_to_every_relay() {
+    let blox = MockHttpServer::start(vec![MockResponse::Json(
+        200,
+        json!({ "error": "rejected" }),
+    )])
+    .await;
+    let next = MockHttpServer::start(vec![MockResponse::Json(200, json!({}))]).await;
+
+    let registry = RelayRegistry::new()
+        .with(Arc::new(BloxrouteRelay::new(blox.url("/"), "b")))
+        .with(Arc::new(NextBlockRelay::new(next.url("/"), "n")));
+
+    let results = registry.broadcast("dHg=", 7).await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].0, "Bloxroute");
+    assert!(results[0].1.is_err());
+    assert_eq!(results[1].0, "NextBlock");
+    assert!(results[1].1.is_ok());
+
+    assert_eq!(blox.requests()[0].json()["transaction"], "dHg=");
+    assert_eq!(next.requests()[0].json()["tx"], "dHg=");
+}
+
+#[tokio::test]
+async fn test_relays_reuse_warm_connection() {
+    let blox =
+        MockHttpServer::start(vec![MockResponse::Json(200, json!({ "signature": "s" }))]).await;
+    let next = MockHttpServer::start(vec![MockResponse::Json(200, json!({}))]).await;
+
+    let registry = RelayRegistry::new().with_http_client(build_http_client());
+    let http = registry.http_client().clone();
+    let registry = registry
+        .with(Arc::new(BloxrouteRelay::with_client(
+            http.clone(),
+            blox.url("/solana/submit"),
+            "b",
+        )))
+        .with(Arc::new(NextBlockRelay::with_client(
+            http,
+            next.url("/solana/tx"),
+            "n",
+        )));
+
+    for (name, res) in registry.warm_up().await {
+        assert!(res.is_ok(), "Прогрев {} не удался: {:?}", name, res);
+    }
+    assert_eq!(blox.requests()[0].method, "HEAD");
+    assert_eq!(blox.requests()[0].path, "/");
+    assert_eq!(next.requests()[0].method, "HEAD");
+
+    for _ in 0..3 {
+        for (_, res) in registry.broadcast("dHg=", 1).await {
+            assert!(res.is_ok());
+        }
+    }
+
+    // Прогрев и все отправки прошли через одно соединение на каждый ретранслятор
+    assert_eq!(blox.requests().len(), 4);
+    assert_eq!(blox.connections(), 1);
+    assert_eq!(next.requests().len(), 4);
+    assert_eq!(next.connections(), 1);
+}
+
+#[tokio::test]
+async fn test_warm_up_reports_unreachable_relay() {
+    let registry = RelayRegistry::new().with(Arc::new(BloxrouteRelay::new(
+        "http://127.0.0.1:1/solana/submit",
+        "b",
+    )));
+
+    let results = registry.warm_up().await;
+    assert_eq!(results.len(), 1);
+    assert!(results[0].1.is_err());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use solana_sdk::signature::Keypair;
+
+use meteora_sniper_bot::geyser::{recorder::GeyserRecorder, Decision, SkipReason};
+use meteora_sniper_bot::replay::{replay_recording, ReplaySpeed};
+
+#[tokio::test]
+async fn test_replay_reproduces_decisions_and_transactions() {
+    let dir = std::env::temp_dir().join(format!("geyser_replay_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let (wsol_update, fixture) = common::wsol_pool_update(100);
+    let (other_update, other_pool) = common::non_wsol_pool_update(101);
+
+    {
+        let mut recorder = GeyserRecorder::new(&dir, "replay", 1 << 20).unwrap();
+        recorder.record_at(&common::ping_update(), 1_000).unwrap();
+        recorder.record_at(&wsol_update, 2_000).unwrap();
+        recorder.record_at(&other_update, 3_000).unwrap();
+        // Повтор того же пула не должен привести ко второй покупке
+        recorder.record_at(&wsol_update, 4_000).unwrap();
+    }
+
+    let payer = Keypair::new();
+    let decisions = replay_recording(&dir, ReplaySpeed::AsFastAsPossible, &payer)
+        .await
+        .unwrap();
+
+    assert_eq!(decisions.len(), 3);
+
+    match &decisions[0].decision {
+        Decision::Buy(launch) => {
+            assert_eq!(launch.accounts.pool, fixture.pool);
+            assert_eq!(launch.target_mint, fixture.target_mint);
+            assert_eq!(launch.signature, fixture.signature);
+            assert_eq!(launch.slot, 100);
+        }
+        other => panic!("Ожидалась покупка, получено {:?}", other),
+    }
+    let tx = decisions[0]
+        .transaction
+        .as_ref()
+        .expect("Транзакция должна быть построена");
+    assert!(tx.verify_with_results().iter().all(|ok| *ok));
+
+    assert_eq!(
+        decisions[1].decision,
+        Decision::Skip {
+            pool: other_pool,
+            reason: SkipReason::NoWsol
+        }
+    );
+    assert_eq!(
+        decisions[2].decision,
+        Decision::Skip {
+            pool: fixture.pool,
+            reason: SkipReason::AlreadySeen
+        }
+    );
+
+    // Повторный прогон даёт те же решения
+    let again = replay_recording(&dir, ReplaySpeed::AsFastAsPossible, &payer)
+        .await
+        .unwrap();
+    let first: Vec<_> = decisions.iter().map(|d| &d.decision).collect();
+    let second: Vec<_> = again.iter().map(|d| &d.decision).collect();
+    assert_eq!(first, second);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
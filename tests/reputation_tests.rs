@@ -0,0 +1,220 @@
+mod common;
+
+use std::{str::FromStr, sync::Arc};
+
+use solana_sdk::pubkey::Pubkey;
+use yellowstone_grpc_proto::{
+    convert_from::create_tx_versioned,
+    prelude::{subscribe_update::UpdateOneof, SubscribeUpdate},
+};
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::config::WSOL_MINT;
+use meteora_sniper_bot::reputation::{
+    analyze_history, score, DeployerReputation, ReputationParams, TokenBalanceChange, WalletTx,
+};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::store::{DeployerHistory, Store};
+
+fn params() -> ReputationParams {
+    ReputationParams {
+        min_score: 50,
+        history_limit: 100,
+        cache_ttl_secs: 3_600,
+        rug_window_secs: 3_600,
+        launch_penalty: 10,
+        rug_penalty: 40,
+        funder_penalty: 30,
+    }
+}
+
+fn wallet_tx(update: &SubscribeUpdate, block_time: i64) -> WalletTx {
+    let Some(UpdateOneof::Transaction(tx)) = &update.update_oneof else {
+        unreachable!()
+    };
+    let info = tx.transaction.as_ref().unwrap();
+    WalletTx {
+        slot: tx.slot,
+        block_time: Some(block_time),
+        tx: create_tx_versioned(info.transaction.clone().unwrap()).unwrap(),
+        loaded_addresses: vec![],
+        token_balances: vec![],
+        lamport_changes: vec![],
+    }
+}
+
+/// Транзакция, которая меняет резервы пула с authority `authority`
+fn reserves_tx(
+    base: &WalletTx,
+    block_time: i64,
+    authority: Pubkey,
+    mint: Pubkey,
+    (sol_pre, sol_post): (u64, u64),
+    (token_pre, token_post): (u64, u64),
+) -> WalletTx {
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    WalletTx {
+        block_time: Some(block_time),
+        token_balances: vec![
+            TokenBalanceChange {
+                owner: authority,
+                mint: wsol,
+                pre: sol_pre,
+                post: sol_post,
+            },
+            TokenBalanceChange {
+                owner: authority,
+                mint,
+                pre: token_pre,
+                post: token_post,
+            },
+        ],
+        ..base.clone()
+    }
+}
+
+#[test]
+fn test_history_counts_launches_rugs_and_funder() {
+    let creator = Pubkey::new_unique();
+    let funder = Pubkey::new_unique();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let (rugged_mint, held_mint, current_mint) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    let (rugged_init, _, rugged_authority) =
+        common::init_pool_update_by(10, creator, wsol, rugged_mint);
+    let (held_init, _, held_authority) = common::init_pool_update_by(20, creator, held_mint, wsol);
+    let (current_init, current_pool, _) =
+        common::init_pool_update_by(30, creator, wsol, current_mint);
+    // Чужой пул в истории создателя не считается
+    let (foreign_init, _, _) = common::init_pool_update_with(25, wsol, Pubkey::new_unique());
+
+    let mut funding = wallet_tx(&foreign_init, 900);
+    funding.lamport_changes = vec![(funder, -2_000_000_005), (creator, 2_000_000_000)];
+
+    let rugged = wallet_tx(&rugged_init, 1_000);
+    let held = wallet_tx(&held_init, 2_000);
+    let txs = vec![
+        funding,
+        rugged.clone(),
+        held.clone(),
+        // Свап в пул — резервы в разные стороны
+        reserves_tx(
+            &rugged,
+            1_010,
+            rugged_authority,
+            rugged_mint,
+            (10, 20),
+            (100, 50),
+        ),
+        // Изъятие ликвидности через минуту после запуска
+        reserves_tx(
+            &rugged,
+            1_060,
+            rugged_authority,
+            rugged_mint,
+            (20, 0),
+            (50, 0),
+        ),
+        // Второй пул опустошён через двое суток
+        reserves_tx(
+            &held,
+            174_800,
+            held_authority,
+            held_mint,
+            (10, 1),
+            (100, 10),
+        ),
+        wallet_tx(&current_init, 200_000),
+    ];
+
+    let history = analyze_history(&creator, &txs, true, &current_pool, 3_600).unwrap();
+
+    assert_eq!(
+        history,
+        DeployerHistory {
+            creator,
+            launches: 2,
+            rugs: 1,
+            fastest_pull_secs: Some(60),
+            funder: Some(funder),
+        }
+    );
+
+    // История неполная — первая транзакция не обязательно пополнение
+    let partial = analyze_history(&creator, &txs, false, &current_pool, 3_600).unwrap();
+    assert_eq!(partial.funder, None);
+    assert_eq!(partial.launches, 2);
+}
+
+#[test]
+fn test_score_penalties_are_capped() {
+    let clean = DeployerHistory {
+        creator: Pubkey::new_unique(),
+        launches: 0,
+        rugs: 0,
+        fastest_pull_secs: None,
+        funder: None,
+    };
+    assert_eq!(score(&clean, 0, &params()), 100);
+
+    let serial = DeployerHistory {
+        launches: 3,
+        ..clean.clone()
+    };
+    assert_eq!(score(&serial, 0, &params()), 70);
+    assert_eq!(score(&serial, 2, &params()), 40);
+
+    let rugger = DeployerHistory {
+        launches: 5,
+        rugs: 4,
+        ..clean
+    };
+    assert_eq!(score(&rugger, 1, &params()), 0);
+}
+
+#[tokio::test]
+async fn test_cached_history_is_used_without_rpc() {
+    // MockRpc не знает getSignaturesForAddress, поэтому без кэша оценка падает
+    let rpc = MockRpc::start().await;
+    let pool = Arc::new(RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap());
+    let store = Arc::new(Store::open_in_memory(16).unwrap());
+    let reputation = DeployerReputation::new(pool, store.clone(), params());
+
+    let funder = Pubkey::new_unique();
+    let (creator, sibling) = (Pubkey::new_unique(), Pubkey::new_unique());
+    store
+        .record_deployer(&DeployerHistory {
+            creator,
+            launches: 1,
+            rugs: 0,
+            fastest_pull_secs: None,
+            funder: Some(funder),
+        })
+        .unwrap();
+    store
+        .record_deployer(&DeployerHistory {
+            creator: sibling,
+            launches: 4,
+            rugs: 3,
+            fastest_pull_secs: Some(30),
+            funder: Some(funder),
+        })
+        .unwrap();
+
+    let assessed = reputation
+        .assess(&creator, &Pubkey::new_unique())
+        .await
+        .unwrap();
+    assert_eq!(assessed.funder_rugs, 3);
+    assert_eq!(assessed.score, 100 - 10 - 30);
+    assert!(assessed.to_string().contains(&funder.to_string()));
+
+    assert!(reputation
+        .assess(&Pubkey::new_unique(), &Pubkey::new_unique())
+        .await
+        .is_err());
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use solana_sdk::pubkey::Pubkey;
+
+use meteora_sniper_bot::risk::{Exposure, RiskLimits, RiskManager, RiskRejection};
+use meteora_sniper_bot::store::Store;
+
+fn limits() -> RiskLimits {
+    RiskLimits {
+        max_trade_lamports: 1_000,
+        max_exposure_lamports: 2_500,
+        max_open_positions: 3,
+        max_buys_per_window: 10,
+        buy_window: Duration::from_secs(60),
+        max_daily_loss_lamports: 5_000,
+    }
+}
+
+#[test]
+fn test_trade_larger_than_limit_is_rejected() {
+    let risk = RiskManager::new(limits());
+
+    let err = risk
+        .approve(Pubkey::new_unique(), 1_001, &Exposure::default())
+        .unwrap_err();
+
+    assert_eq!(
+        err,
+        RiskRejection::TradeTooLarge {
+            amount: 1_001,
+            max: 1_000
+        }
+    );
+    assert_eq!(risk.in_flight(), 0);
+}
+
+#[test]
+fn test_in_flight_buys_count_towards_exposure_until_released() {
+    let risk = RiskManager::new(limits());
+    let exposure = Exposure {
+        open_cost_lamports: 600,
+        ..Exposure::default()
+    };
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    risk.approve(first, 1_000, &exposure).unwrap();
+    let err = risk.approve(second, 1_000, &exposure).unwrap_err();
+    assert_eq!(
+        err,
+        RiskRejection::ExposureLimit {
+            exposure: 1_600,
+            amount: 1_000,
+            max: 2_500
+        }
+    );
+
+    // Первая покупка не исполнилась — резерв снят
+    risk.release(&first);
+    risk.approve(second, 1_000, &exposure).unwrap();
+}
+
+#[test]
+fn test_open_positions_limit_includes_in_flight_buys() {
+    let risk = RiskManager::new(limits());
+    let exposure = Exposure {
+        open_positions: 2,
+        ..Exposure::default()
+    };
+
+    risk.approve(Pubkey::new_unique(), 100, &exposure).unwrap();
+    let err = risk
+        .approve(Pubkey::new_unique(), 100, &exposure)
+        .unwrap_err();
+
+    assert_eq!(err, RiskRejection::TooManyPositions { open: 3, max: 3 });
+}
+
+#[test]
+fn test_buys_per_window_are_limited() {
+    let risk = RiskManager::new(RiskLimits {
+        max_buys_per_window: 2,
+        buy_window: Duration::from_millis(100),
+        ..limits()
+    });
+
+    for _ in 0..2 {
+        let pool = Pubkey::new_unique();
+        risk.approve(pool, 10, &Exposure::default()).unwrap();
+        risk.release(&pool);
+    }
+    let err = risk
+        .approve(Pubkey::new_unique(), 10, &Exposure::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, RiskRejection::BuyRateLimit { buys: 2, .. }),
+        "{:?}",
+        err
+    );
+
+    // Окно сдвинулось
+    std::thread::sleep(Duration::from_millis(120));
+    risk.approve(Pubkey::new_unique(), 10, &Exposure::default())
+        .unwrap();
+}
+
+#[test]
+fn test_daily_loss_halts_buying() {
+    let risk = RiskManager::new(limits());
+    let exposure = Exposure {
+        realized_pnl_today: -5_000,
+        ..Exposure::default()
+    };
+
+    let err = risk
+        .approve(Pubkey::new_unique(), 10, &exposure)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RiskRejection::DailyLossLimit {
+            loss: 5_000,
+            max: 5_000
+        }
+    );
+    assert!(err.to_string().contains("покупки остановлены"));
+
+    // Прибыль за день лимит не трогает
+    let profitable = Exposure {
+        realized_pnl_today: 10_000,
+        ..Exposure::default()
+    };
+    risk.approve(Pubkey::new_unique(), 10, &profitable).unwrap();
+}
+
+#[test]
+fn test_exposure_is_read_from_store() {
+    use solana_sdk::signature::Signature;
+
+    let store = Store::open_in_memory(16).unwrap();
+    let (pool, held, closed) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    store
+        .record_fill(&Signature::new_unique(), &pool, &held, 1_000, 100)
+        .unwrap();
+    store
+        .record_sell(&Signature::new_unique(), &pool, &held, 50, 400)
+        .unwrap();
+    store
+        .record_fill(&Signature::new_unique(), &pool, &closed, 2_000, 10)
+        .unwrap();
+    store
+        .record_sell(&Signature::new_unique(), &pool, &closed, 10, 500)
+        .unwrap();
+
+    let exposure = Exposure::from_store(&store).unwrap();
+
+    assert_eq!(exposure.open_positions, 1);
+    assert_eq!(exposure.open_cost_lamports, 500);
+    assert_eq!(exposure.realized_pnl_today, (400 - 500) + (500 - 2_000));
+    assert!(exposure.pending.is_empty());
+}
+
+#[test]
+fn test_unconfirmed_submissions_count_towards_exposure() {
+    use solana_sdk::signature::Signature;
+
+    let store = Store::open_in_memory(16).unwrap();
+    let risk = RiskManager::new(limits());
+    let (unconfirmed, confirmed) = (Pubkey::new_unique(), Pubkey::new_unique());
+    store
+        .record_submission(&Signature::new_unique(), &unconfirmed, 700)
+        .unwrap();
+    let signature = Signature::new_unique();
+    store
+        .record_submission(&signature, &confirmed, 900)
+        .unwrap();
+    store
+        .record_confirmation(&signature, Some("failed"))
+        .unwrap();
+
+    let exposure = Exposure::from_store(&store).unwrap();
+    assert_eq!(exposure.pending.len(), 1);
+    assert_eq!(exposure.pending[&unconfirmed], 700);
+
+    // Выполняемая покупка, уже записанная как отправленная, учитывается один раз
+    let first = Pubkey::new_unique();
+    risk.approve_from_store(first, 1_000, &store)
+        .unwrap()
+        .unwrap();
+    store
+        .record_submission(&Signature::new_unique(), &first, 1_000)
+        .unwrap();
+    let err = risk
+        .approve_from_store(Pubkey::new_unique(), 1_000, &store)
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RiskRejection::ExposureLimit {
+            exposure: 1_700,
+            amount: 1_000,
+            max: 2_500
+        }
+    );
+}
+
+#[test]
+fn test_cancelled_buy_does_not_count_towards_window() {
+    let risk = RiskManager::new(RiskLimits {
+        max_buys_per_window: 1,
+        ..limits()
+    });
+
+    let unsent = Pubkey::new_unique();
+    risk.approve(unsent, 10, &Exposure::default()).unwrap();
+    risk.cancel(&unsent);
+    assert_eq!(risk.in_flight(), 0);
+
+    // Отправленная покупка занимает окно и после снятия резерва
+    let sent = Pubkey::new_unique();
+    risk.approve(sent, 10, &Exposure::default()).unwrap();
+    risk.release(&sent);
+    let err = risk
+        .approve(Pubkey::new_unique(), 10, &Exposure::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, RiskRejection::BuyRateLimit { buys: 1, .. }),
+        "{:?}",
+        err
+    );
+}
@@ -0,0 +1,225 @@
+mod common;
+
+use std::time::Duration;
+
+use serde_json::json;
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+
+fn latest_blockhash_response() -> MockResponse {
+    MockResponse::Json(
+        200,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 100 },
+                "value": {
+                    "blockhash": Hash::new_from_array([9; 32]).to_string(),
+                    "lastValidBlockHeight": 1_000
+                }
+            }
+        }),
+    )
+}
+
+fn transfer_tx() -> VersionedTransaction {
+    let payer = Keypair::new();
+    let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    VersionedTransaction::from(tx)
+}
+
+#[tokio::test]
+async fn test_read_fails_over_to_next_endpoint() {
+    let broken = MockHttpServer::start(vec![MockResponse::Raw(500, "down".into())]).await;
+    let rpc = MockRpc::start().await;
+    let pool = RpcPool::new([
+        (broken.url("/"), RpcRole::Read),
+        (rpc.url(), RpcRole::ReadSend),
+    ])
+    .unwrap();
+
+    let blockhash = pool
+        .read(|client| async move { client.get_latest_blockhash().await })
+        .await
+        .unwrap();
+
+    assert_eq!(blockhash, Hash::new_from_array([7; 32]));
+    let stats = pool.stats();
+    assert_eq!((stats[0].2.failed, stats[0].2.succeeded), (1, 0));
+    assert_eq!((stats[1].2.failed, stats[1].2.succeeded), (0, 1));
+}
+
+#[tokio::test]
+async fn test_failing_endpoint_is_moved_to_the_back() {
+    let broken = MockHttpServer::start(vec![MockResponse::Raw(500, "down".into())]).await;
+    let rpc = MockRpc::start().await;
+    let pool = RpcPool::new([
+        (broken.url("/"), RpcRole::Read),
+        (rpc.url(), RpcRole::ReadSend),
+    ])
+    .unwrap()
+    .with_health_policy(1, Duration::from_secs(60));
+
+    for _ in 0..3 {
+        pool.read(|client| async move { client.get_balance(&Pubkey::default()).await })
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(
+        broken.requests().len(),
+        1,
+        "Отключённый узел не опрашивается первым"
+    );
+    assert!(pool.stats()[0].2.unhealthy_until.is_some());
+}
+
+#[tokio::test]
+async fn test_reads_prefer_lower_latency() {
+    let slow = MockHttpServer::start(vec![MockResponse::Delay(
+        Duration::from_millis(150),
+        Box::new(latest_blockhash_response()),
+    )])
+    .await;
+    let fast = MockHttpServer::start(vec![latest_blockhash_response()]).await;
+    let pool = RpcPool::new([
+        (slow.url("/"), RpcRole::Read),
+        (fast.url("/"), RpcRole::ReadSend),
+    ])
+    .unwrap();
+
+    for _ in 0..3 {
+        pool.read(|client| async move { client.get_latest_blockhash().await })
+            .await
+            .unwrap();
+    }
+
+    // Первый запрос измеряет медленный узел, второй — быстрый, дальше выбирается быстрый
+    assert_eq!(slow.requests().len(), 1);
+    assert_eq!(fast.requests().len(), 2);
+    let stats = pool.stats();
+    assert!(stats[0].2.latency.unwrap() > stats[1].2.latency.unwrap());
+}
+
+#[tokio::test]
+async fn test_broadcast_sends_to_every_send_endpoint_without_preflight() {
+    let reader = MockRpc::start().await;
+    let sender_a = MockRpc::start().await;
+    let sender_b = MockRpc::start().await;
+    let pool = RpcPool::new([
+        (reader.url(), RpcRole::Read),
+        (sender_a.url(), RpcRole::Send),
+        (sender_b.url(), RpcRole::ReadSend),
+    ])
+    .unwrap();
+    let tx = transfer_tx();
+
+    let results = pool.broadcast(&tx).await;
+
+    assert_eq!(results.len(), 2);
+    for (url, res) in &results {
+        assert_eq!(res.as_ref().unwrap(), &tx.signatures[0], "{}", url);
+    }
+    assert!(reader.sent_transactions().is_empty());
+    for sender in [&sender_a, &sender_b] {
+        assert_eq!(sender.sent_transactions(), vec![tx.clone()]);
+        let body = sender.server.requests()[0].json();
+        assert_eq!(body["method"], "sendTransaction");
+        assert_eq!(body["params"][1]["skipPreflight"], true);
+    }
+}
+
+#[tokio::test]
+async fn test_wait_for_confirmation_returns_status() {
+    let rpc = MockRpc::start().await;
+    let pool = RpcPool::new([(rpc.url(), RpcRole::ReadSend)]).unwrap();
+    let tx = transfer_tx();
+
+    let status = pool
+        .wait_for_confirmation(&tx.signatures[0], Duration::from_secs(1))
+        .await
+        .unwrap();
+
+    assert_eq!(status, Some(Ok(())));
+}
+
+fn signature_status_response() -> MockResponse {
+    MockResponse::Json(
+        200,
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 100 },
+                "value": [{
+                    "slot": 100,
+                    "confirmations": null,
+                    "err": null,
+                    "status": { "Ok": null },
+                    "confirmationStatus": "confirmed"
+                }]
+            }
+        }),
+    )
+}
+
+#[tokio::test]
+async fn test_wait_for_confirmation_survives_failed_poll() {
+    let server = MockHttpServer::start(vec![
+        MockResponse::Raw(500, "down".into()),
+        signature_status_response(),
+    ])
+    .await;
+    let pool = RpcPool::new([(server.url("/"), RpcRole::ReadSend)]).unwrap();
+    let tx = transfer_tx();
+
+    let status = pool
+        .wait_for_confirmation(&tx.signatures[0], Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    assert_eq!(status, Some(Ok(())));
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_wait_for_confirmation_fails_when_polls_fail_until_deadline() {
+    let server = MockHttpServer::start(vec![MockResponse::Raw(500, "down".into())]).await;
+    let pool = RpcPool::new([(server.url("/"), RpcRole::ReadSend)]).unwrap();
+    let tx = transfer_tx();
+
+    let result = pool
+        .wait_for_confirmation(&tx.signatures[0], Duration::from_millis(300))
+        .await;
+
+    assert!(result.is_err());
+    assert!(server.requests().len() > 1);
+}
+
+#[test]
+fn test_pool_requires_read_and_send_endpoints() {
+    assert!(RpcPool::new([("http://127.0.0.1:1", RpcRole::Read)]).is_err());
+    assert!(RpcPool::new([("http://127.0.0.1:1", RpcRole::Send)]).is_err());
+    assert!(RpcPool::new([
+        ("http://127.0.0.1:1", RpcRole::Read),
+        ("http://127.0.0.1:2", RpcRole::Send),
+    ])
+    .is_ok());
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::json;
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::UnixListener,
+};
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use meteora_sniper_bot::signer::{
+    sign_transaction, RemoteSigner, SignRequest, SignResponse, SignerEndpoint,
+};
+use meteora_sniper_bot::swap::{build_swap_transaction, PoolProgram, SwapAccounts};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+fn accounts() -> SwapAccounts {
+    SwapAccounts {
+        program: PoolProgram::Amm,
+        pool: Pubkey::new_unique(),
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::new_unique(),
+        pool_source: Pubkey::new_unique(),
+        pool_destination: Pubkey::new_unique(),
+        pool_authority: Pubkey::new_unique(),
+        token_program: Pubkey::new_unique(),
+    }
+}
+
+/// Подписывает запрос сервиса подписи ключом `key`
+fn sign_request(key: &Keypair, request: &SignRequest) -> SignResponse {
+    let message = general_purpose::STANDARD.decode(&request.message).unwrap();
+    SignResponse {
+        signature: Some(key.sign_message(&message).to_string()),
+        error: None,
+    }
+}
+
+/// Сервис подписи по HTTP, подписывающий ключом `key`
+async fn http_daemon(key: Keypair) -> MockHttpServer {
+    MockHttpServer::start_with_handler(move |request| {
+        let request: SignRequest = serde_json::from_slice(&request.body).unwrap();
+        MockResponse::Json(200, json!(sign_request(&key, &request)))
+    })
+    .await
+}
+
+fn remote(url: String, pubkey: Pubkey) -> RemoteSigner {
+    RemoteSigner::new(url.parse().unwrap(), pubkey, TIMEOUT)
+}
+
+#[tokio::test]
+async fn test_remote_signature_matches_in_process_signing() {
+    let key = Keypair::new();
+    let pubkey = key.pubkey();
+    let local = key.insecure_clone();
+    let daemon = http_daemon(key).await;
+    let signer = remote(daemon.url("/sign"), pubkey);
+    let (accounts, blockhash) = (accounts(), Hash::new_unique());
+
+    let remote_tx = build_swap_transaction(&signer, &accounts, 1_000, 1, 10, blockhash)
+        .await
+        .unwrap();
+    let local_tx = build_swap_transaction(&local, &accounts, 1_000, 1, 10, blockhash)
+        .await
+        .unwrap();
+
+    assert_eq!(remote_tx, local_tx);
+    assert!(remote_tx.verify_with_results().iter().all(|ok| *ok));
+
+    let requests = daemon.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/sign");
+    assert_eq!(requests[0].json()["pubkey"], pubkey.to_string());
+}
+
+#[tokio::test]
+async fn test_signature_from_wrong_key_is_rejected() {
+    let daemon = http_daemon(Keypair::new()).await;
+    let signer = remote(daemon.url("/"), Pubkey::new_unique());
+
+    let err = build_swap_transaction(&signer, &accounts(), 1_000, 1, 10, Hash::new_unique())
+        .await
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains("подпись не от"), "{:#}", err);
+}
+
+#[tokio::test]
+async fn test_daemon_refusal_is_reported() {
+    let daemon = MockHttpServer::start(vec![MockResponse::Json(
+        403,
+        json!({ "error": "ключ заблокирован" }),
+    )])
+    .await;
+    let signer = remote(daemon.url("/"), Pubkey::new_unique());
+
+    let err = build_swap_transaction(&signer, &accounts(), 1_000, 1, 10, Hash::new_unique())
+        .await
+        .unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("ключ заблокирован"),
+        "{:#}",
+        err
+    );
+}
+
+#[tokio::test]
+async fn test_unix_socket_daemon_signs_with_in_process_cosigner() {
+    let path = std::env::temp_dir().join(format!("sniper_signer_{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let payer = Keypair::new();
+    let payer_pubkey = payer.pubkey();
+    let payer = Arc::new(payer);
+    let daemon_key = payer.clone();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let (read, mut write) = stream.into_split();
+            let mut line = String::new();
+            BufReader::new(read).read_line(&mut line).await.unwrap();
+            let request: SignRequest = serde_json::from_str(line.trim()).unwrap();
+            let mut response = serde_json::to_vec(&sign_request(&daemon_key, &request)).unwrap();
+            response.push(b'\n');
+            write.write_all(&response).await.unwrap();
+        }
+    });
+
+    let endpoint: SignerEndpoint = format!("unix:{}", path.display()).parse().unwrap();
+    assert_eq!(endpoint, SignerEndpoint::Unix(PathBuf::from(&path)));
+    let signer = RemoteSigner::new(endpoint, payer_pubkey, TIMEOUT);
+
+    // Второй подписант — ключ в процессе, как у временного WSOL-аккаунта
+    let cosigner = Keypair::new();
+    let ix = system_instruction::create_account(
+        &payer_pubkey,
+        &cosigner.pubkey(),
+        1,
+        0,
+        &Pubkey::new_unique(),
+    );
+    let tx = Transaction::new_with_payer(&[ix], Some(&payer_pubkey));
+
+    let signed = sign_transaction(tx, Hash::new_unique(), &[&cosigner, &signer])
+        .await
+        .unwrap();
+    assert!(signed.verify_with_results().iter().all(|ok| *ok));
+
+    // Ключ, который не подписывает транзакцию, отклоняется до запросов к сервису
+    let stranger = Keypair::new();
+    let tx = Transaction::new_with_payer(&[], Some(&payer_pubkey));
+    assert!(
+        sign_transaction(tx.clone(), Hash::new_unique(), &[&stranger])
+            .await
+            .is_err()
+    );
+    // Без подписи плательщика транзакция не собирается
+    assert!(sign_transaction(tx, Hash::new_unique(), &[]).await.is_err());
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_endpoint_parsing() {
+    assert!(matches!(
+        "http://127.0.0.1:9000/sign".parse::<SignerEndpoint>(),
+        Ok(SignerEndpoint::Http(_))
+    ));
+    assert!("ftp://host".parse::<SignerEndpoint>().is_err());
+    assert!("not a url".parse::<SignerEndpoint>().is_err());
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+
+use meteora_sniper_bot::config::{METEORA_PROGRAM_ID, WSOL_MINT};
+use meteora_sniper_bot::filters::FilterLists;
+use meteora_sniper_bot::geyser::{decide, detect_pool_inits, Decision, SkipReason};
+use meteora_sniper_bot::sizing::{BuySizing, LiquidityRejection};
+use meteora_sniper_bot::store::Store;
+
+const SOL: u64 = 1_000_000_000;
+
+fn sizing() -> BuySizing {
+    BuySizing {
+        min_liquidity: SOL,
+        max_liquidity: 100 * SOL,
+        liquidity_bps: Some(100),
+        max_amount: SOL / 10,
+    }
+}
+
+#[test]
+fn test_amount_is_share_of_liquidity_capped_by_max() {
+    let sizing = sizing();
+
+    // 1% от 5 SOL
+    assert_eq!(sizing.amount_for(Some(5 * SOL)), Ok(SOL / 20));
+    // 1% от 50 SOL больше потолка
+    assert_eq!(sizing.amount_for(Some(50 * SOL)), Ok(SOL / 10));
+    assert_eq!(sizing.amount_for(Some(100 * SOL)), Ok(SOL / 10));
+
+    assert_eq!(
+        sizing.amount_for(Some(SOL / 2)),
+        Err(LiquidityRejection {
+            liquidity: Some(SOL / 2),
+            min: SOL,
+            max: 100 * SOL
+        })
+    );
+    assert!(sizing.amount_for(Some(101 * SOL)).is_err());
+    assert!(sizing.amount_for(None).is_err());
+}
+
+#[test]
+fn test_fixed_sizing_buys_any_pool() {
+    let fixed = BuySizing::fixed(SOL / 10);
+
+    assert!(!fixed.is_bounded());
+    assert_eq!(fixed.amount_for(None), Ok(SOL / 10));
+    assert_eq!(fixed.amount_for(Some(1)), Ok(SOL / 10));
+
+    let tiny_share = BuySizing {
+        liquidity_bps: Some(1),
+        ..fixed
+    };
+    assert_eq!(tiny_share.amount_for(Some(10)), Ok(1));
+}
+
+#[test]
+fn test_liquidity_is_read_from_init_arguments() {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let store = Store::open_in_memory(16).unwrap();
+
+    // В фикстуре WSOL — token_a
+    let (update, pool) = common::wsol_pool_update(7);
+    let update = common::with_init_amounts(update, 5 * SOL, 1_000_000);
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+    assert_eq!(init.initial_amounts, Some((5 * SOL, 1_000_000)));
+
+    let Decision::Buy(launch) =
+        decide(&init, &wsol, &FilterLists::default(), &sizing(), &store).unwrap()
+    else {
+        panic!("Пул в диапазоне должен покупаться");
+    };
+    assert_eq!(launch.accounts.pool, pool.pool);
+    assert_eq!(launch.wsol_liquidity, Some(5 * SOL));
+    assert_eq!(launch.amount_in, SOL / 20);
+}
+
+#[test]
+fn test_liquidity_falls_back_to_pool_reserves() {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let store = Store::open_in_memory(16).unwrap();
+
+    let (update, pool) = common::wsol_pool_update(8);
+    let update = common::with_pool_reserves(update, &pool, SOL / 2, 400);
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+    assert_eq!(init.initial_amounts, Some((SOL / 2, 400)));
+
+    assert_eq!(
+        decide(&init, &wsol, &FilterLists::default(), &sizing(), &store).unwrap(),
+        Decision::Skip {
+            pool: pool.pool,
+            reason: SkipReason::Liquidity(LiquidityRejection {
+                liquidity: Some(SOL / 2),
+                min: SOL,
+                max: 100 * SOL
+            })
+        }
+    );
+    // Отклонённый по ликвидности пул не помечается увиденным
+    assert!(!store.is_pool_seen(&pool.pool).unwrap());
+}
+
+#[test]
+fn test_liquidity_is_read_from_raw_init_instruction() {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+
+    let (update, _) = common::wsol_pool_update(9);
+    let update = common::with_init_data(update, common::INIT_DATA_5_SOL_1M_TOKENS.to_vec());
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+    assert_eq!(init.initial_amounts, Some((5 * SOL, 1_000_000)));
+
+    // Данные неожиданной длины не разбираются — берутся резервы пула
+    let (update, pool) = common::wsol_pool_update(10);
+    let mut data = common::INIT_DATA_5_SOL_1M_TOKENS.to_vec();
+    data.push(0);
+    let update = common::with_init_data(update, data);
+    let update = common::with_pool_reserves(update, &pool, 2 * SOL, 300);
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+    assert_eq!(init.initial_amounts, Some((2 * SOL, 300)));
+}
+
+#[test]
+fn test_overflowing_reserves_are_unknown_liquidity() {
+    let meteora = Pubkey::from_str(METEORA_PROGRAM_ID).unwrap();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+    let store = Store::open_in_memory(16).unwrap();
+
+    let (update, pool) = common::wsol_pool_update(11);
+    let update = common::with_pool_reserves(update, &pool, u64::MAX, 400);
+    let update = common::with_extra_pool_balance(update, &pool, wsol, 1);
+    let init = detect_pool_inits(&update, &meteora).remove(0);
+    assert_eq!(init.initial_amounts, None);
+
+    assert_eq!(
+        decide(&init, &wsol, &FilterLists::default(), &sizing(), &store).unwrap(),
+        Decision::Skip {
+            pool: pool.pool,
+            reason: SkipReason::Liquidity(LiquidityRejection {
+                liquidity: None,
+                min: SOL,
+                max: 100 * SOL
+            })
+        }
+    );
+}
@@ -0,0 +1,234 @@
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use meteora_sniper_bot::store::{DeployerHistory, Store};
+use meteora_sniper_bot::swap::{PoolProgram, SwapAccounts};
+
+#[test]
+fn test_pool_is_bought_once_across_restart() {
+    let path = std::env::temp_dir().join(format!("sniper_store_{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let pool = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let sig = Signature::new_unique();
+
+    {
+        let store = Store::open(&path, 16).unwrap();
+        assert!(store.mark_pool_seen(&pool, &mint).unwrap());
+        assert!(!store.mark_pool_seen(&pool, &mint).unwrap());
+        store.record_submission(&sig, &pool, 1_000_000).unwrap();
+    }
+
+    let store = Store::open(&path, 16).unwrap();
+    let state = store.recover().unwrap();
+
+    assert_eq!(state.seen_pools, 1);
+    assert_eq!(state.pending.len(), 1);
+    assert_eq!(state.pending[0].signature, sig);
+    assert!(
+        !store.mark_pool_seen(&pool, &mint).unwrap(),
+        "Пул не должен покупаться повторно после перезапуска"
+    );
+
+    store.record_confirmation(&sig, None).unwrap();
+    store
+        .record_fill(&sig, &pool, &mint, 1_000_000, 42)
+        .unwrap();
+    assert!(store.recover().unwrap().pending.is_empty());
+    assert_eq!(store.fills().unwrap()[0].amount_out, 42);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_seen_cache_is_bounded_but_falls_back_to_db() {
+    let store = Store::open_in_memory(2).unwrap();
+    let mint = Pubkey::new_unique();
+    let pools: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+    for pool in &pools {
+        assert!(store.mark_pool_seen(pool, &mint).unwrap());
+    }
+
+    // Вытесненный из кэша пул всё равно находится в базе
+    assert!(store.is_pool_seen(&pools[0]).unwrap());
+    assert!(!store.mark_pool_seen(&pools[0], &mint).unwrap());
+    assert!(!store.is_pool_seen(&Pubkey::new_unique()).unwrap());
+}
+
+#[test]
+fn test_unmarked_pool_can_be_seen_again() {
+    let store = Store::open_in_memory(2).unwrap();
+    let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert!(store.mark_pool_seen(&pool, &mint).unwrap());
+    store.unmark_pool_seen(&pool).unwrap();
+    assert!(!store.is_pool_seen(&pool).unwrap());
+    assert!(store.mark_pool_seen(&pool, &mint).unwrap());
+    assert!(!store.mark_pool_seen(&pool, &mint).unwrap());
+}
+
+#[test]
+fn test_positions_net_sells_against_fills() {
+    let store = Store::open_in_memory(16).unwrap();
+    let mint = Pubkey::new_unique();
+    let accounts = SwapAccounts {
+        program: PoolProgram::Amm,
+        pool: Pubkey::new_unique(),
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::new_unique(),
+        pool_source: Pubkey::new_unique(),
+        pool_destination: Pubkey::new_unique(),
+        pool_authority: Pubkey::new_unique(),
+        token_program: Pubkey::new_unique(),
+    };
+
+    assert!(store.pool_accounts_for_mint(&mint).unwrap().is_none());
+    store.record_pool_accounts(&accounts, &mint).unwrap();
+    assert_eq!(store.pool_accounts_for_mint(&mint).unwrap(), Some(accounts));
+
+    store
+        .record_fill(&Signature::new_unique(), &accounts.pool, &mint, 1_000, 500)
+        .unwrap();
+    store
+        .record_fill(&Signature::new_unique(), &accounts.pool, &mint, 1_000, 300)
+        .unwrap();
+    store
+        .record_sell(&Signature::new_unique(), &accounts.pool, &mint, 600, 1_500)
+        .unwrap();
+
+    let positions = store.positions().unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].mint, mint);
+    assert_eq!(positions[0].sol_spent, 2_000);
+    assert_eq!(positions[0].sol_received, 1_500);
+    assert_eq!(positions[0].tokens_held(), 200);
+}
+
+#[test]
+fn test_position_follows_pool_of_latest_fill() {
+    let store = Store::open_in_memory(16).unwrap();
+    let mint = Pubkey::new_unique();
+    // Кривая DBC, затем пул DAMM v2 после миграции; адрес пула после миграции меньше
+    let curve = Pubkey::new_from_array([0xff; 32]);
+    let migrated = Pubkey::new_from_array([0x01; 32]);
+    let accounts = |pool, program| SwapAccounts {
+        program,
+        pool,
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::default(),
+        pool_source: Pubkey::new_unique(),
+        pool_destination: Pubkey::new_unique(),
+        pool_authority: Pubkey::new_unique(),
+        token_program: spl_token::id(),
+    };
+
+    for (pool, program) in [(curve, PoolProgram::Dbc), (migrated, PoolProgram::DammV2)] {
+        store
+            .record_pool_accounts(&accounts(pool, program), &mint)
+            .unwrap();
+        store
+            .record_fill(&Signature::new_unique(), &pool, &mint, 1_000, 100)
+            .unwrap();
+    }
+    // Покупка на кривой, записанная позже, но не исполненная, маршрут не меняет
+    store
+        .record_pool_accounts(&accounts(curve, PoolProgram::Dbc), &mint)
+        .unwrap();
+
+    let positions = store.positions().unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].pool, migrated);
+    assert_eq!(positions[0].tokens_bought, 200);
+
+    let stored = store.pool_accounts_for_mint(&mint).unwrap().unwrap();
+    assert_eq!(stored.pool, migrated);
+    assert_eq!(stored.program, PoolProgram::DammV2);
+}
+
+#[test]
+fn test_amounts_above_i64_are_rejected() {
+    let store = Store::open_in_memory(16).unwrap();
+    let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let max = i64::MAX as u64;
+    let too_large = max + 1;
+    assert!(store
+        .record_submission(&Signature::new_unique(), &pool, too_large)
+        .is_err());
+    assert!(store
+        .record_fill(&Signature::new_unique(), &pool, &mint, 1_000, too_large)
+        .is_err());
+    assert!(store
+        .record_sell(&Signature::new_unique(), &pool, &mint, too_large, 1)
+        .is_err());
+    assert!(store.positions().unwrap().is_empty());
+
+    store
+        .record_fill(&Signature::new_unique(), &pool, &mint, 1_000, max)
+        .unwrap();
+    assert_eq!(store.positions().unwrap()[0].tokens_bought, max);
+}
+
+#[test]
+fn test_realized_pnl_uses_average_buy_price() {
+    let store = Store::open_in_memory(16).unwrap();
+    let (pool, winner, loser) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    // Средняя цена: 2 лампорта за токен
+    store
+        .record_fill(&Signature::new_unique(), &pool, &winner, 1_000, 500)
+        .unwrap();
+    store
+        .record_sell(&Signature::new_unique(), &pool, &winner, 250, 800)
+        .unwrap();
+    // 4 лампорта за токен, продано дешевле
+    store
+        .record_fill(&Signature::new_unique(), &pool, &loser, 4_000, 1_000)
+        .unwrap();
+    store
+        .record_sell(&Signature::new_unique(), &pool, &loser, 1_000, 1_000)
+        .unwrap();
+
+    assert_eq!(
+        store.realized_pnl_since(0).unwrap(),
+        (800 - 500) + (1_000 - 4_000)
+    );
+    assert_eq!(store.realized_pnl_since(i64::MAX).unwrap(), 0);
+
+    let positions = store.positions().unwrap();
+    let winner_position = positions.iter().find(|p| p.mint == winner).unwrap();
+    assert_eq!(winner_position.held_cost(), 500);
+    let loser_position = positions.iter().find(|p| p.mint == loser).unwrap();
+    assert_eq!(loser_position.held_cost(), 0);
+}
+
+#[test]
+fn test_deployer_history_expires_and_links_funders() {
+    let store = Store::open_in_memory(16).unwrap();
+    let (creator, other, funder) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let history = DeployerHistory {
+        creator,
+        launches: 3,
+        rugs: 2,
+        fastest_pull_secs: Some(45),
+        funder: Some(funder),
+    };
+    store.record_deployer(&history).unwrap();
+
+    assert_eq!(store.deployer(&creator, 60).unwrap(), Some(history));
+    assert_eq!(store.deployer(&creator, -1).unwrap(), None);
+    assert_eq!(store.deployer(&other, 60).unwrap(), None);
+
+    // Собственные rug-и создателя не считаются rug-ами связанных кошельков
+    assert_eq!(store.funded_rugs(&funder, &creator).unwrap(), 0);
+    assert_eq!(store.funded_rugs(&funder, &other).unwrap(), 2);
+}
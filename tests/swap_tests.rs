@@ -3,9 +3,8 @@ use std::sync::Arc;
 
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{pubkey::Pubkey, signature::Keypair};
-use tokio;
 
-use meteora_sniper_bot::swap::execute_swap;
+use meteora_sniper_bot::swap::{execute_swap, PoolProgram, SwapAccounts};
 
 #[tokio::test]
 async fn test_execute_swap_simulation() {
@@ -35,3 +34,25 @@ async fn test_execute_swap_simulation() {
         "Ожидалась ошибка при попытке swap с фиктивными данными"
     );
 }
+
+#[test]
+fn test_reversed_accounts_swap_direction() {
+    let accounts = SwapAccounts {
+        program: PoolProgram::Amm,
+        pool: Pubkey::new_unique(),
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::new_unique(),
+        pool_source: Pubkey::new_unique(),
+        pool_destination: Pubkey::new_unique(),
+        pool_authority: Pubkey::new_unique(),
+        token_program: Pubkey::new_unique(),
+    };
+
+    let reversed = accounts.reversed();
+    assert_eq!(reversed.user_source, accounts.user_destination);
+    assert_eq!(reversed.user_destination, accounts.user_source);
+    assert_eq!(reversed.pool_source, accounts.pool_destination);
+    assert_eq!(reversed.pool_destination, accounts.pool_source);
+    assert_eq!(reversed.pool, accounts.pool);
+    assert_eq!(reversed.reversed(), accounts);
+}
@@ -0,0 +1,185 @@
+mod common;
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::{json, Value};
+use solana_sdk::{
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, VersionedTransaction},
+};
+
+use common::mock_http::{MockHttpServer, MockResponse};
+use common::mock_tpu::MockTpuServer;
+use meteora_sniper_bot::relay::{Relay, RelayError};
+use meteora_sniper_bot::rpc::{RpcPool, RpcRole};
+use meteora_sniper_bot::tpu::{leaders::LeaderTracker, TpuRelay};
+
+/// Расписание, в котором каждый лидер ведёт 4 слота подряд, начиная со слота `start`
+fn schedule(tracker: &LeaderTracker, start: u64, leaders: &[Pubkey]) {
+    let slots = leaders.iter().flat_map(|l| [*l; 4]).collect();
+    tracker.observe_slot(start);
+    tracker.set_leader_schedule(start, slots);
+}
+
+fn signed_tx() -> VersionedTransaction {
+    let payer = Keypair::new();
+    let ix = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    VersionedTransaction::from(tx)
+}
+
+fn encode(tx: &VersionedTransaction) -> (Vec<u8>, String) {
+    let wire = bincode::serialize(tx).unwrap();
+    let encoded = general_purpose::STANDARD.encode(&wire);
+    (wire, encoded)
+}
+
+#[test]
+fn test_upcoming_leaders_are_distinct_and_start_at_current_slot() {
+    let (a, b, c) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let tracker = LeaderTracker::new();
+    schedule(&tracker, 100, &[a, b, a, c]);
+
+    assert_eq!(tracker.upcoming_leaders(2), vec![a, b]);
+    assert_eq!(tracker.upcoming_leaders(5), vec![a, b, c]);
+
+    // Слоты лидера `a` уже прошли
+    tracker.observe_slot(104);
+    assert_eq!(tracker.upcoming_leaders(2), vec![b, a]);
+    assert_eq!(tracker.leader_at(112), Some(c));
+    assert_eq!(tracker.leader_at(116), None);
+
+    // Лидеры без адреса TPU пропускаются
+    let addr: SocketAddr = "127.0.0.1:8009".parse().unwrap();
+    tracker.set_contacts([(a, addr)]);
+    assert_eq!(tracker.upcoming_tpu_addresses(3), vec![(a, addr)]);
+}
+
+#[test]
+fn test_current_slot_advances_with_time() {
+    let tracker = LeaderTracker::new();
+    tracker.observe_slot(1_000);
+    tracker.observe_slot(900);
+    assert_eq!(tracker.current_slot(), 1_000);
+
+    std::thread::sleep(Duration::from_millis(850));
+    assert_eq!(tracker.current_slot(), 1_002);
+}
+
+#[tokio::test]
+async fn test_transaction_is_sent_to_upcoming_leaders_over_quic() {
+    let servers = [
+        MockTpuServer::start().await,
+        MockTpuServer::start().await,
+        MockTpuServer::start().await,
+    ];
+    let leaders: Vec<Pubkey> = servers.iter().map(|_| Pubkey::new_unique()).collect();
+    let tracker = Arc::new(LeaderTracker::new());
+    schedule(&tracker, 50, &leaders);
+    tracker.set_contacts(
+        leaders
+            .iter()
+            .copied()
+            .zip(servers.iter().map(|s| s.addr())),
+    );
+
+    let relay = TpuRelay::new(tracker, &Keypair::new(), 2).unwrap();
+    let tx = signed_tx();
+    let (wire, encoded) = encode(&tx);
+
+    let ack = relay.send(&encoded, 0).await.unwrap();
+
+    assert_eq!(ack.signature, Some(tx.signatures[0].to_string()));
+    for server in &servers[..2] {
+        let packets = server.wait_for_packets(1, Duration::from_secs(2)).await;
+        assert_eq!(packets, vec![wire.clone()]);
+    }
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(servers[2].packets().is_empty(), "Третий лидер вне fanout");
+}
+
+#[tokio::test]
+async fn test_connection_is_reused_between_sends() {
+    let server = MockTpuServer::start().await;
+    let leader = Pubkey::new_unique();
+    let tracker = Arc::new(LeaderTracker::new());
+    schedule(&tracker, 10, &[leader]);
+    tracker.set_contacts([(leader, server.addr())]);
+    let relay = TpuRelay::new(tracker, &Keypair::new(), 1).unwrap();
+
+    relay.warm_up().await.unwrap();
+    for _ in 0..2 {
+        relay.send(&encode(&signed_tx()).1, 0).await.unwrap();
+    }
+
+    assert_eq!(
+        server
+            .wait_for_packets(2, Duration::from_secs(2))
+            .await
+            .len(),
+        2
+    );
+    assert_eq!(server.connections(), 1);
+}
+
+#[tokio::test]
+async fn test_send_without_known_leaders_fails() {
+    let relay = TpuRelay::new(Arc::new(LeaderTracker::new()), &Keypair::new(), 2).unwrap();
+
+    let err = relay.send(&encode(&signed_tx()).1, 0).await.unwrap_err();
+    assert!(matches!(err, RelayError::Transport { .. }), "{:?}", err);
+
+    let err = relay.send("не base64", 0).await.unwrap_err();
+    assert!(matches!(err, RelayError::Rejected { .. }), "{:?}", err);
+}
+
+#[tokio::test]
+async fn test_refresh_loads_schedule_and_contacts_from_rpc() {
+    let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let rpc = MockHttpServer::start_with_handler(move |req| {
+        let body = req.json();
+        let result = match body["method"].as_str().unwrap_or_default() {
+            "getSlot" => json!(500),
+            "getSlotLeaders" => {
+                assert_eq!(body["params"], json!([500, 8]));
+                json!([a.to_string(), a.to_string(), b.to_string(), b.to_string()])
+            }
+            "getClusterNodes" => json!([
+                { "pubkey": a.to_string(), "tpuQuic": "10.0.0.1:8009" },
+                { "pubkey": b.to_string(), "tpuQuic": null },
+            ]),
+            _ => Value::Null,
+        };
+        MockResponse::Json(
+            200,
+            json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }),
+        )
+    })
+    .await;
+    let pool = RpcPool::new([(rpc.url("/"), RpcRole::ReadSend)]).unwrap();
+    let tracker = LeaderTracker::new();
+
+    tracker.refresh_schedule(&pool, 8).await.unwrap();
+    assert_eq!(tracker.refresh_contacts(&pool).await.unwrap(), 1);
+
+    assert_eq!(tracker.leader_at(502), Some(b));
+    assert_eq!(tracker.upcoming_leaders(2), vec![a, b]);
+    assert_eq!(
+        tracker.tpu_address(&a),
+        Some("10.0.0.1:8009".parse().unwrap())
+    );
+    assert_eq!(tracker.tpu_address(&b), None);
+}
@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+};
+
+use meteora_sniper_bot::store::Store;
+use meteora_sniper_bot::swap::{associated_token_address, PoolProgram, SwapAccounts};
+use meteora_sniper_bot::wallets::{Wallet, WalletPolicy, WalletPool, WalletRejection};
+
+fn pool_of(wallets: Vec<Wallet>, policy: WalletPolicy) -> (WalletPool, Vec<Pubkey>) {
+    let pool = WalletPool::new(wallets, policy).unwrap();
+    let keys = pool.wallets().iter().map(|w| w.pubkey()).collect();
+    (pool, keys)
+}
+
+fn wallet(budget: u64) -> Wallet {
+    Wallet::new(Keypair::new()).with_budget(budget)
+}
+
+#[test]
+fn test_round_robin_skips_wallets_without_budget() {
+    let (pool, keys) = pool_of(
+        vec![wallet(1_000), wallet(1_000), wallet(1_000)],
+        WalletPolicy::RoundRobin,
+    );
+    let none = HashMap::new();
+
+    let picked: Vec<_> = (0..3)
+        .map(|_| {
+            pool.assign(Pubkey::new_unique(), 600, "snipe", &none)
+                .unwrap()
+                .pubkey()
+        })
+        .collect();
+    assert_eq!(picked, keys);
+
+    // У всех занято 600 из 1 000
+    assert_eq!(
+        pool.assign(Pubkey::new_unique(), 600, "snipe", &none)
+            .unwrap_err(),
+        WalletRejection::BudgetExhausted { amount: 600 }
+    );
+    // Позиции из хранилища тоже занимают бюджет
+    let stored = HashMap::from([(keys[0], 1_000)]);
+    let small = pool
+        .assign(Pubkey::new_unique(), 400, "snipe", &stored)
+        .unwrap();
+    assert_eq!(small.pubkey(), keys[1]);
+}
+
+#[test]
+fn test_release_frees_wallet_budget() {
+    let (pool, keys) = pool_of(vec![wallet(1_000)], WalletPolicy::RoundRobin);
+    let none = HashMap::new();
+    let first = Pubkey::new_unique();
+
+    pool.assign(first, 1_000, "snipe", &none).unwrap();
+    assert!(pool
+        .assign(Pubkey::new_unique(), 1, "snipe", &none)
+        .is_err());
+
+    pool.release(&first);
+    let wallet = pool
+        .assign(Pubkey::new_unique(), 1_000, "snipe", &none)
+        .unwrap();
+    assert_eq!(wallet.pubkey(), keys[0]);
+}
+
+#[test]
+fn test_least_exposed_picks_wallet_with_smallest_usage() {
+    let (pool, keys) = pool_of(
+        vec![wallet(10_000), wallet(10_000), wallet(10_000)],
+        WalletPolicy::LeastExposed,
+    );
+    let stored = HashMap::from([(keys[0], 500), (keys[1], 100), (keys[2], 300)]);
+
+    let first = pool
+        .assign(Pubkey::new_unique(), 300, "snipe", &stored)
+        .unwrap();
+    assert_eq!(first.pubkey(), keys[1]);
+    // Теперь у второго 400 вместе с покупкой в процессе
+    let second = pool
+        .assign(Pubkey::new_unique(), 300, "snipe", &stored)
+        .unwrap();
+    assert_eq!(second.pubkey(), keys[2]);
+}
+
+#[test]
+fn test_per_strategy_uses_dedicated_wallets_then_unlabelled() {
+    let (pool, keys) = pool_of(
+        vec![
+            wallet(1_000).with_strategy("snipe"),
+            wallet(1_000).with_strategy("migration"),
+            wallet(1_000),
+        ],
+        WalletPolicy::PerStrategy,
+    );
+    let none = HashMap::new();
+
+    for _ in 0..2 {
+        let picked = pool
+            .assign(Pubkey::new_unique(), 100, "snipe", &none)
+            .unwrap();
+        assert_eq!(picked.pubkey(), keys[0]);
+    }
+    let other = pool
+        .assign(Pubkey::new_unique(), 100, "copy", &none)
+        .unwrap();
+    assert_eq!(other.pubkey(), keys[2]);
+
+    let (dedicated_only, _) = pool_of(
+        vec![wallet(1_000).with_strategy("snipe")],
+        WalletPolicy::PerStrategy,
+    );
+    assert_eq!(
+        dedicated_only
+            .assign(Pubkey::new_unique(), 100, "copy", &none)
+            .unwrap_err(),
+        WalletRejection::NoWallet {
+            strategy: "copy".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_wallet_swaps_through_its_own_accounts() {
+    let wsol = Pubkey::new_unique();
+    let accounts = SwapAccounts {
+        program: PoolProgram::Amm,
+        pool: Pubkey::new_unique(),
+        user_source: Pubkey::new_unique(),
+        user_destination: Pubkey::new_unique(),
+        pool_source: Pubkey::new_unique(),
+        pool_destination: Pubkey::new_unique(),
+        pool_authority: Pubkey::new_unique(),
+        token_program: Pubkey::new_unique(),
+    };
+
+    let mint = Pubkey::new_unique();
+
+    let wallet = wallet(0).with_wsol_account(wsol);
+    let swapped = wallet.swap_accounts(&accounts, &mint).unwrap();
+    assert_eq!(swapped.user_source, wsol);
+    // Токен приходит на ATA кошелька, а не на аккаунт создателя пула
+    let ata = associated_token_address(&wallet.pubkey(), &mint, &accounts.token_program).unwrap();
+    assert_eq!(swapped.user_destination, ata);
+    assert_eq!(swapped.pool, accounts.pool);
+    let prepare = wallet.prepare_instructions(&swapped, &mint).unwrap();
+    assert_eq!(prepare.len(), 1);
+    assert_eq!(prepare[0].accounts[1].pubkey, ata);
+
+    // Адаптеры DLMM, DAMM v2 и DBC сами подставляют ATA вместо нулевого ключа
+    let dlmm = SwapAccounts {
+        program: PoolProgram::Dlmm,
+        ..accounts
+    };
+    let swapped = wallet.swap_accounts(&dlmm, &mint).unwrap();
+    assert_eq!(swapped.user_source, wsol);
+    assert_eq!(swapped.user_destination, Pubkey::default());
+    let prepare = wallet.prepare_instructions(&swapped, &mint).unwrap();
+    assert!(prepare.is_empty());
+}
+
+#[test]
+fn test_store_splits_open_cost_between_wallets() {
+    let store = Store::open_in_memory(16).unwrap();
+    let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    for (wallet, amount_in, amount_out) in [(first, 3_000, 300), (second, 1_000, 100)] {
+        let signature = Signature::new_unique();
+        store
+            .record_fill(&signature, &pool, &mint, amount_in, amount_out)
+            .unwrap();
+        store.record_buy_wallet(&signature, &wallet).unwrap();
+    }
+    // Покупка без кошелька (до появления кошельков) не распределяется
+    store
+        .record_fill(&Signature::new_unique(), &pool, &mint, 4_000, 400)
+        .unwrap();
+    // Продана половина: себестоимость остатка 4 000
+    store
+        .record_sell(&Signature::new_unique(), &pool, &mint, 400, 5_000)
+        .unwrap();
+
+    let exposure = store.wallet_exposure().unwrap();
+    assert_eq!(exposure.get(&first), Some(&1_500));
+    assert_eq!(exposure.get(&second), Some(&500));
+}
+
+#[test]
+fn test_sell_wallet_is_the_wallet_that_bought_the_pool() {
+    let store = Store::open_in_memory(16).unwrap();
+    let (pool, mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let buyer = Pubkey::new_unique();
+
+    // Покупка без кошелька (до появления кошельков)
+    store
+        .record_fill(&Signature::new_unique(), &pool, &mint, 1_000, 100)
+        .unwrap();
+    assert_eq!(store.pool_buy_wallet(&pool).unwrap(), None);
+
+    let signature = Signature::new_unique();
+    store
+        .record_fill(&signature, &pool, &mint, 1_000, 100)
+        .unwrap();
+    store.record_buy_wallet(&signature, &buyer).unwrap();
+    assert_eq!(store.pool_buy_wallet(&pool).unwrap(), Some(buyer));
+
+    // Неисполненная покупка другим кошельком не в счёт
+    let pending = Signature::new_unique();
+    store
+        .record_buy_wallet(&pending, &Pubkey::new_unique())
+        .unwrap();
+    assert_eq!(store.pool_buy_wallet(&pool).unwrap(), Some(buyer));
+    assert_eq!(store.pool_buy_wallet(&Pubkey::new_unique()).unwrap(), None);
+}
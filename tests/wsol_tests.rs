@@ -1,13 +1,154 @@
-use solana_sdk::signature::Keypair;
-use meteora_sniper_bot::wsol::ensure_wsol_account;
+mod common;
+
+use std::str::FromStr;
+
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction::SystemInstruction,
+    system_program,
+    transaction::VersionedTransaction,
+};
+
+use common::mock_rpc::MockRpc;
+use meteora_sniper_bot::config::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, WSOL_FEE_RESERVE_LAMPORTS, WSOL_MINT,
+};
+use meteora_sniper_bot::swap::associated_token_address;
+use meteora_sniper_bot::wsol::ensure_wsol_account;
+
+/// Баланс кошелька в `getBalance` мока
+const WALLET_LAMPORTS: u64 = 5 * LAMPORTS_PER_SOL;
+
+fn wsol_ata(owner: &Pubkey) -> Pubkey {
+    associated_token_address(
+        owner,
+        &Pubkey::from_str(WSOL_MINT).unwrap(),
+        &spl_token::id(),
+    )
+    .unwrap()
+}
+
+/// Переводы SOL в транзакции: (получатель, лампорты)
+fn transfers(tx: &VersionedTransaction) -> Vec<(Pubkey, u64)> {
+    let keys = tx.message.static_account_keys();
+    tx.message
+        .instructions()
+        .iter()
+        .filter(|ix| keys[ix.program_id_index as usize] == system_program::id())
+        .filter_map(|ix| match bincode::deserialize(&ix.data) {
+            Ok(SystemInstruction::Transfer { lamports }) => {
+                Some((keys[ix.accounts[1] as usize], lamports))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn invokes(tx: &VersionedTransaction, program: &str) -> bool {
+    let keys = tx.message.static_account_keys();
+    tx.message
+        .instructions()
+        .iter()
+        .any(|ix| keys[ix.program_id_index as usize].to_string() == program)
+}
 
 #[tokio::test]
 async fn test_ensure_wsol_account_invalid_rpc() {
     let rpc_client = RpcClient::new("https://invalid-rpc.test".to_string());
     let dummy_payer = Keypair::new();
 
-    let result = ensure_wsol_account(&rpc_client, &dummy_payer).await;
+    let result = ensure_wsol_account(&rpc_client, &dummy_payer, LAMPORTS_PER_SOL).await;
 
     assert!(result.is_err(), "Должна быть ошибка при невалидном RPC");
 }
+
+#[tokio::test]
+async fn test_missing_wsol_account_is_created_as_ata() {
+    let mock = MockRpc::start().await;
+    let rpc = RpcClient::new(mock.url());
+    let payer = Keypair::new();
+
+    let account = ensure_wsol_account(&rpc, &payer, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(account, wsol_ata(&payer.pubkey()));
+
+    let sent = mock.sent_transactions();
+    assert_eq!(sent.len(), 1);
+    assert!(invokes(&sent[0], ASSOCIATED_TOKEN_PROGRAM_ID));
+    assert_eq!(transfers(&sent[0]), vec![(account, LAMPORTS_PER_SOL)]);
+}
+
+#[tokio::test]
+async fn test_existing_wsol_account_is_reused_and_topped_up() {
+    let mock = MockRpc::start().await;
+    let rpc = RpcClient::new(mock.url());
+    let payer = Keypair::new();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+
+    // Прежний аккаунт не ATA: новый не создаётся, доливается недостающее
+    let existing = Pubkey::new_unique();
+    mock.set_token_account(payer.pubkey(), existing, wsol, LAMPORTS_PER_SOL / 4);
+
+    let account = ensure_wsol_account(&rpc, &payer, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(account, existing);
+
+    let sent = mock.sent_transactions();
+    assert_eq!(sent.len(), 1);
+    assert!(!invokes(&sent[0], ASSOCIATED_TOKEN_PROGRAM_ID));
+    assert_eq!(
+        transfers(&sent[0]),
+        vec![(existing, LAMPORTS_PER_SOL * 3 / 4)]
+    );
+}
+
+#[tokio::test]
+async fn test_funded_wsol_account_needs_no_transaction() {
+    let mock = MockRpc::start().await;
+    let rpc = RpcClient::new(mock.url());
+    let payer = Keypair::new();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+
+    // ATA выбирается раньше остальных WSOL-аккаунтов, даже с меньшим балансом
+    let ata = wsol_ata(&payer.pubkey());
+    mock.set_token_account(
+        payer.pubkey(),
+        Pubkey::new_unique(),
+        wsol,
+        3 * LAMPORTS_PER_SOL,
+    );
+    mock.set_token_account(payer.pubkey(), ata, wsol, LAMPORTS_PER_SOL);
+
+    let account = ensure_wsol_account(&rpc, &payer, LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(account, ata);
+    assert!(mock.sent_transactions().is_empty());
+}
+
+#[tokio::test]
+async fn test_top_up_leaves_fee_reserve_on_wallet() {
+    let mock = MockRpc::start().await;
+    let rpc = RpcClient::new(mock.url());
+    let payer = Keypair::new();
+    let wsol = Pubkey::from_str(WSOL_MINT).unwrap();
+
+    let existing = Pubkey::new_unique();
+    mock.set_token_account(payer.pubkey(), existing, wsol, 0);
+
+    // Бюджет больше, чем SOL на кошельке
+    ensure_wsol_account(&rpc, &payer, 2 * WALLET_LAMPORTS)
+        .await
+        .unwrap();
+
+    let sent = mock.sent_transactions();
+    assert_eq!(
+        transfers(&sent[0]),
+        vec![(existing, WALLET_LAMPORTS - WSOL_FEE_RESERVE_LAMPORTS)]
+    );
+}